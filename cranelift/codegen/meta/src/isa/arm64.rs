@@ -10,6 +10,20 @@ pub(crate) fn define() -> TargetIsa {
         "",
         false,
     );
+    settings.add_bool(
+        "has_fp16",
+        "Has half-precision floating-point (FEAT_FP16) support; does not have \
+         an effect on code generation by itself.",
+        "",
+        false,
+    );
+    settings.add_bool(
+        "has_dotprod",
+        "Has dot product (FEAT_DotProd) support; does not have an effect on \
+         code generation by itself.",
+        "",
+        false,
+    );
     settings.add_bool(
         "has_pauth",
         "Has Pointer authentication (FEAT_PAuth) support; enables the use of \
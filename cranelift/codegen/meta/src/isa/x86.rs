@@ -77,6 +77,18 @@ pub(crate) fn define() -> TargetIsa {
         "AVX512F: CPUID.07H:EBX.AVX512F[bit 16]",
         false,
     );
+    let has_avx512bw = settings.add_bool(
+        "has_avx512bw",
+        "Has support for AVX512BW.",
+        "AVX512BW: CPUID.07H:EBX.AVX512BW[bit 30]",
+        false,
+    );
+    let has_avx512cd = settings.add_bool(
+        "has_avx512cd",
+        "Has support for AVX512CD.",
+        "AVX512CD: CPUID.07H:EBX.AVX512CD[bit 28]",
+        false,
+    );
     let has_popcnt = settings.add_bool(
         "has_popcnt",
         "Has support for POPCNT.",
@@ -118,9 +130,12 @@ pub(crate) fn define() -> TargetIsa {
     settings.add_predicate("use_avx512vl", predicate!(has_avx512vl));
     settings.add_predicate("use_avx512vbmi", predicate!(has_avx512vbmi));
     settings.add_predicate("use_avx512f", predicate!(has_avx512f));
+    settings.add_predicate("use_avx512bw", predicate!(has_avx512bw));
+    settings.add_predicate("use_avx512cd", predicate!(has_avx512cd));
 
     settings.add_predicate("use_popcnt", predicate!(has_popcnt && has_sse42));
     settings.add_predicate("use_bmi1", predicate!(has_bmi1));
+    settings.add_predicate("use_bmi2", predicate!(has_bmi2));
     settings.add_predicate("use_lzcnt", predicate!(has_lzcnt));
 
     let sse3 = settings.add_preset("sse3", "SSE3 and earlier.", preset!(has_sse3));
@@ -228,7 +243,15 @@ pub(crate) fn define() -> TargetIsa {
     let knights_landing = settings.add_preset(
         "knl",
         "Knights Landing microarchitecture.",
-        preset!(has_popcnt && has_avx512f && has_fma && has_bmi1 && has_bmi2 && has_lzcnt),
+        preset!(
+            has_popcnt
+                && has_avx512f
+                && has_avx512cd
+                && has_fma
+                && has_bmi1
+                && has_bmi2
+                && has_lzcnt
+        ),
     );
     settings.add_preset(
         "knm",
@@ -238,7 +261,14 @@ pub(crate) fn define() -> TargetIsa {
     let skylake_avx512 = settings.add_preset(
         "skylake-avx512",
         "Skylake AVX512 microarchitecture.",
-        preset!(broadwell && has_avx512f && has_avx512dq && has_avx512vl),
+        preset!(
+            broadwell
+                && has_avx512f
+                && has_avx512bw
+                && has_avx512cd
+                && has_avx512dq
+                && has_avx512vl
+        ),
     );
     settings.add_preset(
         "skx",
@@ -258,7 +288,15 @@ pub(crate) fn define() -> TargetIsa {
     let cannonlake = settings.add_preset(
         "cannonlake",
         "Canon Lake microarchitecture.",
-        preset!(skylake && has_avx512f && has_avx512dq && has_avx512vl && has_avx512vbmi),
+        preset!(
+            skylake
+                && has_avx512f
+                && has_avx512bw
+                && has_avx512cd
+                && has_avx512dq
+                && has_avx512vl
+                && has_avx512vbmi
+        ),
     );
     let icelake_client = settings.add_preset(
         "icelake-client",
@@ -394,7 +432,14 @@ pub(crate) fn define() -> TargetIsa {
     settings.add_preset(
         "x86_64_v4",
         "Generic x86_64 (V4) microarchitecture.",
-        preset!(x86_64_v3 && has_avx512dq && has_avx512vl),
+        preset!(
+            x86_64_v3
+                && has_avx512f
+                && has_avx512bw
+                && has_avx512cd
+                && has_avx512dq
+                && has_avx512vl
+        ),
     );
 
     TargetIsa::new("x86", settings.build())
@@ -3818,6 +3818,27 @@ pub(crate) fn define(
         .other_side_effects(),
     );
 
+    ig.push(
+        Inst::new(
+            "prefetch",
+            r#"
+        Hint that the memory at `p` will be accessed soon.
+
+        `locality` ranges from 0 (no temporal locality, the data should not be kept in the
+        cache hierarchy) to 3 (high temporal locality, keep the data in all levels of cache);
+        values greater than 3 are treated as 3. This instruction has no semantic effect: it
+        never traps, even when `p` is not a valid address, and backends are free to emit
+        nothing for it.
+        "#,
+            &formats.binary_imm8,
+        )
+        .operands_in(vec![
+            Operand::new("p", iAddr),
+            Operand::new("locality", &imm.uimm8).with_doc("Expected temporal locality"),
+        ])
+        .other_side_effects(),
+    );
+
     let TxN = &TypeVar::new(
         "TxN",
         "A dynamic vector type",
@@ -51,6 +51,14 @@ pub enum LibCall {
     Memmove,
     /// libc.memcmp
     Memcmp,
+    /// udiv.i128
+    UdivI128,
+    /// sdiv.i128
+    SdivI128,
+    /// urem.i128
+    UremI128,
+    /// srem.i128
+    SremI128,
 
     /// Elf __tls_get_addr
     ElfTlsGetAddr,
@@ -88,6 +96,10 @@ impl FromStr for LibCall {
             "Memset" => Ok(Self::Memset),
             "Memmove" => Ok(Self::Memmove),
             "Memcmp" => Ok(Self::Memcmp),
+            "UdivI128" => Ok(Self::UdivI128),
+            "SdivI128" => Ok(Self::SdivI128),
+            "UremI128" => Ok(Self::UremI128),
+            "SremI128" => Ok(Self::SremI128),
 
             "ElfTlsGetAddr" => Ok(Self::ElfTlsGetAddr),
             "ElfTlsGetOffset" => Ok(Self::ElfTlsGetOffset),
@@ -118,6 +130,10 @@ impl LibCall {
             Memset,
             Memmove,
             Memcmp,
+            UdivI128,
+            SdivI128,
+            UremI128,
+            SremI128,
             ElfTlsGetAddr,
             ElfTlsGetOffset,
             X86Pshufb,
@@ -168,6 +184,11 @@ impl LibCall {
                 sig.params.push(AbiParam::new(pointer_type));
                 sig.returns.push(AbiParam::new(I32))
             }
+            LibCall::UdivI128 | LibCall::SdivI128 | LibCall::UremI128 | LibCall::SremI128 => {
+                sig.params.push(AbiParam::new(I128));
+                sig.params.push(AbiParam::new(I128));
+                sig.returns.push(AbiParam::new(I128));
+            }
 
             LibCall::Probestack | LibCall::ElfTlsGetAddr | LibCall::ElfTlsGetOffset => {
                 unimplemented!()
@@ -294,6 +294,25 @@ impl Cond {
     pub fn bits(self) -> u32 {
         self as u32
     }
+
+    /// Return a set of flags for which this condition holds.
+    pub fn nzcv(self) -> NZCV {
+        let (n, z, c, v) = match self {
+            Cond::Eq | Cond::Ls | Cond::Le => (false, true, false, false),
+            Cond::Hs | Cond::Hi => (false, false, true, false),
+            Cond::Mi | Cond::Lt => (true, false, false, false),
+            Cond::Vs => (false, false, false, true),
+            Cond::Ne
+            | Cond::Lo
+            | Cond::Pl
+            | Cond::Vc
+            | Cond::Ge
+            | Cond::Gt
+            | Cond::Al
+            | Cond::Nv => (false, false, false, false),
+        };
+        NZCV::new(n, z, c, v)
+    }
 }
 
 /// The kind of conditional branch: the common-case-optimized "reg-is-zero" /
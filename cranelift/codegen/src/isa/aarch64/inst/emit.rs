@@ -249,6 +249,25 @@ pub(crate) fn enc_ldst_imm19(op_31_24: u32, imm19: u32, rd: Reg) -> u32 {
     (op_31_24 << 24) | (imm19 << 5) | machreg_to_gpr_or_vec(rd)
 }
 
+/// Encode the `prfop` operand of a `prfm`: type in bits 4:3, target in bits
+/// 2:1 and policy in bit 0.
+fn enc_prfop(kind: PrefetchKind, target: PrefetchTarget, policy: PrefetchPolicy) -> u8 {
+    let kind = match kind {
+        PrefetchKind::Load => 0b00,
+        PrefetchKind::Store => 0b10,
+    };
+    let target = match target {
+        PrefetchTarget::L1 => 0b00,
+        PrefetchTarget::L2 => 0b01,
+        PrefetchTarget::L3 => 0b10,
+    };
+    let policy = match policy {
+        PrefetchPolicy::Keep => 0,
+        PrefetchPolicy::Strm => 1,
+    };
+    (kind << 3) | (target << 1) | policy
+}
+
 fn enc_ldst_vec(q: u32, size: u32, rn: Reg, rt: Writable<Reg>) -> u32 {
     debug_assert_eq!(q & 0b1, q);
     debug_assert_eq!(size & 0b11, size);
@@ -1822,6 +1841,100 @@ impl MachInstEmit for Inst {
             &Inst::Csdb {} => {
                 sink.put4(0xd503229f);
             }
+            &Inst::Prefetch {
+                kind,
+                target,
+                policy,
+                ref mem,
+            } => {
+                let mem = mem.with_allocs(&mut allocs);
+                let (mem_insts, mem) = mem_finalize(Some(sink), &mem, state);
+
+                for inst in mem_insts.into_iter() {
+                    inst.emit(&[], sink, emit_info, state);
+                }
+
+                // The prefetch operation occupies the `Rt` field.
+                let rt = xreg(enc_prfop(kind, target, policy));
+                // Same as a 64-bit load, with opc = 0b10.
+                let op = 0b1111100010;
+
+                // No trap is registered: a prefetch never faults.
+                match &mem {
+                    &AMode::Unscaled { rn, simm9 } => {
+                        let reg = allocs.next(rn);
+                        sink.put4(enc_ldst_simm9(op, simm9, 0b00, reg, rt));
+                    }
+                    &AMode::UnsignedOffset { rn, uimm12 } => {
+                        let reg = allocs.next(rn);
+                        if uimm12.value() != 0 {
+                            assert_eq!(64, ty_bits(uimm12.scale_ty()));
+                        }
+                        sink.put4(enc_ldst_uimm12(op, uimm12, reg, rt));
+                    }
+                    &AMode::RegReg { rn, rm } => {
+                        let r1 = allocs.next(rn);
+                        let r2 = allocs.next(rm);
+                        sink.put4(enc_ldst_reg(
+                            op, r1, r2, /* scaled = */ false, /* extendop = */ None, rt,
+                        ));
+                    }
+                    &AMode::RegScaled { rn, rm, ty }
+                    | &AMode::RegScaledExtended { rn, rm, ty, .. } => {
+                        let r1 = allocs.next(rn);
+                        let r2 = allocs.next(rm);
+                        assert_eq!(64, ty_bits(ty));
+                        let extendop = match &mem {
+                            &AMode::RegScaled { .. } => None,
+                            &AMode::RegScaledExtended { extendop, .. } => Some(extendop),
+                            _ => unreachable!(),
+                        };
+                        sink.put4(enc_ldst_reg(
+                            op, r1, r2, /* scaled = */ true, extendop, rt,
+                        ));
+                    }
+                    &AMode::RegExtended { rn, rm, extendop } => {
+                        let r1 = allocs.next(rn);
+                        let r2 = allocs.next(rm);
+                        sink.put4(enc_ldst_reg(
+                            op,
+                            r1,
+                            r2,
+                            /* scaled = */ false,
+                            Some(extendop),
+                            rt,
+                        ));
+                    }
+                    &AMode::Label { ref label } => {
+                        let offset = match label {
+                            // cast i32 to u32 (two's-complement)
+                            MemLabel::PCRel(off) => *off as u32,
+                            MemLabel::Mach(label) => {
+                                sink.use_label_at_offset(
+                                    sink.cur_offset(),
+                                    *label,
+                                    LabelUse::Ldr19,
+                                );
+                                0
+                            }
+                        } / 4;
+                        assert!(offset < (1 << 19));
+                        // PRFM (literal)
+                        sink.put4(enc_ldst_imm19(0b11011000, offset, rt));
+                    }
+                    // No pre- or post-indexed forms exist for `prfm`.
+                    &AMode::SPPreIndexed { .. }
+                    | &AMode::SPPostIndexed { .. }
+                    // Eliminated by `mem_finalize()` above.
+                    | &AMode::SPOffset { .. }
+                    | &AMode::FPOffset { .. }
+                    | &AMode::NominalSPOffset { .. }
+                    | &AMode::Const { .. }
+                    | &AMode::RegOffset { .. } => {
+                        panic!("Should not see {:?} here!", mem)
+                    }
+                }
+            }
             &Inst::FpuMove64 { rd, rn } => {
                 let rd = allocs.next_writable(rd);
                 let rn = allocs.next(rn);
@@ -2140,6 +2253,7 @@ impl MachInstEmit for Inst {
                 };
                 let (u, opcode) = match op {
                     VecLanesOp::Uminv => (0b1, 0b11010),
+                    VecLanesOp::Umaxv => (0b1, 0b01010),
                     VecLanesOp::Addv => (0b0, 0b11011),
                 };
                 sink.put4(enc_vec_lanes(q, u, size, opcode, rd, rn));
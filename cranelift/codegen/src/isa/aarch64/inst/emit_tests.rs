@@ -3090,6 +3090,17 @@ fn test_aarch64_binemit() {
         "sqxtn v14.2s, v20.2d",
     ));
 
+    insns.push((
+        Inst::VecRRNarrowLow {
+            op: VecRRNarrowOp::Sqxtn,
+            rd: writable_vreg(3),
+            rn: vreg(5),
+            lane_size: ScalarSize::Size16,
+        },
+        "A348610E",
+        "sqxtn v3.4h, v5.4s",
+    ));
+
     insns.push((
         Inst::VecRRNarrowLow {
             op: VecRRNarrowOp::Sqxtun,
@@ -3147,6 +3158,18 @@ fn test_aarch64_binemit() {
         "uqxtn v31.4h, v31.4s",
     ));
 
+    insns.push((
+        Inst::VecRRNarrowHigh {
+            op: VecRRNarrowOp::Uqxtn,
+            rd: writable_vreg(6),
+            ri: vreg(6),
+            rn: vreg(9),
+            lane_size: ScalarSize::Size16,
+        },
+        "2649616E",
+        "uqxtn2 v6.8h, v6.8h, v9.4s",
+    ));
+
     insns.push((
         Inst::VecRRNarrowHigh {
             op: VecRRNarrowOp::Uqxtn,
@@ -5394,6 +5417,17 @@ fn test_aarch64_binemit() {
         "uminv s18, v4.4s",
     ));
 
+    insns.push((
+        Inst::VecLanes {
+            op: VecLanesOp::Addv,
+            rd: writable_vreg(3),
+            rn: vreg(12),
+            size: VectorSize::Size8x8,
+        },
+        "83B9310E",
+        "addv b3, v12.8b",
+    ));
+
     insns.push((
         Inst::VecLanes {
             op: VecLanesOp::Addv,
@@ -5438,6 +5472,61 @@ fn test_aarch64_binemit() {
         "addv s18, v5.4s",
     ));
 
+    insns.push((
+        Inst::VecLanes {
+            op: VecLanesOp::Umaxv,
+            rd: writable_vreg(0),
+            rn: vreg(31),
+            size: VectorSize::Size8x8,
+        },
+        "E0AB302E",
+        "umaxv b0, v31.8b",
+    ));
+
+    insns.push((
+        Inst::VecLanes {
+            op: VecLanesOp::Umaxv,
+            rd: writable_vreg(2),
+            rn: vreg(1),
+            size: VectorSize::Size8x16,
+        },
+        "22A8306E",
+        "umaxv b2, v1.16b",
+    ));
+
+    insns.push((
+        Inst::VecLanes {
+            op: VecLanesOp::Umaxv,
+            rd: writable_vreg(3),
+            rn: vreg(11),
+            size: VectorSize::Size16x4,
+        },
+        "63A9702E",
+        "umaxv h3, v11.4h",
+    ));
+
+    insns.push((
+        Inst::VecLanes {
+            op: VecLanesOp::Umaxv,
+            rd: writable_vreg(3),
+            rn: vreg(11),
+            size: VectorSize::Size16x8,
+        },
+        "63A9706E",
+        "umaxv h3, v11.8h",
+    ));
+
+    insns.push((
+        Inst::VecLanes {
+            op: VecLanesOp::Umaxv,
+            rd: writable_vreg(18),
+            rn: vreg(4),
+            size: VectorSize::Size32x4,
+        },
+        "92A8B06E",
+        "umaxv s18, v4.4s",
+    ));
+
     insns.push((
         Inst::VecShiftImm {
             op: VecShiftImmOp::Shl,
@@ -7903,6 +7992,59 @@ fn test_aarch64_binemit() {
 
     insns.push((Inst::Fence {}, "BF3B03D5", "dmb ish"));
 
+    insns.push((
+        Inst::Prefetch {
+            kind: PrefetchKind::Load,
+            target: PrefetchTarget::L1,
+            policy: PrefetchPolicy::Keep,
+            mem: AMode::UnsignedOffset {
+                rn: xreg(1),
+                uimm12: UImm12Scaled::maybe_from_i64(8, I64).unwrap(),
+            },
+        },
+        "200480F9",
+        "prfm pldl1keep, [x1, #8]",
+    ));
+    insns.push((
+        Inst::Prefetch {
+            kind: PrefetchKind::Store,
+            target: PrefetchTarget::L2,
+            policy: PrefetchPolicy::Strm,
+            mem: AMode::UnsignedOffset {
+                rn: xreg(2),
+                uimm12: UImm12Scaled::zero(I64),
+            },
+        },
+        "530080F9",
+        "prfm pstl2strm, [x2]",
+    ));
+    insns.push((
+        Inst::Prefetch {
+            kind: PrefetchKind::Load,
+            target: PrefetchTarget::L3,
+            policy: PrefetchPolicy::Keep,
+            mem: AMode::Unscaled {
+                rn: xreg(3),
+                simm9: SImm9::maybe_from_i64(-8).unwrap(),
+            },
+        },
+        "64809FF8",
+        "prfum pldl3keep, [x3, #-8]",
+    ));
+    insns.push((
+        Inst::Prefetch {
+            kind: PrefetchKind::Load,
+            target: PrefetchTarget::L1,
+            policy: PrefetchPolicy::Strm,
+            mem: AMode::RegReg {
+                rn: xreg(4),
+                rm: xreg(5),
+            },
+        },
+        "8168A5F8",
+        "prfm pldl1strm, [x4, x5]",
+    ));
+
     let flags = settings::Flags::new(settings::builder());
     let emit_info = EmitInfo::new(flags);
     for (insn, expected_encoding, expected_printing) in insns {
@@ -35,9 +35,10 @@ mod emit_tests;
 
 pub use crate::isa::aarch64::lower::isle::generated_code::{
     ALUOp, ALUOp3, AMode, APIKey, AtomicRMWLoopOp, AtomicRMWOp, BitOp, BranchTargetType, FPUOp1,
-    FPUOp2, FPUOp3, FpuRoundMode, FpuToIntOp, IntToFpuOp, MInst as Inst, MoveWideOp, VecALUModOp,
-    VecALUOp, VecExtendOp, VecLanesOp, VecMisc2, VecPairOp, VecRRLongOp, VecRRNarrowOp,
-    VecRRPairLongOp, VecRRRLongModOp, VecRRRLongOp, VecShiftImmModOp, VecShiftImmOp,
+    FPUOp2, FPUOp3, FpuRoundMode, FpuToIntOp, IntToFpuOp, MInst as Inst, MoveWideOp, PrefetchKind,
+    PrefetchPolicy, PrefetchTarget, VecALUModOp, VecALUOp, VecExtendOp, VecLanesOp, VecMisc2,
+    VecPairOp, VecRRLongOp, VecRRNarrowOp, VecRRPairLongOp, VecRRRLongModOp, VecRRRLongOp,
+    VecShiftImmModOp, VecShiftImmOp,
 };
 
 /// A floating-point unit (FPU) operation with two args, a register and an immediate.
@@ -581,6 +582,9 @@ fn aarch64_get_operands<F: Fn(VReg) -> VReg>(inst: &Inst, collector: &mut Operan
             collector.reg_use(rt);
         }
         &Inst::Fence {} | &Inst::Csdb {} => {}
+        &Inst::Prefetch { ref mem, .. } => {
+            memarg_operands(mem, collector);
+        }
         &Inst::FpuMove64 { rd, rn } => {
             collector.reg_def(rd);
             collector.reg_use(rn);
@@ -1668,6 +1672,34 @@ impl Inst {
             &Inst::Csdb {} => {
                 format!("csdb")
             }
+            &Inst::Prefetch {
+                kind,
+                target,
+                policy,
+                ref mem,
+            } => {
+                let op = match &mem {
+                    &AMode::Unscaled { .. } => "prfum",
+                    _ => "prfm",
+                };
+                let kind = match kind {
+                    PrefetchKind::Load => "pld",
+                    PrefetchKind::Store => "pst",
+                };
+                let target = match target {
+                    PrefetchTarget::L1 => "l1",
+                    PrefetchTarget::L2 => "l2",
+                    PrefetchTarget::L3 => "l3",
+                };
+                let policy = match policy {
+                    PrefetchPolicy::Keep => "keep",
+                    PrefetchPolicy::Strm => "strm",
+                };
+                let mem = mem.with_allocs(allocs);
+                let (mem_str, mem) = mem_finalize_for_show(&mem, state);
+                let mem = mem.pretty_print_default();
+                format!("{}{} {}{}{}, {}", mem_str, op, kind, target, policy, mem)
+            }
             &Inst::FpuMove64 { rd, rn } => {
                 let rd = pretty_print_vreg_scalar(rd.to_reg(), ScalarSize::Size64, allocs);
                 let rn = pretty_print_vreg_scalar(rn, ScalarSize::Size64, allocs);
@@ -2390,6 +2422,7 @@ impl Inst {
             &Inst::VecLanes { op, rd, rn, size } => {
                 let op = match op {
                     VecLanesOp::Uminv => "uminv",
+                    VecLanesOp::Umaxv => "umaxv",
                     VecLanesOp::Addv => "addv",
                 };
                 let rd = pretty_print_vreg_scalar(rd.to_reg(), size.lane_size(), allocs);
@@ -580,6 +580,10 @@ impl Context for IsleContext<'_, '_, MInst, AArch64Backend> {
     fn invert_cond(&mut self, cond: &Cond) -> Cond {
         (*cond).invert()
     }
+
+    fn cond_nzcv(&mut self, cond: &Cond) -> NZCV {
+        cond.nzcv()
+    }
     fn preg_sp(&mut self) -> PReg {
         super::regs::stack_reg().to_real_reg().unwrap().into()
     }
@@ -758,6 +762,15 @@ impl Context for IsleContext<'_, '_, MInst, AArch64Backend> {
         }
     }
 
+    fn shuffle_tbl1_from_imm(&mut self, imm: Immediate) -> Option<u128> {
+        let bytes = self.lower_ctx.get_immediate_data(imm).as_slice();
+        if bytes.iter().all(|b| *b < 16) {
+            Some(u128::from_le_bytes(bytes.try_into().ok()?))
+        } else {
+            None
+        }
+    }
+
     fn shuffle_dup8_from_imm(&mut self, imm: Immediate) -> Option<u8> {
         let bytes = self.lower_ctx.get_immediate_data(imm).as_slice();
         if bytes.iter().all(|b| *b == bytes[0]) && bytes[0] < 16 {
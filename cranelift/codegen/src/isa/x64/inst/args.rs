@@ -831,12 +831,21 @@ impl fmt::Display for AluRmiROpcode {
 pub enum AluRmROpcode {
     /// And with negated second operand.
     Andn,
+    /// Arithmetic shift right without affecting flags.
+    Sarx,
+    /// Logical shift right without affecting flags.
+    Shrx,
+    /// Logical shift left without affecting flags.
+    Shlx,
 }
 
 impl AluRmROpcode {
     pub(crate) fn available_from(&self) -> SmallVec<[InstructionSet; 2]> {
         match self {
             AluRmROpcode::Andn => smallvec![InstructionSet::BMI1],
+            AluRmROpcode::Sarx | AluRmROpcode::Shrx | AluRmROpcode::Shlx => {
+                smallvec![InstructionSet::BMI2]
+            }
         }
     }
 }
@@ -845,6 +854,9 @@ impl fmt::Debug for AluRmROpcode {
     fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
         let name = match self {
             AluRmROpcode::Andn => "andn",
+            AluRmROpcode::Sarx => "sarx",
+            AluRmROpcode::Shrx => "shrx",
+            AluRmROpcode::Shlx => "shlx",
         };
         write!(fmt, "{}", name)
     }
@@ -2186,3 +2198,40 @@ pub enum FenceKind {
     /// `sfence` instruction ("Store Fence")
     SFence,
 }
+
+/// The cache level hint of an x64 prefetch.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum PrefetchLocality {
+    /// `prefetcht0`: fetch into all levels of the cache hierarchy.
+    T0,
+    /// `prefetcht1`: fetch into level 2 cache and higher.
+    T1,
+    /// `prefetcht2`: fetch into level 3 cache and higher.
+    T2,
+    /// `prefetchnta`: fetch while minimizing cache pollution.
+    Nta,
+}
+
+impl PrefetchLocality {
+    /// The `/r` opcode extension selecting this hint in `0F 18 /r`.
+    pub(crate) fn enc(self) -> u8 {
+        match self {
+            PrefetchLocality::Nta => 0,
+            PrefetchLocality::T0 => 1,
+            PrefetchLocality::T1 => 2,
+            PrefetchLocality::T2 => 3,
+        }
+    }
+}
+
+impl fmt::Display for PrefetchLocality {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            PrefetchLocality::T0 => "prefetcht0",
+            PrefetchLocality::T1 => "prefetcht1",
+            PrefetchLocality::T2 => "prefetcht2",
+            PrefetchLocality::Nta => "prefetchnta",
+        };
+        write!(f, "{name}")
+    }
+}
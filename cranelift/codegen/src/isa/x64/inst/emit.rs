@@ -120,7 +120,7 @@ pub(crate) fn emit(
             InstructionSet::Popcnt => info.isa_flags.use_popcnt(),
             InstructionSet::Lzcnt => info.isa_flags.use_lzcnt(),
             InstructionSet::BMI1 => info.isa_flags.use_bmi1(),
-            InstructionSet::BMI2 => info.isa_flags.has_bmi2(),
+            InstructionSet::BMI2 => info.isa_flags.use_bmi2(),
             InstructionSet::FMA => info.isa_flags.has_fma(),
             InstructionSet::AVX => info.isa_flags.has_avx(),
             InstructionSet::AVX2 => info.isa_flags.has_avx2(),
@@ -370,16 +370,26 @@ pub(crate) fn emit(
                 _ => unreachable!(),
             };
 
-            let opcode = match op {
-                Andn => 0xf2,
+            let dst = dst.to_real_reg().unwrap().hw_enc();
+            let src1 = src1.to_real_reg().unwrap().hw_enc();
+            let src2 = src2.to_real_reg().unwrap().hw_enc();
+
+            // `andn` takes its first source in `vvvv`, while the BMI2 shifts
+            // take the value to shift in `r/m` and the shift amount in `vvvv`.
+            let (prefix, opcode, vvvv, rm) = match op {
+                Andn => (LegacyPrefixes::None, 0xf2, src1, src2),
+                Sarx => (LegacyPrefixes::_F3, 0xf7, src2, src1),
+                Shrx => (LegacyPrefixes::_F2, 0xf7, src2, src1),
+                Shlx => (LegacyPrefixes::_66, 0xf7, src2, src1),
             };
 
             VexInstruction::new()
+                .prefix(prefix)
                 .map(OpcodeMap::_0F38)
                 .w(w)
-                .reg(dst.to_real_reg().unwrap().hw_enc())
-                .vvvv(src1.to_real_reg().unwrap().hw_enc())
-                .rm(src2.to_real_reg().unwrap().hw_enc())
+                .reg(dst)
+                .vvvv(vvvv)
+                .rm(rm)
                 .opcode(opcode)
                 .encode(sink);
         }
@@ -3931,6 +3941,21 @@ pub(crate) fn emit(
             }
         }
 
+        Inst::Prefetch { locality, addr } => {
+            let addr = &addr.finalize(state, sink).with_allocs(allocs);
+            // prefetch{nta,t0,t1,t2} = 0F 18 /{0,1,2,3}
+            emit_std_enc_mem(
+                sink,
+                LegacyPrefixes::None,
+                0x0F18,
+                2,
+                locality.enc(),
+                addr,
+                RexFlags::clear_w(),
+                0,
+            );
+        }
+
         Inst::Hlt => {
             sink.put1(0xcc);
         }
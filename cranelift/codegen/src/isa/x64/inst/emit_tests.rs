@@ -29,6 +29,22 @@ impl Inst {
         }
     }
 
+    fn alu_rm_r_vex(
+        size: OperandSize,
+        op: AluRmROpcode,
+        src1: Reg,
+        src2: Reg,
+        dst: Writable<Reg>,
+    ) -> Inst {
+        Inst::AluRmRVex {
+            size,
+            op,
+            src1: Gpr::new(src1).unwrap(),
+            src2: Gpr::new(src2).unwrap(),
+            dst: WritableGpr::from_writable_reg(dst).unwrap(),
+        }
+    }
+
     fn xmm_unary_rm_r_imm(op: SseOpcode, src: RegMem, dst: Writable<Reg>, imm: u8) -> Inst {
         src.assert_regclass_is(RegClass::Float);
         debug_assert!(dst.to_reg().class() == RegClass::Float);
@@ -99,6 +115,18 @@ impl Inst {
         }
     }
 
+    fn xmm_rmi_r_vex(op: AvxOpcode, src1: Reg, src2: RegMem, dst: Writable<Reg>) -> Self {
+        src2.assert_regclass_is(RegClass::Float);
+        debug_assert!(src1.class() == RegClass::Float);
+        debug_assert!(dst.to_reg().class() == RegClass::Float);
+        Inst::XmmRmiRVex {
+            op,
+            src1: Xmm::new(src1).unwrap(),
+            src2: XmmMemImm::new(src2.into()).unwrap(),
+            dst: WritableXmm::from_writable_reg(dst).unwrap(),
+        }
+    }
+
     // TODO Can be replaced by `Inst::move` (high-level) and `Inst::unary_rm_r` (low-level)
     fn xmm_mov(op: SseOpcode, src: RegMem, dst: Writable<Reg>) -> Inst {
         src.assert_regclass_is(RegClass::Float);
@@ -3632,6 +3660,35 @@ fn test_x64_emit() {
         "rorw    $5, %r15w, %r15w",
     ));
 
+    // ========================================================
+    // AluRmRVex: the BMI2 shifts take the amount in any register, unlike the
+    // legacy Shift_R forms above which require it to be in %cl.
+    insns.push((
+        Inst::alu_rm_r_vex(OperandSize::Size64, AluRmROpcode::Shlx, rdi, rcx, w_rdi),
+        "C4E2F1F7FF",
+        "shlx    %rcx, %rdi, %rdi",
+    ));
+    insns.push((
+        Inst::alu_rm_r_vex(OperandSize::Size32, AluRmROpcode::Shlx, r12, rcx, w_r12),
+        "C44271F7E4",
+        "shlx    %ecx, %r12d, %r12d",
+    ));
+    insns.push((
+        Inst::alu_rm_r_vex(OperandSize::Size64, AluRmROpcode::Shlx, r13, r9, w_rdx),
+        "C4C2B1F7D5",
+        "shlx    %r9, %r13, %rdx",
+    ));
+    insns.push((
+        Inst::alu_rm_r_vex(OperandSize::Size64, AluRmROpcode::Shrx, rdi, rcx, w_rdi),
+        "C4E2F3F7FF",
+        "shrx    %rcx, %rdi, %rdi",
+    ));
+    insns.push((
+        Inst::alu_rm_r_vex(OperandSize::Size64, AluRmROpcode::Sarx, rdi, rcx, w_rdi),
+        "C4E2F2F7FF",
+        "sarx    %rcx, %rdi, %rdi",
+    ));
+
     // ========================================================
     // CmpRMIR
     insns.push((
@@ -4346,6 +4403,24 @@ fn test_x64_emit() {
         "addsd   %xmm4, %xmm15, %xmm4",
     ));
 
+    // The legacy SSE form of a float add overwrites its first source, while the
+    // VEX form used with `has_avx` takes a separate destination.
+    insns.push((
+        Inst::xmm_rm_r(SseOpcode::Addps, RegMem::reg(xmm1), w_xmm0),
+        "0F58C1",
+        "addps   %xmm0, %xmm1, %xmm0",
+    ));
+    insns.push((
+        Inst::xmm_rmi_r_vex(AvxOpcode::Vaddps, xmm0, RegMem::reg(xmm1), w_xmm2),
+        "C5F858D1",
+        "vaddps  %xmm0, %xmm1, %xmm2",
+    ));
+    insns.push((
+        Inst::xmm_rmi_r_vex(AvxOpcode::Vaddps, xmm0, RegMem::reg(xmm1), w_xmm10),
+        "C57858D1",
+        "vaddps  %xmm0, %xmm1, %xmm10",
+    ));
+
     insns.push((
         Inst::xmm_rm_r(SseOpcode::Subss, RegMem::reg(xmm0), w_xmm1),
         "F30F5CC8",
@@ -4484,6 +4559,16 @@ fn test_x64_emit() {
         "paddusb %xmm13, %xmm12, %xmm13",
     ));
 
+    insns.push((
+        Inst::xmm_rm_r(
+            SseOpcode::Paddusb,
+            RegMem::mem(Amode::imm_reg(0, rdi)),
+            w_xmm1,
+        ),
+        "660FDC0F",
+        "paddusb %xmm1, 0(%rdi), %xmm1",
+    ));
+
     insns.push((
         Inst::xmm_rm_r(SseOpcode::Paddusw, RegMem::reg(xmm1), w_xmm8),
         "66440FDDC1",
@@ -4694,6 +4779,28 @@ fn test_x64_emit() {
         "pshufb  %xmm2, %xmm11, %xmm2",
     ));
 
+    insns.push((
+        Inst::xmm_rm_r(SseOpcode::Pshufb, RegMem::reg(xmm1), w_xmm0),
+        "660F3800C1",
+        "pshufb  %xmm0, %xmm1, %xmm0",
+    ));
+
+    insns.push((
+        Inst::xmm_rm_r(SseOpcode::Pshufb, RegMem::reg(xmm10), w_xmm9),
+        "66450F3800CA",
+        "pshufb  %xmm9, %xmm10, %xmm9",
+    ));
+
+    insns.push((
+        Inst::xmm_rm_r(
+            SseOpcode::Pshufb,
+            RegMem::mem(Amode::imm_reg(16, rsi)),
+            w_xmm5,
+        ),
+        "660F38006E10",
+        "pshufb  %xmm5, 16(%rsi), %xmm5",
+    ));
+
     insns.push((
         Inst::xmm_rm_r(SseOpcode::Packssdw, RegMem::reg(xmm11), w_xmm12),
         "66450F6BE3",
@@ -5397,6 +5504,40 @@ fn test_x64_emit() {
         "sfence",
     ));
 
+    // Prefetch
+    insns.push((
+        Inst::Prefetch {
+            locality: PrefetchLocality::Nta,
+            addr: Amode::imm_reg(0, rax).into(),
+        },
+        "0F1800",
+        "prefetchnta 0(%rax)",
+    ));
+    insns.push((
+        Inst::Prefetch {
+            locality: PrefetchLocality::T0,
+            addr: Amode::imm_reg(64, rdi).into(),
+        },
+        "0F184F40",
+        "prefetcht0 64(%rdi)",
+    ));
+    insns.push((
+        Inst::Prefetch {
+            locality: PrefetchLocality::T1,
+            addr: Amode::imm_reg(0, r8).into(),
+        },
+        "410F1810",
+        "prefetcht1 0(%r8)",
+    ));
+    insns.push((
+        Inst::Prefetch {
+            locality: PrefetchLocality::T2,
+            addr: Amode::imm_reg(0, r13).into(),
+        },
+        "410F185D00",
+        "prefetcht2 0(%r13)",
+    ));
+
     // ========================================================
     // Misc instructions.
 
@@ -5452,6 +5593,7 @@ fn test_x64_emit() {
     isa_flag_builder.enable("has_avx512f").unwrap();
     isa_flag_builder.enable("has_avx512vbmi").unwrap();
     isa_flag_builder.enable("has_avx512vl").unwrap();
+    isa_flag_builder.enable("has_bmi2").unwrap();
     let isa_flags = x64::settings::Flags::new(&flags, &isa_flag_builder);
 
     let emit_info = EmitInfo::new(flags, isa_flags);
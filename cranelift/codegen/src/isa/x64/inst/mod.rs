@@ -137,6 +137,7 @@ impl Inst {
             | Inst::Not { .. }
             | Inst::Nop { .. }
             | Inst::Pop64 { .. }
+            | Inst::Prefetch { .. }
             | Inst::Push64 { .. }
             | Inst::StackProbeLoop { .. }
             | Inst::Args { .. }
@@ -1789,6 +1790,11 @@ impl PrettyPrint for Inst {
                 FenceKind::SFence => "sfence".to_string(),
             },
 
+            Inst::Prefetch { locality, addr } => {
+                let addr = addr.pretty_print(8, allocs);
+                format!("{locality} {addr}")
+            }
+
             Inst::VirtualSPOffsetAdj { offset } => format!("virtual_sp_offset_adjust {offset}"),
 
             Inst::Hlt => "hlt".into(),
@@ -2210,6 +2216,9 @@ fn x64_get_operands<F: Fn(VReg) -> VReg>(inst: &Inst, collector: &mut OperandCol
             collector.reg_use(src.to_reg());
             dst.get_operands(collector);
         }
+        Inst::Prefetch { addr, .. } => {
+            addr.get_operands(collector);
+        }
         Inst::ShiftR {
             num_bits, src, dst, ..
         } => {
@@ -149,8 +149,8 @@ fn emit_vm_call(
     flags: &Flags,
     triple: &Triple,
     libcall: LibCall,
-    inputs: &[Reg],
-    outputs: &[Writable<Reg>],
+    inputs: &[ValueRegs<Reg>],
+    outputs: &[ValueRegs<Writable<Reg>>],
 ) -> CodegenResult<()> {
     let extname = ExternalName::LibCall(libcall);
 
@@ -178,14 +178,14 @@ fn emit_vm_call(
     assert_eq!(inputs.len(), abi.num_args(ctx.sigs()));
 
     for (i, input) in inputs.iter().enumerate() {
-        for inst in abi.gen_arg(ctx, i, ValueRegs::one(*input)) {
+        for inst in abi.gen_arg(ctx, i, *input) {
             ctx.emit(inst);
         }
     }
 
     let mut retval_insts: SmallInstVec<_> = smallvec![];
     for (i, output) in outputs.iter().enumerate() {
-        retval_insts.extend(abi.gen_retval(ctx, i, ValueRegs::one(*output)).into_iter());
+        retval_insts.extend(abi.gen_retval(ctx, i, *output).into_iter());
     }
     abi.emit_call(ctx);
     for inst in retval_insts {
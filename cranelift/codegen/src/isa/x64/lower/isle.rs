@@ -270,6 +270,26 @@ impl Context for IsleContext<'_, '_, MInst, X64Backend> {
         self.backend.x64_flags.use_bmi1()
     }
 
+    fn icmp_flags_live_at_branch(&mut self, val: Value) -> bool {
+        let inst = match self.lower_ctx.get_value_as_source_or_const(val).inst {
+            InputSourceInst::Use(inst, 0) => inst,
+            _ => return false,
+        };
+        let branch = self.lower_ctx.cur_inst();
+        self.lower_ctx.prev_inst(branch) == Some(inst)
+            && self.lower_ctx.dfg().inst_args(inst).iter().all(|&arg| {
+                self.lower_ctx
+                    .get_value_as_source_or_const(arg)
+                    .constant
+                    .is_none()
+            })
+    }
+
+    #[inline]
+    fn use_bmi2(&mut self) -> bool {
+        self.backend.x64_flags.use_bmi2()
+    }
+
     #[inline]
     fn use_popcnt(&mut self) -> bool {
         self.backend.x64_flags.use_popcnt()
@@ -285,6 +305,11 @@ impl Context for IsleContext<'_, '_, MInst, X64Backend> {
         self.backend.x64_flags.use_ssse3()
     }
 
+    #[inline]
+    fn use_llvm_abi_extensions(&mut self) -> bool {
+        self.backend.flags.enable_llvm_abi_extensions()
+    }
+
     #[inline]
     fn use_sse41(&mut self) -> bool {
         self.backend.x64_flags.use_sse41()
@@ -565,6 +590,14 @@ impl Context for IsleContext<'_, '_, MInst, X64Backend> {
         Imm8Gpr::new(ir.clone()).unwrap()
     }
 
+    #[inline]
+    fn imm8_gpr_reg(&mut self, ig: &Imm8Gpr) -> Option<Gpr> {
+        match ig.clone().to_imm8_reg() {
+            Imm8Reg::Reg { reg } => Some(Gpr::new(reg).unwrap()),
+            Imm8Reg::Imm8 { .. } => None,
+        }
+    }
+
     #[inline]
     fn gpr_to_gpr_mem(&mut self, gpr: Gpr) -> GprMem {
         GprMem::from(gpr)
@@ -693,8 +726,8 @@ impl Context for IsleContext<'_, '_, MInst, X64Backend> {
             &self.backend.flags,
             &self.backend.triple,
             libcall.clone(),
-            &[a],
-            &[output_reg],
+            &[ValueRegs::one(a)],
+            &[WritableValueRegs::one(output_reg)],
         )
         .expect("Failed to emit LibCall");
 
@@ -711,8 +744,8 @@ impl Context for IsleContext<'_, '_, MInst, X64Backend> {
             &self.backend.flags,
             &self.backend.triple,
             libcall.clone(),
-            &[a, b],
-            &[output_reg],
+            &[ValueRegs::one(a), ValueRegs::one(b)],
+            &[WritableValueRegs::one(output_reg)],
         )
         .expect("Failed to emit LibCall");
 
@@ -729,14 +762,30 @@ impl Context for IsleContext<'_, '_, MInst, X64Backend> {
             &self.backend.flags,
             &self.backend.triple,
             libcall.clone(),
-            &[a, b, c],
-            &[output_reg],
+            &[ValueRegs::one(a), ValueRegs::one(b), ValueRegs::one(c)],
+            &[WritableValueRegs::one(output_reg)],
         )
         .expect("Failed to emit LibCall");
 
         output_reg.to_reg()
     }
 
+    fn libcall_2_i128(&mut self, libcall: &LibCall, a: ValueRegs, b: ValueRegs) -> ValueRegs {
+        let output_regs = self.lower_ctx.alloc_tmp(I128);
+
+        emit_vm_call(
+            self.lower_ctx,
+            &self.backend.flags,
+            &self.backend.triple,
+            libcall.clone(),
+            &[a, b],
+            &[output_regs],
+        )
+        .expect("Failed to emit LibCall");
+
+        output_regs.map(|r| r.to_reg())
+    }
+
     #[inline]
     fn single_target(&mut self, targets: &MachLabelSlice) -> Option<MachLabel> {
         if targets.len() == 1 {
@@ -1092,6 +1092,16 @@ impl<'func, I: VCodeInst> Lower<'func, I> {
         &self.f.dfg
     }
 
+    /// Get the instruction currently being lowered.
+    pub fn cur_inst(&self) -> Inst {
+        self.cur_inst.unwrap()
+    }
+
+    /// Get the instruction immediately before `inst` in its block, if any.
+    pub fn prev_inst(&self, inst: Inst) -> Option<Inst> {
+        self.f.layout.prev_inst(inst)
+    }
+
     /// Get the `Callee`.
     pub fn abi(&self) -> &Callee<I::ABIMachineSpec> {
         self.vcode.abi()
@@ -93,12 +93,13 @@ impl TestFileCompiler {
         let _ = &mut builder; // require mutability on all architectures
         #[cfg(target_arch = "x86_64")]
         {
-            builder.symbol_lookup_fn(Box::new(|name| {
-                if name == "__cranelift_x86_pshufb" {
-                    Some(__cranelift_x86_pshufb as *const u8)
-                } else {
-                    None
-                }
+            builder.symbol_lookup_fn(Box::new(|name| match name {
+                "__cranelift_x86_pshufb" => Some(__cranelift_x86_pshufb as *const u8),
+                "__udivti3" => Some(udivti3 as *const u8),
+                "__divti3" => Some(divti3 as *const u8),
+                "__umodti3" => Some(umodti3 as *const u8),
+                "__modti3" => Some(modti3 as *const u8),
+                _ => None,
             }));
         }
         let module = JITModule::new(builder);
@@ -511,6 +512,33 @@ fn make_trampoline(name: UserFuncName, signature: &ir::Signature, isa: &dyn Targ
     func
 }
 
+// 128-bit division libcalls. The compiler-builtins versions of these aren't
+// exported from the test binary, so provide them here. Division by zero and
+// overflow are checked before these are called.
+#[cfg(target_arch = "x86_64")]
+#[allow(improper_ctypes_definitions)]
+extern "C" fn udivti3(a: u128, b: u128) -> u128 {
+    a / b
+}
+
+#[cfg(target_arch = "x86_64")]
+#[allow(improper_ctypes_definitions)]
+extern "C" fn divti3(a: i128, b: i128) -> i128 {
+    a / b
+}
+
+#[cfg(target_arch = "x86_64")]
+#[allow(improper_ctypes_definitions)]
+extern "C" fn umodti3(a: u128, b: u128) -> u128 {
+    a % b
+}
+
+#[cfg(target_arch = "x86_64")]
+#[allow(improper_ctypes_definitions)]
+extern "C" fn modti3(a: i128, b: i128) -> i128 {
+    a.wrapping_rem(b)
+}
+
 #[cfg(target_arch = "x86_64")]
 use std::arch::x86_64::__m128i;
 #[cfg(target_arch = "x86_64")]
@@ -1,5 +1,7 @@
+use cranelift::codegen::ir::Opcode;
 use std::collections::HashMap;
 use std::ops::RangeInclusive;
+use target_lexicon::{Architecture, Triple};
 
 /// Holds the range of acceptable values to use during the generation of testcases
 pub struct Config {
@@ -37,6 +39,13 @@ pub struct Config {
     pub static_stack_slots_per_function: RangeInclusive<usize>,
     /// Size in bytes
     pub static_stack_slot_size: RangeInclusive<usize>,
+    /// Number of store/reload round-trips through a stack slot that we generate per block.
+    /// Set this to `0..=0` to disable them.
+    pub stack_roundtrips_per_block: RangeInclusive<usize>,
+    /// Number of if/else diamonds that we generate at the end of each block. Each one
+    /// branches on a `brif` to two new blocks, which pass a value (optionally picked with a
+    /// `select`) to a merge block. Set this to `0..=0` to disable them.
+    pub diamonds_per_block: RangeInclusive<usize>,
     /// Allowed stack probe sizes
     pub stack_probe_size_log2: RangeInclusive<usize>,
 
@@ -64,9 +73,48 @@ pub struct Config {
     /// impacted as much
     pub compile_flag_ratio: HashMap<&'static str, (usize, usize)>,
 
+    /// Relative weights of the opcode families that instructions are picked from.
+    ///
+    /// Every opcode has a weight of 1, unless it belongs to a family listed here, in
+    /// which case it takes that family's weight. A weight of 0 disables the family.
+    /// Raising a family's weight concentrates generated instructions on it, which is
+    /// useful when a fuzzing campaign targets the lowering of those instructions.
+    ///
+    /// When empty, opcodes are picked uniformly.
+    pub opcode_family_weights: HashMap<OpcodeFamily, usize>,
+
     /// Range of values for the padding between basic blocks. Larger values will
     /// generate larger functions.
     pub bb_padding_log2_size: RangeInclusive<usize>,
+
+    /// Targets that a printed test case should declare in its header.
+    ///
+    /// When empty, only the target of the ISA that the test case was generated
+    /// for is declared.
+    pub targets: Vec<Architecture>,
+
+    /// Target used to build the ISA that the function passes (NaN canonicalization,
+    /// `int_divz`, `fcvt`) run against.
+    ///
+    /// When `None`, or if this build of Cranelift doesn't include a backend for the
+    /// requested target, the host ISA is used instead.
+    pub pass_target: Option<Triple>,
+
+    /// Whether to run the NaN canonicalization pass on generated functions.
+    ///
+    /// The bits of a NaN produced by an operation differ between targets, and between
+    /// the interpreter and compiled code, so by default every NaN is replaced by a single
+    /// canonical one. Disabling this allows a fuzzing campaign to look for genuine
+    /// NaN-handling discrepancies, at the cost of comparing NaN results more loosely
+    /// (see `exact_nan_payloads`).
+    pub canonicalize_nans: bool,
+
+    /// When `canonicalize_nans` is disabled, whether NaN results must still match
+    /// bit-for-bit.
+    ///
+    /// Only enable this when every target under test guarantees the payload of the
+    /// NaNs it produces. Otherwise any NaN is considered equal to any other NaN.
+    pub exact_nan_payloads: bool,
 }
 
 impl Default for Config {
@@ -86,6 +134,8 @@ impl Default for Config {
             switch_max_range_size: 2..=32,
             static_stack_slots_per_function: 0..=8,
             static_stack_slot_size: 0..=128,
+            stack_roundtrips_per_block: 0..=0,
+            diamonds_per_block: 0..=0,
             // We need the mix of sizes that allows us to:
             //  * not generates any stack probes
             //  * generate unrolled stack probes
@@ -105,11 +155,124 @@ impl Default for Config {
             allowed_int_divz_ratio: (1, 1_000_000),
             allowed_fcvt_traps_ratio: (1, 1_000_000),
             compile_flag_ratio: [("regalloc_checker", (1usize, 1000))].into_iter().collect(),
+            opcode_family_weights: HashMap::new(),
             // Generate up to 4KiB of padding between basic blocks. Although we only
             // explicitly generate up to 16 blocks, after SSA construction we can
             // end up with way more blocks than that (Seeing 400 blocks is not uncommon).
             // At 4KiB we end up at around 1.5MiB of padding per function, which seems reasonable.
             bb_padding_log2_size: 0..=12,
+            targets: Vec::new(),
+            pass_target: None,
+            canonicalize_nans: true,
+            exact_nan_payloads: false,
+        }
+    }
+}
+
+/// A group of related opcodes whose weight can be set through
+/// [`Config::opcode_family_weights`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum OpcodeFamily {
+    IntArithmetic,
+    FloatArithmetic,
+    /// Conversions between integers and floats, and between float types.
+    FloatConversion,
+    Bitwise,
+    Shift,
+    Memory,
+}
+
+impl OpcodeFamily {
+    /// Returns the opcodes that belong to this family.
+    pub fn opcodes(self) -> &'static [Opcode] {
+        match self {
+            OpcodeFamily::IntArithmetic => &[
+                Opcode::Iadd,
+                Opcode::Isub,
+                Opcode::Imul,
+                Opcode::Umulhi,
+                Opcode::Smulhi,
+                Opcode::Udiv,
+                Opcode::Sdiv,
+                Opcode::Urem,
+                Opcode::Srem,
+                Opcode::Ineg,
+                Opcode::Iabs,
+                Opcode::Smin,
+                Opcode::Umin,
+                Opcode::Smax,
+                Opcode::Umax,
+            ],
+            OpcodeFamily::FloatArithmetic => &[
+                Opcode::Fadd,
+                Opcode::Fsub,
+                Opcode::Fmul,
+                Opcode::Fdiv,
+                Opcode::Sqrt,
+                Opcode::Fma,
+                Opcode::Fneg,
+                Opcode::Fabs,
+                Opcode::Fcopysign,
+                Opcode::Fmin,
+                Opcode::Fmax,
+                Opcode::Ceil,
+                Opcode::Floor,
+                Opcode::Trunc,
+                Opcode::Nearest,
+            ],
+            OpcodeFamily::FloatConversion => &[
+                Opcode::FcvtToUint,
+                Opcode::FcvtToSint,
+                Opcode::FcvtToUintSat,
+                Opcode::FcvtToSintSat,
+                Opcode::FcvtFromUint,
+                Opcode::FcvtFromSint,
+                Opcode::Fpromote,
+                Opcode::Fdemote,
+                Opcode::FvpromoteLow,
+                Opcode::Fvdemote,
+            ],
+            OpcodeFamily::Bitwise => &[
+                Opcode::Band,
+                Opcode::Bor,
+                Opcode::Bxor,
+                Opcode::Bnot,
+                Opcode::BandNot,
+                Opcode::BorNot,
+                Opcode::BxorNot,
+                Opcode::Bitrev,
+                Opcode::Clz,
+                Opcode::Cls,
+                Opcode::Ctz,
+                Opcode::Popcnt,
+                Opcode::Bswap,
+            ],
+            OpcodeFamily::Shift => &[
+                Opcode::Ishl,
+                Opcode::Ushr,
+                Opcode::Sshr,
+                Opcode::Rotl,
+                Opcode::Rotr,
+            ],
+            OpcodeFamily::Memory => &[
+                Opcode::Load,
+                Opcode::Store,
+                Opcode::Uload8,
+                Opcode::Sload8,
+                Opcode::Uload16,
+                Opcode::Sload16,
+                Opcode::Uload32,
+                Opcode::Sload32,
+                Opcode::Istore8,
+                Opcode::Istore16,
+                Opcode::Istore32,
+                Opcode::StackLoad,
+                Opcode::StackStore,
+                Opcode::AtomicLoad,
+                Opcode::AtomicStore,
+                Opcode::AtomicRmw,
+                Opcode::AtomicCas,
+            ],
         }
     }
 }
@@ -1,5 +1,5 @@
 use crate::config::Config;
-use crate::cranelift_arbitrary::CraneliftArbitrary;
+use crate::cranelift_arbitrary::{types_for_architecture, CraneliftArbitrary};
 use anyhow::Result;
 use arbitrary::{Arbitrary, Unstructured};
 use cranelift::codegen::data_value::DataValue;
@@ -860,6 +860,7 @@ static OPCODE_SIGNATURES: Lazy<Vec<OpcodeSignature>> = Lazy::new(|| {
                 args.as_slice(),
                 rets.as_slice(),
                 (Opcode::Debugtrap),
+                (Opcode::Prefetch),
                 (Opcode::Trap),
                 (Opcode::Trapz),
                 (Opcode::ResumableTrap),
@@ -1706,10 +1707,46 @@ where
         Ok(())
     }
 
+    /// Picks the signature of the next instruction to generate, weighted by
+    /// `Config::opcode_family_weights`.
+    fn choose_opcode_signature(&mut self) -> Result<&'static OpcodeSignature> {
+        // Keep the plain uniform choice when no weights are configured, so that
+        // existing corpora keep generating the same functions.
+        if self.config.opcode_family_weights.is_empty() {
+            return Ok(self.u.choose(&OPCODE_SIGNATURES)?);
+        }
+
+        let weight = |op: Opcode| {
+            self.config
+                .opcode_family_weights
+                .iter()
+                .find(|(family, _)| family.opcodes().contains(&op))
+                .map_or(1, |(_, weight)| *weight)
+        };
+        // Sum in `u128` so that even `usize::MAX` weights can't overflow.
+        let weights = OPCODE_SIGNATURES
+            .iter()
+            .map(|(op, _, _)| weight(*op) as u128)
+            .collect::<Vec<_>>();
+        let total: u128 = weights.iter().sum();
+        if total == 0 {
+            return Err(arbitrary::Error::EmptyChoose.into());
+        }
+
+        let mut choice = self.u.int_in_range(0..=total - 1)?;
+        for (signature, weight) in OPCODE_SIGNATURES.iter().zip(weights) {
+            if choice < weight {
+                return Ok(signature);
+            }
+            choice -= weight;
+        }
+        unreachable!()
+    }
+
     /// Fills the current block with random instructions
     fn generate_instructions(&mut self, builder: &mut FunctionBuilder) -> Result<()> {
         for _ in 0..self.param(&self.config.instructions_per_block)? {
-            let (op, args, rets) = self.u.choose(&OPCODE_SIGNATURES)?;
+            let (op, args, rets) = self.choose_opcode_signature()?;
 
             // We filter out instructions that aren't supported by the target at this point instead
             // of building a single vector of valid instructions at the beginning of function
@@ -1722,6 +1759,101 @@ where
             inserter(self, builder, *op, &args, &rets)?;
         }
 
+        for _ in 0..self.param(&self.config.stack_roundtrips_per_block)? {
+            self.insert_stack_roundtrip(builder)?;
+        }
+
+        for _ in 0..self.param(&self.config.diamonds_per_block)? {
+            self.insert_diamond(builder)?;
+        }
+
+        Ok(())
+    }
+
+    /// Splits the current block into an if/else diamond:
+    ///
+    /// ```text
+    ///          brif
+    ///        /      \
+    ///     then      else
+    ///        \      /
+    ///    merge(value)
+    /// ```
+    ///
+    /// Both arms are only reachable through the `brif`, and both jump to the merge block, so
+    /// the CFG stays valid. Instruction generation continues in the merge block.
+    fn insert_diamond(&mut self, builder: &mut FunctionBuilder) -> Result<()> {
+        let cond_ty = *self.u.choose(&[I8, I16, I32, I64, I128][..])?;
+        let ty = self.u._type(self.isa.triple().architecture)?;
+
+        let then_block = builder.create_block();
+        let else_block = builder.create_block();
+        let merge_block = builder.create_block();
+        let merge_param = builder.append_block_param(merge_block, ty);
+
+        let cond = builder.use_var(self.get_variable_of_type(cond_ty)?);
+        builder.ins().brif(cond, then_block, &[], else_block, &[]);
+
+        for arm in [then_block, else_block] {
+            builder.switch_to_block(arm);
+            let mut val = builder.use_var(self.get_variable_of_type(ty)?);
+            let select_valid =
+                valid_for_target(self.isa.triple(), Opcode::Select, &[cond_ty, ty, ty], &[ty]);
+            if select_valid && bool::arbitrary(self.u)? {
+                let other = builder.use_var(self.get_variable_of_type(ty)?);
+                let select_cond = builder.use_var(self.get_variable_of_type(cond_ty)?);
+                val = builder.ins().select(select_cond, val, other);
+            }
+            builder.ins().jump(merge_block, &[val]);
+        }
+
+        builder.switch_to_block(merge_block);
+        let dst = self.get_variable_of_type(ty)?;
+        builder.def_var(dst, merge_param);
+
+        Ok(())
+    }
+
+    /// Stores a variable into a stack slot and reloads it into a variable of the same type,
+    /// so that the reloaded value feeds into the instructions generated after it.
+    fn insert_stack_roundtrip(&mut self, builder: &mut FunctionBuilder) -> Result<()> {
+        // Only pick types that can be stored on this target and that fit in at least one of
+        // the slots, and skip the round-trip altogether if there are none.
+        let triple = self.isa.triple();
+        let max_slot_size = self
+            .resources
+            .stack_slots
+            .iter()
+            .map(|&(_slot, size)| size)
+            .max()
+            .unwrap_or(0);
+        let types = types_for_architecture(triple.architecture)
+            .iter()
+            .copied()
+            .filter(|&ty| {
+                ty.bytes() <= max_slot_size
+                    && valid_for_target(triple, Opcode::StackStore, &[ty], &[])
+                    && valid_for_target(triple, Opcode::StackLoad, &[], &[ty])
+            })
+            .collect::<Vec<_>>();
+        if types.is_empty() {
+            return Ok(());
+        }
+        let ty = *self.u.choose(&types)?;
+
+        let type_size = ty.bytes();
+        let (slot, slot_size) = self.stack_slot_with_size(type_size)?;
+        // stack_slot_with_size guarantees that slot_size >= type_size
+        let offset = self.u.int_in_range(0..=(slot_size - type_size))? as i32;
+
+        let src = self.get_variable_of_type(ty)?;
+        let val = builder.use_var(src);
+        builder.ins().stack_store(val, slot, offset);
+
+        let reloaded = builder.ins().stack_load(ty, slot, offset);
+        let dst = self.get_variable_of_type(ty)?;
+        builder.def_var(dst, reloaded);
+
         Ok(())
     }
 
@@ -2109,3 +2241,85 @@ where
         Ok(func)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::OpcodeFamily;
+    use crate::tests::for_each_generated;
+
+    fn opcodes(func: &Function) -> impl Iterator<Item = Opcode> + '_ {
+        func.layout
+            .blocks()
+            .flat_map(|b| func.layout.block_insts(b))
+            .map(|inst| func.dfg.insts[inst].opcode())
+    }
+
+    #[test]
+    fn stack_roundtrips_pass_verifier() {
+        let config = Config {
+            static_stack_slots_per_function: 1..=8,
+            static_stack_slot_size: 16..=128,
+            stack_roundtrips_per_block: 1..=4,
+            ..Config::default()
+        };
+        for_each_generated(&config, 1000, |_, func| {
+            assert!(opcodes(&func).any(|op| op == Opcode::StackLoad));
+        });
+    }
+
+    #[test]
+    fn diamonds_pass_verifier() {
+        let config = Config {
+            // With a single block, every `brif` comes from a diamond.
+            blocks_per_function: 0..=0,
+            diamonds_per_block: 1..=4,
+            ..Config::default()
+        };
+        for_each_generated(&config, 1000, |_, func| {
+            assert!(func.layout.blocks().count() >= 4);
+            assert!(opcodes(&func).any(|op| op == Opcode::Brif));
+        });
+    }
+
+    #[test]
+    fn opcode_family_weights_dominate() {
+        let config = Config {
+            blocks_per_function: 0..=0,
+            instructions_per_block: 1..=8,
+            static_stack_slots_per_function: 0..=0,
+            stack_roundtrips_per_block: 0..=0,
+            diamonds_per_block: 0..=0,
+            opcode_family_weights: [(OpcodeFamily::FloatConversion, usize::MAX)]
+                .into_iter()
+                .collect(),
+            ..Config::default()
+        };
+        let mut family = 0;
+        let mut other = 0;
+        for_each_generated(&config, 1000, |_, func| {
+            for opcode in opcodes(&func) {
+                // Skip the constants that initialize variables and the terminator.
+                if opcode.is_terminator()
+                    || matches!(
+                        opcode,
+                        Opcode::Iconst
+                            | Opcode::F32const
+                            | Opcode::F64const
+                            | Opcode::Vconst
+                            | Opcode::Iconcat
+                    )
+                {
+                    continue;
+                }
+                if OpcodeFamily::FloatConversion.opcodes().contains(&opcode) {
+                    family += 1;
+                } else {
+                    other += 1;
+                }
+            }
+        });
+        assert!(family > 0);
+        assert!(family > 10 * other, "{family} conversions, {other} others");
+    }
+}
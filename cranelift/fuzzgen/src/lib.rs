@@ -21,10 +21,50 @@ mod function_generator;
 mod passes;
 mod print;
 
+pub use config::OpcodeFamily;
+pub use passes::minimize;
 pub use print::PrintableTestCase;
 
 pub type TestCaseInput = Vec<DataValue>;
 
+/// Compares two values of type `ty`, considering any two NaNs equal regardless
+/// of their sign and payload.
+///
+/// Float vectors are compared lane by lane, every other value is compared
+/// bitwise. This is used to compare results when NaN canonicalization is
+/// disabled, see `Config::canonicalize_nans`.
+pub fn nan_insensitive_eq(ty: Type, l: &DataValue, r: &DataValue) -> bool {
+    fn is_nan(v: &DataValue) -> bool {
+        match v {
+            DataValue::F32(f) => f.is_nan(),
+            DataValue::F64(f) => f.is_nan(),
+            _ => false,
+        }
+    }
+
+    let lane_ty = ty.lane_type();
+    if !lane_ty.is_float() {
+        return l.bitwise_eq(r);
+    }
+    if !ty.is_vector() {
+        return (is_nan(l) && is_nan(r)) || l.bitwise_eq(r);
+    }
+
+    let size = ty.bytes() as usize;
+    let lane_size = lane_ty.bytes() as usize;
+    let (mut lbytes, mut rbytes) = (vec![0; size], vec![0; size]);
+    l.write_to_slice_ne(&mut lbytes);
+    r.write_to_slice_ne(&mut rbytes);
+    lbytes
+        .chunks(lane_size)
+        .zip(rbytes.chunks(lane_size))
+        .all(|(l, r)| {
+            let l = DataValue::read_from_slice_ne(l, lane_ty);
+            let r = DataValue::read_from_slice_ne(r, lane_ty);
+            (is_nan(&l) && is_nan(&r)) || l.bitwise_eq(&r)
+        })
+}
+
 pub enum IsaFlagGen {
     /// When generating ISA flags, ensure that they are all supported by
     /// the current host.
@@ -91,6 +131,35 @@ where
         Ok(inputs)
     }
 
+    /// Builds the ISA that the function passes run against.
+    fn pass_isa(&self) -> OwnedTargetIsa {
+        // Unless `pass_target` says otherwise, assume that we are generating this
+        // function for the current ISA.
+        // We disable the verifier here, since if it fails it prevents a test case from
+        // being generated and formatted by `cargo fuzz fmt`.
+        // We run the verifier before compiling the code, so it always gets verified.
+        let flags = settings::Flags::new({
+            let mut builder = settings::builder();
+            builder.set("enable_verifier", "false").unwrap();
+            builder
+        });
+
+        // If a specific target was requested for the passes use it, but only if this
+        // build of Cranelift actually includes a backend for it.
+        let isa_builder = self
+            .config
+            .pass_target
+            .clone()
+            .and_then(|triple| isa::lookup(triple).ok())
+            .unwrap_or_else(|| {
+                builder_with_options(false)
+                    .expect("Unable to build a TargetIsa for the current host")
+            });
+        isa_builder
+            .finish(flags)
+            .expect("Failed to build TargetISA")
+    }
+
     fn run_func_passes(&mut self, func: Function) -> Result<Function> {
         // Do a NaN Canonicalization pass on the generated function.
         //
@@ -106,25 +175,18 @@ where
         //
         // This is something that we can enable via flags for the compiled version, however
         // the interpreter won't get that version, so call that pass manually here.
+        //
+        // Campaigns looking for NaN-handling bugs can turn this off through
+        // `Config::canonicalize_nans`, in which case results are compared with
+        // `nan_insensitive_eq` instead.
 
         let mut ctx = Context::for_function(func);
-        // Assume that we are generating this function for the current ISA.
-        // We disable the verifier here, since if it fails it prevents a test case from
-        // being generated and formatted by `cargo fuzz fmt`.
-        // We run the verifier before compiling the code, so it always gets verified.
-        let flags = settings::Flags::new({
-            let mut builder = settings::builder();
-            builder.set("enable_verifier", "false").unwrap();
-            builder
-        });
+        let isa = self.pass_isa();
 
-        let isa = builder_with_options(false)
-            .expect("Unable to build a TargetIsa for the current host")
-            .finish(flags)
-            .expect("Failed to build TargetISA");
-
-        ctx.canonicalize_nans(isa.as_ref())
-            .expect("Failed NaN canonicalization pass");
+        if self.config.canonicalize_nans {
+            ctx.canonicalize_nans(isa.as_ref())
+                .expect("Failed NaN canonicalization pass");
+        }
 
         // Run the int_divz pass
         //
@@ -297,3 +359,179 @@ where
         Ok(())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::OpcodeFamily;
+    use cranelift::codegen::ir::immediates::{Ieee32, Ieee64};
+    use cranelift::codegen::verify_function;
+
+    /// Generates a function for each of `n` pseudo-random inputs, calling `f` with the index
+    /// of the input and the generated function for every input that produces one. The inputs
+    /// come from a fixed xorshift sequence so that tests using this are deterministic.
+    pub(crate) fn for_each_generated(
+        config: &Config,
+        n: usize,
+        mut f: impl FnMut(usize, Function),
+    ) {
+        let isa = builder_with_options(false)
+            .unwrap()
+            .finish(settings::Flags::new(settings::builder()))
+            .unwrap();
+
+        let mut state = 0x2545_f491_4f6c_dd1d_u64;
+        let mut generated = 0;
+        for i in 0..n {
+            let bytes = (0..4096)
+                .map(|_| {
+                    state ^= state << 13;
+                    state ^= state >> 7;
+                    state ^= state << 17;
+                    state as u8
+                })
+                .collect::<Vec<_>>();
+            let mut u = Unstructured::new(&bytes);
+            let func = match FunctionGenerator::new(
+                &mut u,
+                config,
+                isa.clone(),
+                UserFuncName::user(1, 0),
+                Signature::new(isa::CallConv::Fast),
+                vec![],
+                vec![],
+            )
+            .generate()
+            {
+                Ok(func) => func,
+                // Not every input produces a valid function.
+                Err(_) => continue,
+            };
+            verify_function(&func, isa.as_ref()).unwrap();
+            f(i, func);
+            generated += 1;
+        }
+        assert!(generated > 0);
+    }
+
+    fn inst_count(func: &Function) -> usize {
+        func.layout
+            .blocks()
+            .map(|b| func.layout.block_insts(b).count())
+            .sum()
+    }
+
+    #[test]
+    fn nan_insensitive_eq_ignores_payloads() {
+        let qnan = DataValue::F32(Ieee32::with_bits(0x7fc0_0000));
+        let payload = DataValue::F32(Ieee32::with_bits(0xffc0_1234));
+        assert!(!qnan.bitwise_eq(&payload));
+        assert!(nan_insensitive_eq(F32, &qnan, &payload));
+        assert!(!nan_insensitive_eq(
+            F32,
+            &qnan,
+            &DataValue::F32(Ieee32::with_float(1.0))
+        ));
+        assert!(!nan_insensitive_eq(
+            F64,
+            &DataValue::F64(Ieee64::with_float(0.0)),
+            &DataValue::F64(Ieee64::with_float(-0.0))
+        ));
+
+        // Integers are still compared bitwise, even if they happen to look like a NaN.
+        assert!(!nan_insensitive_eq(
+            I32,
+            &DataValue::I32(0x7fc0_0000),
+            &DataValue::I32(0xffc0_1234_u32 as i32)
+        ));
+
+        // Float vectors are compared lane by lane.
+        let lanes = |l: [u32; 4]| {
+            let mut bytes = [0; 16];
+            for (chunk, lane) in bytes.chunks_mut(4).zip(l) {
+                chunk.copy_from_slice(&lane.to_ne_bytes());
+            }
+            DataValue::V128(bytes)
+        };
+        let one = 1.0f32.to_bits();
+        let l = lanes([0x7fc0_0000, one, 0, 0x7f80_0001]);
+        let r = lanes([0xffc0_1234, one, 0, 0x7fc0_0000]);
+        assert!(nan_insensitive_eq(F32X4, &l, &r));
+        assert!(!nan_insensitive_eq(I32X4, &l, &r));
+        let r = lanes([0xffc0_1234, one, 1, 0x7fc0_0000]);
+        assert!(!nan_insensitive_eq(F32X4, &l, &r));
+    }
+
+    #[test]
+    fn disabled_nan_canonicalization_generates_valid_ir() {
+        let config = |canonicalize_nans| Config {
+            blocks_per_function: 0..=0,
+            instructions_per_block: 1..=16,
+            opcode_family_weights: [(OpcodeFamily::FloatArithmetic, 1000)]
+                .into_iter()
+                .collect(),
+            canonicalize_nans,
+            ..Config::default()
+        };
+
+        let mut skipped_canonicalization = false;
+        for_each_generated(&config(false), 1000, |_, func| {
+            // Run the passes over the same function with both configurations, so the only
+            // difference between them is the canonicalization sequences.
+            let run_passes = |canonicalize_nans| {
+                let mut u = Unstructured::new(&[]);
+                let mut gen = FuzzGen::new(&mut u);
+                gen.config = config(canonicalize_nans);
+                gen.run_func_passes(func.clone()).unwrap()
+            };
+            let plain = run_passes(false);
+            let canonicalized = run_passes(true);
+            assert!(inst_count(&canonicalized) >= inst_count(&plain));
+            skipped_canonicalization |= inst_count(&canonicalized) > inst_count(&plain);
+        });
+        assert!(skipped_canonicalization);
+    }
+
+    #[test]
+    fn passes_run_against_pass_target() {
+        let target: target_lexicon::Triple = if cfg!(target_arch = "x86_64") {
+            "aarch64-unknown-linux-gnu"
+        } else {
+            "x86_64-unknown-linux-gnu"
+        }
+        .parse()
+        .unwrap();
+        let config = |canonicalize_nans| Config {
+            blocks_per_function: 0..=0,
+            instructions_per_block: 1..=16,
+            opcode_family_weights: [
+                (OpcodeFamily::FloatArithmetic, 1000),
+                (OpcodeFamily::IntArithmetic, 1000),
+            ]
+            .into_iter()
+            .collect(),
+            pass_target: Some(target.clone()),
+            canonicalize_nans,
+            ..Config::default()
+        };
+
+        let mut u = Unstructured::new(&[]);
+        let mut gen = FuzzGen::new(&mut u);
+        gen.config = config(true);
+        let isa = gen.pass_isa();
+        assert_eq!(isa.triple(), &target);
+
+        let mut canonicalized = false;
+        for_each_generated(&config(true), 1000, |_, func| {
+            let mut u = Unstructured::new(&[]);
+            let mut gen = FuzzGen::new(&mut u);
+            gen.config = config(false);
+            let plain = gen.run_func_passes(func.clone()).unwrap();
+            gen.config = config(true);
+            let passed = gen.run_func_passes(func).unwrap();
+            verify_function(&passed, isa.as_ref()).unwrap();
+            canonicalized |= inst_count(&passed) > inst_count(&plain);
+        });
+        assert!(canonicalized);
+    }
+}
@@ -0,0 +1,190 @@
+use cranelift::codegen::ir::{types::*, Block, Function, Inst, Opcode, Value};
+use cranelift::codegen::{settings, verify_function};
+use cranelift::prelude::InstBuilder;
+use std::collections::HashSet;
+
+/// Shrinks a failing function down to a smaller one that still fails.
+///
+/// `fails` is called with each candidate and should return true if the candidate still
+/// reproduces the failure that `func` triggers. Candidates are produced by:
+///
+/// * Removing instructions whose results are unused
+/// * Replacing single-result instructions with a constant
+/// * Replacing conditional branches and jump tables with a jump to one of their targets
+/// * Removing blocks
+///
+/// Candidates that don't pass the verifier are discarded without calling `fails`. This is
+/// repeated until no candidate fails anymore, and the smallest failing function is returned.
+pub fn minimize(func: &Function, mut fails: impl FnMut(&Function) -> bool) -> Function {
+    let flags = settings::Flags::new(settings::builder());
+    let mut func = func.clone();
+    'outer: loop {
+        for candidate in candidates(&func) {
+            if verify_function(&candidate, &flags).is_ok() && fails(&candidate) {
+                func = candidate;
+                continue 'outer;
+            }
+        }
+        return func;
+    }
+}
+
+/// Generates every function that is one reduction step away from `func`.
+fn candidates(func: &Function) -> Vec<Function> {
+    let used = used_values(func);
+    let mut candidates = Vec::new();
+
+    for block in func.layout.blocks() {
+        for inst in func.layout.block_insts(block) {
+            if func.dfg.insts[inst].opcode().is_terminator() {
+                candidates.extend(replace_branch(func, inst));
+                continue;
+            }
+
+            let results = func.dfg.inst_results(inst);
+            if results.iter().all(|v| !used.contains(v)) {
+                let mut candidate = func.clone();
+                candidate.layout.remove_inst(inst);
+                candidates.push(candidate);
+            } else if let [result] = results {
+                candidates.extend(replace_with_const(func, inst, *result));
+            }
+        }
+
+        if Some(block) != func.layout.entry_block() {
+            let mut candidate = func.clone();
+            while let Some(inst) = candidate.layout.last_inst(block) {
+                candidate.layout.remove_inst(inst);
+            }
+            candidate.layout.remove_block(block);
+            candidates.push(candidate);
+        }
+    }
+
+    candidates
+}
+
+/// Collects all values used as instruction arguments or block call arguments.
+fn used_values(func: &Function) -> HashSet<Value> {
+    func.layout
+        .blocks()
+        .flat_map(|block| func.layout.block_insts(block))
+        .flat_map(|inst| func.dfg.inst_values(inst))
+        .map(|v| func.dfg.resolve_aliases(v))
+        .collect()
+}
+
+/// Replaces `inst`, which defines `result`, with a constant of the same type.
+fn replace_with_const(func: &Function, inst: Inst, result: Value) -> Option<Function> {
+    let opcode = func.dfg.insts[inst].opcode();
+    if matches!(
+        opcode,
+        Opcode::Iconst | Opcode::F32const | Opcode::F64const | Opcode::Vconst
+    ) {
+        return None;
+    }
+
+    let ty = func.dfg.value_type(result);
+    let mut candidate = func.clone();
+    if ty == F32 {
+        candidate.dfg.replace(inst).f32const(0.0);
+    } else if ty == F64 {
+        candidate.dfg.replace(inst).f64const(0.0);
+    } else if ty.is_vector() {
+        let zero = vec![0; ty.bytes() as usize].into();
+        let zero = candidate.dfg.constants.insert(zero);
+        candidate.dfg.replace(inst).vconst(ty, zero);
+    } else if ty.is_int() && ty != I128 {
+        candidate.dfg.replace(inst).iconst(ty, 0);
+    } else {
+        return None;
+    }
+    Some(candidate)
+}
+
+/// Replaces the branch `inst` with a jump to each of its destinations.
+fn replace_branch(func: &Function, inst: Inst) -> Vec<Function> {
+    if !matches!(
+        func.dfg.insts[inst].opcode(),
+        Opcode::Brif | Opcode::BrTable
+    ) {
+        return Vec::new();
+    }
+
+    let mut seen = HashSet::new();
+    let mut candidates = Vec::new();
+    for call in func.dfg.insts[inst].branch_destination(&func.dfg.jump_tables) {
+        let block: Block = call.block(&func.dfg.value_lists);
+        let args = call.args_slice(&func.dfg.value_lists).to_vec();
+        if !seen.insert((block, args.clone())) {
+            continue;
+        }
+        let mut candidate = func.clone();
+        candidate.dfg.replace(inst).jump(block, &args);
+        candidates.push(candidate);
+    }
+    candidates
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use cranelift::codegen::ir::{AbiParam, Signature, UserFuncName};
+    use cranelift::codegen::isa::CallConv;
+    use cranelift::frontend::{FunctionBuilder, FunctionBuilderContext};
+
+    fn has_opcode(func: &Function, opcode: Opcode) -> bool {
+        func.layout
+            .blocks()
+            .flat_map(|b| func.layout.block_insts(b))
+            .any(|inst| func.dfg.insts[inst].opcode() == opcode)
+    }
+
+    fn inst_count(func: &Function) -> usize {
+        func.layout
+            .blocks()
+            .map(|b| func.layout.block_insts(b).count())
+            .sum()
+    }
+
+    #[test]
+    fn removes_dead_instructions() {
+        let mut sig = Signature::new(CallConv::SystemV);
+        sig.params.push(AbiParam::new(I32));
+        sig.params.push(AbiParam::new(I32));
+        sig.returns.push(AbiParam::new(I32));
+        let mut func = Function::with_name_signature(UserFuncName::user(0, 0), sig);
+
+        let mut ctx = FunctionBuilderContext::new();
+        let mut builder = FunctionBuilder::new(&mut func, &mut ctx);
+        let entry = builder.create_block();
+        let then_block = builder.create_block();
+        let merge = builder.create_block();
+        builder.append_block_param(merge, I32);
+        builder.append_block_params_for_function_params(entry);
+        builder.switch_to_block(entry);
+        let a = builder.block_params(entry)[0];
+        let b = builder.block_params(entry)[1];
+        let _dead = builder.ins().iadd(a, b);
+        let _dead = builder.ins().imul(a, a);
+        let quotient = builder.ins().udiv(a, b);
+        builder.ins().brif(b, then_block, &[], merge, &[quotient]);
+        builder.switch_to_block(then_block);
+        let sum = builder.ins().iadd(quotient, a);
+        builder.ins().jump(merge, &[sum]);
+        builder.switch_to_block(merge);
+        let ret = builder.block_params(merge)[0];
+        builder.ins().return_(&[ret]);
+        builder.seal_all_blocks();
+        builder.finalize();
+
+        // Pretend that any function containing a `udiv` reproduces the failure.
+        let minimized = minimize(&func, |f| has_opcode(f, Opcode::Udiv));
+
+        assert!(has_opcode(&minimized, Opcode::Udiv));
+        assert!(!has_opcode(&minimized, Opcode::Imul));
+        assert!(!has_opcode(&minimized, Opcode::Brif));
+        assert!(inst_count(&minimized) < inst_count(&func));
+        verify_function(&minimized, &settings::Flags::new(settings::builder())).unwrap();
+    }
+}
@@ -1,5 +1,7 @@
 mod fcvt;
 mod int_divz;
+mod minimize;
 
 pub use fcvt::do_fcvt_trap_pass;
 pub use int_divz::do_int_divz_pass;
+pub use minimize::minimize;
@@ -3,10 +3,13 @@ use cranelift::codegen::ir::Function;
 use cranelift::prelude::settings::{self, SettingKind};
 use cranelift::prelude::*;
 use std::fmt;
+use std::io;
+use std::path::Path;
+use target_lexicon::Architecture;
 
 use crate::TestCaseInput;
 
-#[derive(Debug)]
+#[derive(Debug, Clone, Copy)]
 enum TestCaseKind {
     Compile,
     Run,
@@ -19,6 +22,13 @@ pub struct PrintableTestCase<'a> {
     functions: &'a [Function],
     // Only applicable for run test cases
     inputs: &'a [TestCaseInput],
+    // Targets to declare in the header, defaults to the target of `isa`
+    targets: &'a [Architecture],
+    // Raw fuzzer input that generated this test case, printed as a comment
+    input_bytes: &'a [u8],
+    // Whether run test cases print `; run:` lines comparing against placeholder
+    // results, rather than `; print:` lines without any expectation
+    placeholder_results: bool,
 }
 
 impl<'a> PrintableTestCase<'a> {
@@ -29,6 +39,9 @@ impl<'a> PrintableTestCase<'a> {
             isa,
             functions,
             inputs: &[],
+            targets: &[],
+            input_bytes: &[],
+            placeholder_results: true,
         }
     }
 
@@ -45,13 +58,45 @@ impl<'a> PrintableTestCase<'a> {
             isa,
             functions,
             inputs,
+            targets: &[],
+            input_bytes: &[],
+            placeholder_results: true,
         }
     }
 
+    /// Declares `targets` in the test case header instead of the target of the ISA
+    /// that the test case was generated for.
+    ///
+    /// ISA flags are only printed for the target that matches that ISA.
+    pub fn with_targets(mut self, targets: &'a [Architecture]) -> Self {
+        self.targets = targets;
+        self
+    }
+
+    /// Records the raw fuzzer input that generated this test case. It is printed as a
+    /// hex-encoded comment so that the exact case can be regenerated later.
+    pub fn with_input_bytes(mut self, input_bytes: &'a [u8]) -> Self {
+        self.input_bytes = input_bytes;
+        self
+    }
+
     /// Returns the main function of this test case.
     pub fn main(&self) -> &Function {
         &self.functions[0]
     }
+
+    /// Writes this test case to `path` as a self-contained `.clif` file that can be
+    /// replayed with `clif-util test`.
+    ///
+    /// The expected results of the inputs aren't known, so they are invoked through
+    /// `; print:` commands instead of being compared against placeholder values.
+    pub fn write_reproducer(&self, path: impl AsRef<Path>) -> io::Result<()> {
+        let reproducer = Self {
+            placeholder_results: false,
+            ..*self
+        };
+        std::fs::write(path, format!("{:?}", reproducer))
+    }
 }
 
 impl<'a> fmt::Debug for PrintableTestCase<'a> {
@@ -68,11 +113,27 @@ impl<'a> fmt::Debug for PrintableTestCase<'a> {
             }
         };
 
+        if !self.input_bytes.is_empty() {
+            writeln!(f, "; fuzz input ({} bytes):", self.input_bytes.len())?;
+            for chunk in self.input_bytes.chunks(32) {
+                write!(f, ";   ")?;
+                for byte in chunk {
+                    write!(f, "{:02x}", byte)?;
+                }
+                writeln!(f)?;
+            }
+        }
+
         write_non_default_flags(f, self.isa.flags())?;
 
-        write!(f, "target {} ", self.isa.triple().architecture)?;
-        write_non_default_isa_flags(f, &self.isa)?;
-        write!(f, "\n\n")?;
+        if self.targets.is_empty() {
+            write_target(f, self.isa.triple().architecture, &self.isa)?;
+        } else {
+            for &target in self.targets {
+                write_target(f, target, &self.isa)?;
+            }
+        }
+        writeln!(f)?;
 
         // Print the functions backwards, so that the main function is printed last
         // and near the test inputs for run test cases.
@@ -80,11 +141,22 @@ impl<'a> fmt::Debug for PrintableTestCase<'a> {
             writeln!(f, "{}\n", func)?;
         }
 
-        if !self.inputs.is_empty() {
+        if !self.inputs.is_empty() && self.placeholder_results {
             writeln!(f, "; Note: the results in the below test cases are simply a placeholder and probably will be wrong\n")?;
         }
 
         for input in self.inputs.iter() {
+            let args = input
+                .iter()
+                .map(|val| format!("{}", val))
+                .collect::<Vec<_>>()
+                .join(", ");
+
+            if !self.placeholder_results {
+                writeln!(f, "; print: {}({})", self.main().name, args)?;
+                continue;
+            }
+
             // TODO: We don't know the expected outputs, maybe we can run the interpreter
             // here to figure them out? Should work, however we need to be careful to catch
             // panics in case its the interpreter that is failing.
@@ -104,12 +176,6 @@ impl<'a> fmt::Debug for PrintableTestCase<'a> {
                 _ => format!(" == [{}]", placeholder_output),
             };
 
-            let args = input
-                .iter()
-                .map(|val| format!("{}", val))
-                .collect::<Vec<_>>()
-                .join(", ");
-
             writeln!(f, "; run: {}({}){}", self.main().name, args, test_condition)?;
         }
 
@@ -131,6 +197,19 @@ fn write_non_default_flags(f: &mut fmt::Formatter<'_>, flags: &settings::Flags)
     Ok(())
 }
 
+/// Print a `target` declaration, including the non default ISA flags if `arch` matches `isa`.
+fn write_target(
+    f: &mut fmt::Formatter<'_>,
+    arch: Architecture,
+    isa: &isa::OwnedTargetIsa,
+) -> fmt::Result {
+    write!(f, "target {} ", arch)?;
+    if arch == isa.triple().architecture {
+        write_non_default_isa_flags(f, isa)?;
+    }
+    writeln!(f)
+}
+
 /// Print non default ISA flags in a single line, as used in `target` declarations.
 fn write_non_default_isa_flags(
     f: &mut fmt::Formatter<'_>,
@@ -160,3 +239,92 @@ fn write_non_default_isa_flags(
 
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use target_lexicon::Riscv64Architecture;
+
+    #[test]
+    fn prints_only_requested_targets() {
+        let isa = cranelift_native::builder()
+            .unwrap()
+            .finish(settings::Flags::new(settings::builder()))
+            .unwrap();
+        let targets = [Architecture::Riscv64(Riscv64Architecture::Riscv64)];
+        let printed = format!(
+            "{:?}",
+            PrintableTestCase::compile(&isa, &[]).with_targets(&targets)
+        );
+
+        let target_lines = printed
+            .lines()
+            .filter(|l| l.starts_with("target "))
+            .map(|l| l.trim_end())
+            .collect::<Vec<_>>();
+        assert_eq!(target_lines, ["target riscv64"]);
+    }
+
+    #[test]
+    fn reproducer_round_trips() {
+        let mut flags = settings::builder();
+        flags.set("opt_level", "speed").unwrap();
+        let isa = cranelift_native::builder()
+            .unwrap()
+            .finish(settings::Flags::new(flags))
+            .unwrap();
+        let functions = cranelift_reader::parse_functions(
+            "function %main(i32) -> i32 {
+             block0(v0: i32):
+                 v1 = iadd_imm v0, 1
+                 return v1
+             }",
+        )
+        .unwrap();
+        let inputs = [vec![DataValue::I32(41)]];
+        let input_bytes = [0xde, 0xad, 0xbe, 0xef];
+
+        let path = std::env::temp_dir().join(format!(
+            "cranelift-fuzzgen-reproducer-{}.clif",
+            std::process::id()
+        ));
+        PrintableTestCase::run(&isa, &functions, &inputs)
+            .with_input_bytes(&input_bytes)
+            .write_reproducer(&path)
+            .unwrap();
+        let text = std::fs::read_to_string(&path).unwrap();
+        std::fs::remove_file(&path).unwrap();
+
+        let parsed =
+            cranelift_reader::parse_test(&text, cranelift_reader::ParseOptions::default()).unwrap();
+        let parsed_isa = match &parsed.isa_spec {
+            cranelift_reader::IsaSpec::Some(isas) => &isas[0],
+            cranelift_reader::IsaSpec::None(_) => panic!("no target in reproducer"),
+        };
+        assert_eq!(parsed_isa.flags().to_string(), isa.flags().to_string());
+        assert_eq!(parsed.functions.len(), 1);
+        assert_eq!(parsed.functions[0].0.to_string(), functions[0].to_string());
+        assert!(parsed
+            .preamble_comments
+            .iter()
+            .any(|c| c.text.trim_start_matches(';').trim() == "deadbeef"));
+
+        // The expected result is unknown, so the input is only invoked.
+        let commands = text
+            .lines()
+            .filter(|l| l.starts_with("; run") || l.starts_with("; print"))
+            .map(|l| {
+                cranelift_reader::parse_run_command(l, &functions[0].signature)
+                    .unwrap()
+                    .unwrap()
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(commands.len(), 1);
+        assert!(matches!(
+            commands[0],
+            cranelift_reader::RunCommand::Print(_)
+        ));
+        assert!(text.contains("; print: %main(41)\n"));
+        assert!(!text.contains("=="));
+    }
+}
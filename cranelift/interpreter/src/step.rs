@@ -1271,6 +1271,8 @@ where
             // actually need to emit a fence here.
             ControlFlow::Continue
         }
+        // Prefetching is only a performance hint.
+        Opcode::Prefetch => ControlFlow::Continue,
         Opcode::SqmulRoundSat => {
             let lane_type = ctrl_ty.lane_type();
             let double_width = ctrl_ty.double_width().unwrap().lane_type();
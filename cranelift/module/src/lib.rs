@@ -69,6 +69,10 @@ pub fn default_libcall_names() -> Box<dyn Fn(ir::LibCall) -> String + Send + Syn
         ir::LibCall::Memset => "memset".to_owned(),
         ir::LibCall::Memmove => "memmove".to_owned(),
         ir::LibCall::Memcmp => "memcmp".to_owned(),
+        ir::LibCall::UdivI128 => "__udivti3".to_owned(),
+        ir::LibCall::SdivI128 => "__divti3".to_owned(),
+        ir::LibCall::UremI128 => "__umodti3".to_owned(),
+        ir::LibCall::SremI128 => "__modti3".to_owned(),
 
         ir::LibCall::ElfTlsGetAddr => "__tls_get_addr".to_owned(),
         ir::LibCall::ElfTlsGetOffset => "__tls_get_offset".to_owned(),
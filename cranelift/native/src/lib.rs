@@ -24,7 +24,7 @@
 )]
 
 use cranelift_codegen::isa;
-use cranelift_codegen::settings::Configurable;
+use cranelift_codegen::settings::{Configurable, SetResult};
 use target_lexicon::Triple;
 
 #[cfg(all(target_arch = "riscv64", target_os = "linux"))]
@@ -55,6 +55,47 @@ pub fn builder_with_options(infer_native_flags: bool) -> Result<isa::Builder, &'
     Ok(isa_builder)
 }
 
+/// Return an `isa` builder configured for the current host
+/// machine, or an `Err` describing why the host machine is not
+/// supported in the current configuration.
+///
+/// Only the detected features whose setting names (e.g. `"has_avx2"`)
+/// are contained in `allow` are enabled. This is useful to simulate a
+/// less capable CPU than the host, e.g. to test codegen fallbacks.
+pub fn builder_with_features(allow: &[&str]) -> Result<isa::Builder, &'static str> {
+    let mut isa_builder = builder_with_options(false)?;
+    infer_native_flags(&mut AllowList {
+        builder: &mut isa_builder,
+        allow,
+    })?;
+    Ok(isa_builder)
+}
+
+/// A `Configurable` that forwards only the settings in `allow` to `builder`,
+/// silently ignoring all others.
+struct AllowList<'a> {
+    builder: &'a mut dyn Configurable,
+    allow: &'a [&'a str],
+}
+
+impl Configurable for AllowList<'_> {
+    fn set(&mut self, name: &str, value: &str) -> SetResult<()> {
+        if self.allow.contains(&name) {
+            self.builder.set(name, value)
+        } else {
+            Ok(())
+        }
+    }
+
+    fn enable(&mut self, name: &str) -> SetResult<()> {
+        if self.allow.contains(&name) {
+            self.builder.enable(name)
+        } else {
+            Ok(())
+        }
+    }
+}
+
 /// Return an `isa` builder configured for the current host
 /// machine, or `Err(())` if the host machine is not supported
 /// in the current configuration.
@@ -108,6 +149,12 @@ pub fn infer_native_flags(isa_builder: &mut dyn Configurable) -> Result<(), &'st
         if std::is_x86_feature_detected!("avx512f") {
             isa_builder.enable("has_avx512f").unwrap();
         }
+        if std::is_x86_feature_detected!("avx512bw") {
+            isa_builder.enable("has_avx512bw").unwrap();
+        }
+        if std::is_x86_feature_detected!("avx512cd") {
+            isa_builder.enable("has_avx512cd").unwrap();
+        }
         if std::is_x86_feature_detected!("avx512vl") {
             isa_builder.enable("has_avx512vl").unwrap();
         }
@@ -125,6 +172,14 @@ pub fn infer_native_flags(isa_builder: &mut dyn Configurable) -> Result<(), &'st
             isa_builder.enable("has_lse").unwrap();
         }
 
+        if std::arch::is_aarch64_feature_detected!("fp16") {
+            isa_builder.enable("has_fp16").unwrap();
+        }
+
+        if std::arch::is_aarch64_feature_detected!("dotprod") {
+            isa_builder.enable("has_dotprod").unwrap();
+        }
+
         if std::arch::is_aarch64_feature_detected!("paca") {
             isa_builder.enable("has_pauth").unwrap();
         }
@@ -169,7 +224,7 @@ pub fn infer_native_flags(isa_builder: &mut dyn Configurable) -> Result<(), &'st
 
 #[cfg(test)]
 mod tests {
-    use super::builder;
+    use super::{builder, builder_with_features};
     use cranelift_codegen::isa::CallConv;
     use cranelift_codegen::settings;
 
@@ -198,6 +253,102 @@ mod tests {
             }
         }
     }
+
+    #[test]
+    #[cfg(target_arch = "x86_64")]
+    fn avx512_detection() {
+        let isa = builder()
+            .unwrap()
+            .finish(settings::Flags::new(settings::builder()))
+            .unwrap();
+        let enabled = |name: &str| {
+            isa.isa_flags()
+                .into_iter()
+                .find(|f| f.name == name)
+                .and_then(|f| f.as_bool())
+                .unwrap()
+        };
+
+        for (detected, clif) in [
+            (std::is_x86_feature_detected!("avx512f"), "has_avx512f"),
+            (std::is_x86_feature_detected!("avx512bw"), "has_avx512bw"),
+            (std::is_x86_feature_detected!("avx512cd"), "has_avx512cd"),
+        ] {
+            // Nothing to check on hosts without this feature.
+            if detected {
+                assert!(enabled(clif), "{} was detected but not enabled", clif);
+            }
+        }
+    }
+
+    #[test]
+    #[cfg(target_arch = "aarch64")]
+    fn aarch64_detection() {
+        let isa = builder()
+            .unwrap()
+            .finish(settings::Flags::new(settings::builder()))
+            .unwrap();
+        let enabled = |name: &str| {
+            isa.isa_flags()
+                .into_iter()
+                .find(|f| f.name == name)
+                .and_then(|f| f.as_bool())
+                .unwrap()
+        };
+
+        assert_eq!(
+            enabled("has_lse"),
+            std::arch::is_aarch64_feature_detected!("lse")
+        );
+        assert_eq!(
+            enabled("has_fp16"),
+            std::arch::is_aarch64_feature_detected!("fp16")
+        );
+        assert_eq!(
+            enabled("has_dotprod"),
+            std::arch::is_aarch64_feature_detected!("dotprod")
+        );
+    }
+
+    #[test]
+    #[cfg(target_arch = "x86_64")]
+    fn empty_allowlist() {
+        let isa = builder_with_features(&[])
+            .unwrap()
+            .finish(settings::Flags::new(settings::builder()))
+            .unwrap();
+
+        // Only the SSE2 baseline, which has no setting, remains.
+        for flag in isa.isa_flags() {
+            if flag.name.starts_with("has_") {
+                assert_eq!(flag.as_bool(), Some(false), "{} is enabled", flag.name);
+            }
+        }
+    }
+
+    #[test]
+    #[cfg(target_arch = "x86_64")]
+    fn allowlist_filters_detected_features() {
+        let isa = builder_with_features(&["has_sse3"])
+            .unwrap()
+            .finish(settings::Flags::new(settings::builder()))
+            .unwrap();
+
+        for flag in isa.isa_flags() {
+            if flag.name.starts_with("has_") && flag.name != "has_sse3" {
+                assert_eq!(flag.as_bool(), Some(false), "{} is enabled", flag.name);
+            }
+        }
+        let has_sse3 = isa
+            .isa_flags()
+            .into_iter()
+            .find(|f| f.name == "has_sse3")
+            .unwrap();
+        assert_eq!(
+            has_sse3.as_bool(),
+            Some(std::is_x86_feature_detected!("sse3"))
+        );
+    }
 }
 
 /// Version number of this crate.
@@ -413,11 +413,12 @@ impl CommonOptions {
         }
     }
 
+    /// Returns the optimization level to compile with.
+    ///
+    /// An explicit `--opt-level` always takes precedence. Optimizations are
+    /// on by default, so `-O` selects the same `Speed` level as no flag.
     pub fn opt_level(&self) -> wasmtime::OptLevel {
-        match (self.optimize, self.opt_level.clone()) {
-            (true, _) => wasmtime::OptLevel::Speed,
-            (false, other) => other.unwrap_or(wasmtime::OptLevel::Speed),
-        }
+        self.opt_level.clone().unwrap_or(wasmtime::OptLevel::Speed)
     }
 }
 
@@ -425,6 +426,8 @@ fn parse_opt_level(opt_level: &str) -> Result<wasmtime::OptLevel> {
     match opt_level {
         "s" => Ok(wasmtime::OptLevel::SpeedAndSize),
         "0" => Ok(wasmtime::OptLevel::None),
+        // Cranelift doesn't currently distinguish between levels 1 and 2, so
+        // both map to `Speed` until it does.
         "1" => Ok(wasmtime::OptLevel::Speed),
         "2" => Ok(wasmtime::OptLevel::Speed),
         other => bail!(
@@ -484,7 +487,15 @@ fn parse_wasm_features(features: &str) -> Result<WasmFeatures> {
 
             match values.get_mut(feature) {
                 Some(v) => *v = Some(value),
-                None => bail!("unsupported WebAssembly feature '{}'", feature),
+                None => bail!(
+                    "unsupported WebAssembly feature '{}', supported features are: {}",
+                    feature,
+                    SUPPORTED_WASM_FEATURES
+                        .iter()
+                        .map(|(name, _)| *name)
+                        .collect::<Vec<_>>()
+                        .join(", ")
+                ),
             }
         }
     }
@@ -668,6 +679,20 @@ mod test {
         Ok(())
     }
 
+    #[test]
+    fn test_unknown_feature() {
+        let err = CommonOptions::try_parse_from(vec!["foo", "--wasm-features=simd,not-a-feature"])
+            .unwrap_err()
+            .to_string();
+        assert!(
+            err.contains("unsupported WebAssembly feature 'not-a-feature'"),
+            "{err}"
+        );
+        for (name, _) in SUPPORTED_WASM_FEATURES {
+            assert!(err.contains(name), "{err}");
+        }
+    }
+
     #[test]
     fn test_multiple_features() -> Result<()> {
         let options = CommonOptions::try_parse_from(vec![
@@ -745,6 +770,40 @@ mod test {
     feature_test!(test_multi_memory_feature, multi_memory, "multi-memory");
     feature_test!(test_memory64_feature, memory64, "memory64");
 
+    #[test]
+    fn test_opt_level() -> Result<()> {
+        let opt_level = |args: &[&str]| -> Result<wasmtime::OptLevel> {
+            let options =
+                CommonOptions::try_parse_from(std::iter::once("foo").chain(args.iter().copied()))?;
+            Ok(options.opt_level())
+        };
+
+        assert_eq!(opt_level(&[])?, wasmtime::OptLevel::Speed);
+        assert_eq!(opt_level(&["-O"])?, wasmtime::OptLevel::Speed);
+        assert_eq!(opt_level(&["--opt-level", "0"])?, wasmtime::OptLevel::None);
+        assert_eq!(opt_level(&["--opt-level", "1"])?, wasmtime::OptLevel::Speed);
+        assert_eq!(opt_level(&["--opt-level", "2"])?, wasmtime::OptLevel::Speed);
+        assert_eq!(
+            opt_level(&["--opt-level", "s"])?,
+            wasmtime::OptLevel::SpeedAndSize
+        );
+        assert_eq!(
+            opt_level(&["-O", "--opt-level", "s"])?,
+            wasmtime::OptLevel::SpeedAndSize
+        );
+        assert_eq!(
+            opt_level(&["--opt-level", "s", "-O"])?,
+            wasmtime::OptLevel::SpeedAndSize
+        );
+        assert_eq!(
+            opt_level(&["-O", "--opt-level", "0"])?,
+            wasmtime::OptLevel::None
+        );
+        assert!(opt_level(&["--opt-level", "3"]).is_err());
+
+        Ok(())
+    }
+
     #[test]
     fn test_default_modules() {
         let options = CommonOptions::try_parse_from(vec!["foo", "--wasi-modules=default"]).unwrap();
@@ -3,7 +3,7 @@ use std::collections::HashMap;
 use std::path::{Path, PathBuf};
 use syn::parse::{Error, Parse, ParseStream, Result};
 use syn::punctuated::Punctuated;
-use syn::{braced, token, Ident, Token};
+use syn::{braced, bracketed, token, Ident, Token};
 use wasmtime_wit_bindgen::{Opts, Ownership, TrappableError};
 use wit_parser::{PackageId, Resolve, UnresolvedPackage, WorldId};
 
@@ -22,7 +22,17 @@ pub fn expand(input: &Config) -> Result<TokenStream> {
         ));
     }
 
-    let src = input.opts.generate(&input.resolve, input.world);
+    if input.opts.generate_blocking_wrappers && !input.opts.async_ {
+        return Err(Error::new(
+            Span::call_site(),
+            "cannot generate blocking wrappers unless `async: true` is specified",
+        ));
+    }
+
+    let src = input
+        .opts
+        .generate(&input.resolve, input.world)
+        .map_err(|e| Error::new(Span::call_site(), e))?;
     let mut contents = src.parse::<TokenStream>().unwrap();
 
     // Include a dummy `include_str!` for any files we read so rustc knows that
@@ -72,6 +82,7 @@ impl Parse for Config {
                     }
                     Opt::Tracing(val) => opts.tracing = val,
                     Opt::Async(val) => opts.async_ = val,
+                    Opt::GenerateBlockingWrappers(val) => opts.generate_blocking_wrappers = val,
                     Opt::TrappableErrorType(val) => opts.trappable_error_type = val,
                     Opt::Ownership(val) => opts.ownership = val,
                     Opt::Interfaces(s) => {
@@ -100,6 +111,9 @@ impl Parse for Config {
                         opts.only_interfaces = true;
                     }
                     Opt::With(val) => opts.with.extend(val),
+                    Opt::SkipDebug(val) => opts.skip_debug.extend(val),
+                    Opt::FeatureGates(val) => opts.feature_gates.extend(val),
+                    Opt::ImmutableImports(val) => opts.immutable_imports.extend(val),
                 }
             }
         } else {
@@ -171,6 +185,10 @@ mod kw {
     syn::custom_keyword!(ownership);
     syn::custom_keyword!(interfaces);
     syn::custom_keyword!(with);
+    syn::custom_keyword!(skip_debug);
+    syn::custom_keyword!(feature_gates);
+    syn::custom_keyword!(immutable_imports);
+    syn::custom_keyword!(generate_blocking_wrappers);
 }
 
 enum Opt {
@@ -179,10 +197,14 @@ enum Opt {
     Inline(syn::LitStr),
     Tracing(bool),
     Async(bool),
+    GenerateBlockingWrappers(bool),
     TrappableErrorType(Vec<TrappableError>),
     Ownership(Ownership),
     Interfaces(syn::LitStr),
     With(HashMap<String, String>),
+    SkipDebug(Vec<String>),
+    FeatureGates(Vec<(String, String)>),
+    ImmutableImports(Vec<String>),
 }
 
 impl Parse for Opt {
@@ -208,6 +230,12 @@ impl Parse for Opt {
             input.parse::<Token![async]>()?;
             input.parse::<Token![:]>()?;
             Ok(Opt::Async(input.parse::<syn::LitBool>()?.value))
+        } else if l.peek(kw::generate_blocking_wrappers) {
+            input.parse::<kw::generate_blocking_wrappers>()?;
+            input.parse::<Token![:]>()?;
+            Ok(Opt::GenerateBlockingWrappers(
+                input.parse::<syn::LitBool>()?.value,
+            ))
         } else if l.peek(kw::ownership) {
             input.parse::<kw::ownership>()?;
             input.parse::<Token![:]>()?;
@@ -266,6 +294,32 @@ impl Parse for Opt {
             let fields: Punctuated<(String, String), Token![,]> =
                 contents.parse_terminated(with_field_parse, Token![,])?;
             Ok(Opt::With(HashMap::from_iter(fields.into_iter())))
+        } else if l.peek(kw::skip_debug) {
+            input.parse::<kw::skip_debug>()?;
+            input.parse::<Token![:]>()?;
+            let contents;
+            let _lbracket = bracketed!(contents in input);
+            let names: Punctuated<syn::LitStr, Token![,]> =
+                contents.parse_terminated(|p| p.parse(), Token![,])?;
+            Ok(Opt::SkipDebug(names.iter().map(|s| s.value()).collect()))
+        } else if l.peek(kw::feature_gates) {
+            input.parse::<kw::feature_gates>()?;
+            input.parse::<Token![:]>()?;
+            let contents;
+            let _lbrace = braced!(contents in input);
+            let fields: Punctuated<(String, String), Token![,]> =
+                contents.parse_terminated(feature_gate_field_parse, Token![,])?;
+            Ok(Opt::FeatureGates(Vec::from_iter(fields.into_iter())))
+        } else if l.peek(kw::immutable_imports) {
+            input.parse::<kw::immutable_imports>()?;
+            input.parse::<Token![:]>()?;
+            let contents;
+            let _lbracket = bracketed!(contents in input);
+            let names: Punctuated<syn::LitStr, Token![,]> =
+                contents.parse_terminated(|p| p.parse(), Token![,])?;
+            Ok(Opt::ImmutableImports(
+                names.iter().map(|s| s.value()).collect(),
+            ))
         } else {
             Err(l.error())
         }
@@ -299,6 +353,13 @@ fn trappable_error_field_parse(input: ParseStream<'_>) -> Result<TrappableError>
     })
 }
 
+fn feature_gate_field_parse(input: ParseStream<'_>) -> Result<(String, String)> {
+    let name = input.parse::<syn::LitStr>()?.value();
+    input.parse::<Token![:]>()?;
+    let feature = input.parse::<syn::LitStr>()?.value();
+    Ok((name, feature))
+}
+
 fn with_field_parse(input: ParseStream<'_>) -> Result<(String, String)> {
     let interface = input.parse::<syn::LitStr>()?.value();
     input.parse::<Token![:]>()?;
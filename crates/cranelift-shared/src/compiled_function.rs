@@ -86,6 +86,11 @@ where
     }
 
     /// Returns an iterator to the function's relocation information.
+    ///
+    /// Offsets are relative to the start of this function's body, and every
+    /// external name is resolved to a [`RelocationTarget`](crate::RelocationTarget),
+    /// so these records are enough to link the function with a custom linker
+    /// or to embed it in a foreign object format.
     pub fn relocations(&self) -> impl Iterator<Item = Relocation> + '_ {
         self.buffer.relocs().iter().map(|r| {
             mach_reloc_to_reloc(r, |external| {
@@ -4,7 +4,7 @@ use cranelift_codegen::{
     settings, MachReloc, MachTrap,
 };
 use std::collections::BTreeMap;
-use wasmtime_environ::{FlagValue, FuncIndex, Trap, TrapInformation};
+use wasmtime_environ::{DefinedFuncIndex, FlagValue, FuncIndex, PrimaryMap, Trap, TrapInformation};
 
 pub mod isa_builder;
 mod obj;
@@ -30,10 +30,40 @@ pub struct Relocation {
 pub enum RelocationTarget {
     /// The user function index.
     UserFunc(FuncIndex),
-    /// A compiler-generated libcall.
+    /// A compiler-generated libcall, such as `LibCall::CeilF32` when the target
+    /// lacks a native rounding instruction. The embedder must provide the
+    /// implementation of the libcall when linking.
     LibCall(ir::LibCall),
 }
 
+/// The relocations of the functions compiled for a module, indexed by their
+/// [`DefinedFuncIndex`].
+///
+/// This is meant for embedders which link Cranelift's output themselves, for
+/// example to embed it in a foreign object format.
+#[derive(Debug, Default)]
+pub struct Compilation {
+    relocations: PrimaryMap<DefinedFuncIndex, Vec<Relocation>>,
+}
+
+impl Compilation {
+    /// Records the relocations of `func`, the next defined function of the
+    /// module, and returns its index.
+    pub fn push(&mut self, func: &CompiledFunction<impl CompiledFuncEnv>) -> DefinedFuncIndex {
+        self.relocations.push(func.relocations().collect())
+    }
+
+    /// Returns the relocations of the defined function `func`, with offsets
+    /// relative to the start of its body.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `func` wasn't recorded with [`Compilation::push`].
+    pub fn relocations(&self, func: DefinedFuncIndex) -> &[Relocation] {
+        &self.relocations[func]
+    }
+}
+
 /// Converts cranelift_codegen settings to the wasmtime_environ equivalent.
 pub fn clif_flags_to_wasmtime(
     flags: impl IntoIterator<Item = settings::Value>,
@@ -44,6 +74,16 @@ pub fn clif_flags_to_wasmtime(
         .collect()
 }
 
+/// Returns the names of the boolean cranelift_codegen settings in `flags`
+/// which are enabled.
+pub fn enabled_clif_flags(flags: impl IntoIterator<Item = settings::Value>) -> Vec<&'static str> {
+    flags
+        .into_iter()
+        .filter(|val| val.as_bool() == Some(true))
+        .map(|val| val.name)
+        .collect()
+}
+
 fn to_flag_value(v: &settings::Value) -> FlagValue {
     match v.kind() {
         settings::SettingKind::Enum => FlagValue::Enum(v.as_enum().unwrap().into()),
@@ -127,3 +167,102 @@ where
         addend,
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use cranelift_codegen::cursor::{Cursor, FuncCursor};
+    use cranelift_codegen::entity::EntityRef;
+    use cranelift_codegen::ir::{types, AbiParam, InstBuilder};
+    use cranelift_codegen::Context;
+    use cranelift_control::ControlPlane;
+
+    #[test]
+    fn reloc_targets() {
+        let libcall = MachReloc {
+            offset: 4,
+            kind: binemit::Reloc::Abs8,
+            name: ExternalName::LibCall(ir::LibCall::CeilF32),
+            addend: 0,
+        };
+        let reloc = mach_reloc_to_reloc(&libcall, |_| unreachable!());
+        assert_eq!(
+            reloc,
+            Relocation {
+                reloc: binemit::Reloc::Abs8,
+                reloc_target: RelocationTarget::LibCall(ir::LibCall::CeilF32),
+                offset: 4,
+                addend: 0,
+            }
+        );
+
+        let user = MachReloc {
+            offset: 12,
+            kind: binemit::Reloc::X86CallPCRel4,
+            name: ExternalName::User(UserExternalNameRef::new(0)),
+            addend: -4,
+        };
+        let reloc = mach_reloc_to_reloc(&user, |r| {
+            assert_eq!(r, UserExternalNameRef::new(0));
+            (0, 7)
+        });
+        assert_eq!(
+            reloc.reloc_target,
+            RelocationTarget::UserFunc(FuncIndex::from_u32(7))
+        );
+        assert_eq!(reloc.offset, 12);
+        assert_eq!(reloc.addend, -4);
+    }
+
+    struct NoUserFuncs;
+
+    impl CompiledFuncEnv for NoUserFuncs {
+        fn resolve_user_external_name_ref(&self, _: UserExternalNameRef) -> (u32, u32) {
+            unreachable!()
+        }
+    }
+
+    #[test]
+    fn compilation_relocations() {
+        let isa = cranelift_native::builder()
+            .unwrap()
+            .finish(settings::Flags::new(settings::builder()))
+            .unwrap();
+
+        // A function which forwards its argument to the `ceilf` libcall.
+        let mut sig = ir::Signature::new(isa.default_call_conv());
+        sig.params.push(AbiParam::new(types::F32));
+        sig.returns.push(AbiParam::new(types::F32));
+        let mut func = ir::Function::with_name_signature(Default::default(), sig.clone());
+        let signature = func.import_signature(sig);
+        let ceil = func.import_function(ir::ExtFuncData {
+            name: ExternalName::LibCall(ir::LibCall::CeilF32),
+            signature,
+            colocated: false,
+        });
+        let block = func.dfg.make_block();
+        let arg = func.dfg.append_block_param(block, types::F32);
+        let mut pos = FuncCursor::new(&mut func);
+        pos.insert_block(block);
+        let call = pos.ins().call(ceil, &[arg]);
+        let result = pos.func.dfg.first_result(call);
+        pos.ins().return_(&[result]);
+
+        let mut ctx = Context::for_function(func);
+        let code = ctx
+            .compile(&*isa, &mut ControlPlane::default())
+            .map_err(|e| e.inner)
+            .unwrap();
+        let func = CompiledFunction::new(code.buffer.clone(), NoUserFuncs, 16);
+
+        let mut compilation = Compilation::default();
+        let index = compilation.push(&func);
+        assert_eq!(index, DefinedFuncIndex::new(0));
+        let relocs = compilation.relocations(index);
+        assert_eq!(relocs.len(), 1);
+        assert_eq!(
+            relocs[0].reloc_target,
+            RelocationTarget::LibCall(ir::LibCall::CeilF32)
+        );
+    }
+}
@@ -20,6 +20,7 @@ struct Builder {
     linkopts: LinkOptions,
     cache_store: Option<Arc<dyn CacheStore>>,
     clif_dir: Option<path::PathBuf>,
+    collect_metrics: bool,
 }
 
 #[derive(Clone, Default)]
@@ -42,6 +43,7 @@ pub fn builder() -> Box<dyn CompilerBuilder> {
         linkopts: LinkOptions::default(),
         cache_store: None,
         clif_dir: None,
+        collect_metrics: false,
     })
 }
 
@@ -55,6 +57,11 @@ impl CompilerBuilder for Builder {
         Ok(())
     }
 
+    fn collect_metrics(&mut self, enable: bool) -> Result<()> {
+        self.collect_metrics = enable;
+        Ok(())
+    }
+
     fn target(&mut self, target: target_lexicon::Triple) -> Result<()> {
         self.inner.target(target)?;
         Ok(())
@@ -91,6 +98,7 @@ impl CompilerBuilder for Builder {
             self.cache_store.clone(),
             self.linkopts.clone(),
             self.clif_dir.clone(),
+            self.collect_metrics,
         )))
     }
 
@@ -26,12 +26,13 @@ use std::convert::TryFrom;
 use std::mem;
 use std::path;
 use std::sync::{Arc, Mutex};
+use std::time::Instant;
 use wasmparser::{FuncValidatorAllocations, FunctionBody};
 use wasmtime_cranelift_shared::{CompiledFunction, ModuleTextBuilder};
 use wasmtime_environ::{
     AddressMapSection, CacheStore, CompileError, FlagValue, FunctionBodyData, FunctionLoc,
-    ModuleTranslation, ModuleTypes, PtrSize, StackMapInformation, TrapEncodingBuilder, Tunables,
-    VMOffsets, WasmFunctionInfo,
+    FunctionMetrics, ModuleTranslation, ModuleTypes, PtrSize, StackMapInformation,
+    TrapEncodingBuilder, Tunables, VMOffsets, WasmFunctionInfo,
 };
 
 #[cfg(feature = "component-model")]
@@ -71,6 +72,7 @@ pub(crate) struct Compiler {
     linkopts: LinkOptions,
     cache_store: Option<Arc<dyn CacheStore>>,
     clif_dir: Option<path::PathBuf>,
+    collect_metrics: bool,
 }
 
 impl Drop for Compiler {
@@ -108,6 +110,7 @@ impl Compiler {
         cache_store: Option<Arc<dyn CacheStore>>,
         linkopts: LinkOptions,
         clif_dir: Option<path::PathBuf>,
+        collect_metrics: bool,
     ) -> Compiler {
         Compiler {
             contexts: Default::default(),
@@ -116,6 +119,7 @@ impl Compiler {
             linkopts,
             cache_store,
             clif_dir,
+            collect_metrics,
         }
     }
 }
@@ -128,6 +132,7 @@ impl wasmtime_environ::Compiler for Compiler {
         input: FunctionBodyData<'_>,
         types: &ModuleTypes,
     ) -> Result<(WasmFunctionInfo, Box<dyn Any + Send>), CompileError> {
+        let start = self.collect_metrics.then(Instant::now);
         let isa = &*self.isa;
         let module = &translation.module;
         let func_index = module.func_index(func_index);
@@ -222,7 +227,13 @@ impl wasmtime_environ::Compiler for Compiler {
             write!(output, "{}", context.func.display()).unwrap();
         }
 
-        let (info, func) = compiler.finish_with_info(Some((&body, &self.tunables)))?;
+        let (mut info, func) = compiler.finish_with_info(Some((&body, &self.tunables)))?;
+        if let Some(start) = start {
+            info.metrics = Some(FunctionMetrics {
+                code_size: u32::try_from(func.buffer.data().len()).unwrap(),
+                compile_time: start.elapsed(),
+            });
+        }
 
         let timing = cranelift_codegen::timing::take_current();
         log::debug!("{:?} translated in {:?}", func_index, timing.total());
@@ -523,6 +534,10 @@ impl wasmtime_environ::Compiler for Compiler {
         wasmtime_cranelift_shared::clif_flags_to_wasmtime(self.isa.isa_flags())
     }
 
+    fn enabled_isa_flags(&self) -> Vec<&'static str> {
+        wasmtime_cranelift_shared::enabled_clif_flags(self.isa.isa_flags())
+    }
+
     fn is_branch_protection_enabled(&self) -> bool {
         self.isa.is_branch_protection_enabled()
     }
@@ -1074,6 +1089,7 @@ impl FunctionCompiler<'_> {
             WasmFunctionInfo {
                 start_srcloc: compiled_function.metadata().address_map.start_srcloc,
                 stack_maps: stack_maps.into(),
+                metrics: None,
             },
             compiled_function,
         ))
@@ -16,6 +16,7 @@ use std::collections::BTreeMap;
 use std::fmt;
 use std::path;
 use std::sync::Arc;
+use std::time::Duration;
 use thiserror::Error;
 
 /// Information about a function, such as trap information, address map,
@@ -25,6 +26,26 @@ use thiserror::Error;
 pub struct WasmFunctionInfo {
     pub start_srcloc: FilePos,
     pub stack_maps: Box<[StackMapInformation]>,
+    pub metrics: Option<FunctionMetrics>,
+}
+
+/// Size and timing information about the compilation of a single function.
+///
+/// This is only collected when enabled with
+/// [`CompilerBuilder::collect_metrics`].
+#[derive(Serialize, Deserialize, Copy, Clone, Debug, Default, PartialEq)]
+pub struct FunctionMetrics {
+    /// The size, in bytes, of the machine code generated for the function.
+    pub code_size: u32,
+    /// The time spent translating and compiling the function.
+    pub compile_time: Duration,
+}
+
+/// The [`FunctionMetrics`] of every defined function in a module.
+#[derive(Clone, Debug, Default)]
+pub struct CompilationMetrics {
+    /// Metrics for each defined function, indexed by its defined index.
+    pub functions: PrimaryMap<DefinedFuncIndex, FunctionMetrics>,
 }
 
 /// Description of where a function is located in the text section of a
@@ -95,6 +116,15 @@ pub trait CompilerBuilder: Send + Sync + fmt::Debug {
         anyhow::bail!("clif output not supported");
     }
 
+    /// Configures whether [`FunctionMetrics`] are recorded in the
+    /// [`WasmFunctionInfo`] of each compiled function.
+    fn collect_metrics(&mut self, enable: bool) -> Result<()> {
+        if enable {
+            anyhow::bail!("compilation metrics not supported");
+        }
+        Ok(())
+    }
+
     /// Returns the currently configured target triple that compilation will
     /// produce artifacts for.
     fn triple(&self) -> &target_lexicon::Triple;
@@ -350,6 +380,10 @@ pub trait Compiler: Send + Sync {
     /// Same as [`Compiler::flags`], but ISA-specific (a cranelift-ism)
     fn isa_flags(&self) -> BTreeMap<String, FlagValue>;
 
+    /// Returns the names of the ISA-specific boolean settings which are
+    /// enabled, such as `has_avx2`.
+    fn enabled_isa_flags(&self) -> Vec<&'static str>;
+
     /// Get a flag indicating whether branch protection is enabled.
     fn is_branch_protection_enabled(&self) -> bool;
 
@@ -66,6 +66,39 @@ impl MemoryStyle {
             tunables.dynamic_memory_offset_guard_size,
         )
     }
+
+    /// Like [`MemoryStyle::for_memory`], but always picks a static style
+    /// regardless of the memory's declared maximum.
+    ///
+    /// The bound is widened to cover the memory's maximum, or the whole index
+    /// space if it has none, so that bounds checks can be elided just as they
+    /// are for memories which fit within `Tunables::static_memory_bound`. The
+    /// cost is virtual address space: every instance of the memory reserves its
+    /// entire bound plus the static guard region up front, which for a memory
+    /// without a maximum is 4GiB plus the guard.
+    ///
+    /// Returns an error for 64-bit memories, whose index space is too large to
+    /// be covered by a static bound.
+    pub fn for_memory_forced_static(
+        memory: Memory,
+        tunables: &Tunables,
+    ) -> WasmResult<(Self, u64)> {
+        if memory.memory64 {
+            return Err(WasmError::Unsupported(format!(
+                "64-bit memories cannot be forced to be static"
+            )));
+        }
+        let maximum = std::cmp::min(
+            memory.maximum.unwrap_or(crate::WASM32_MAX_PAGES),
+            crate::WASM32_MAX_PAGES,
+        );
+        Ok((
+            Self::Static {
+                bound: std::cmp::max(maximum, tunables.static_memory_bound),
+            },
+            tunables.static_memory_offset_guard_size,
+        ))
+    }
 }
 
 /// A WebAssembly linear memory description along with our chosen style for
@@ -86,6 +119,24 @@ impl MemoryPlan {
     /// Draw up a plan for implementing a `Memory`.
     pub fn for_memory(memory: Memory, tunables: &Tunables) -> Self {
         let (style, offset_guard_size) = MemoryStyle::for_memory(memory, tunables);
+        Self::with_style(memory, style, offset_guard_size, tunables)
+    }
+
+    /// Draw up a plan for implementing a `Memory` which is always static.
+    ///
+    /// See [`MemoryStyle::for_memory_forced_static`] for the address-space
+    /// cost of doing so, and for which memories can't be made static.
+    pub fn for_memory_forced_static(memory: Memory, tunables: &Tunables) -> WasmResult<Self> {
+        let (style, offset_guard_size) = MemoryStyle::for_memory_forced_static(memory, tunables)?;
+        Ok(Self::with_style(memory, style, offset_guard_size, tunables))
+    }
+
+    fn with_style(
+        memory: Memory,
+        style: MemoryStyle,
+        offset_guard_size: u64,
+        tunables: &Tunables,
+    ) -> Self {
         Self {
             memory,
             style,
@@ -1081,3 +1132,108 @@ impl FunctionType {
 #[derive(Copy, Clone, PartialEq, Eq, Hash, PartialOrd, Ord, Debug, Serialize, Deserialize)]
 pub struct FuncRefIndex(u32);
 cranelift_entity::entity_impl!(FuncRefIndex);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn memory(minimum: u64, maximum: Option<u64>) -> Memory {
+        Memory {
+            minimum,
+            maximum,
+            shared: false,
+            memory64: false,
+        }
+    }
+
+    #[test]
+    fn memory_plan_guard_sizes() {
+        let tunables = Tunables {
+            static_memory_bound: 0x100,
+            static_memory_offset_guard_size: 0x20_0000,
+            dynamic_memory_offset_guard_size: 0x1_0000,
+            guard_before_linear_memory: true,
+            ..Tunables::default()
+        };
+
+        // A memory whose maximum fits in the static bound is static and uses
+        // the static guard size.
+        let plan = MemoryPlan::for_memory(memory(1, Some(0x100)), &tunables);
+        assert!(matches!(plan.style, MemoryStyle::Static { bound: 0x100 }));
+        assert_eq!(plan.offset_guard_size, 0x20_0000);
+        assert_eq!(plan.pre_guard_size, 0x20_0000);
+
+        // Otherwise it's dynamic and uses the dynamic guard size.
+        let plan = MemoryPlan::for_memory(memory(1, None), &tunables);
+        assert!(matches!(plan.style, MemoryStyle::Dynamic { .. }));
+        assert_eq!(plan.offset_guard_size, 0x1_0000);
+        assert_eq!(plan.pre_guard_size, 0x1_0000);
+
+        // Widening the static bound moves the same memory to a static plan.
+        let tunables = Tunables {
+            static_memory_bound: crate::WASM32_MAX_PAGES,
+            ..tunables
+        };
+        let plan = MemoryPlan::for_memory(memory(1, None), &tunables);
+        assert!(matches!(plan.style, MemoryStyle::Static { .. }));
+        assert_eq!(plan.offset_guard_size, 0x20_0000);
+    }
+
+    #[test]
+    fn memory_plan_forced_static() {
+        let tunables = Tunables {
+            static_memory_bound: 0x100,
+            static_memory_offset_guard_size: 0x20_0000,
+            dynamic_memory_offset_guard_size: 0x1_0000,
+            ..Tunables::default()
+        };
+
+        // A huge maximum would normally be dynamic...
+        let plan = MemoryPlan::for_memory(memory(1, Some(0x1_0000)), &tunables);
+        assert!(matches!(plan.style, MemoryStyle::Dynamic { .. }));
+
+        // ...but forcing it yields a static plan covering the whole maximum.
+        let plan = MemoryPlan::for_memory_forced_static(memory(1, Some(0x1_0000)), &tunables);
+        assert!(matches!(
+            plan.style,
+            MemoryStyle::Static { bound: 0x1_0000 }
+        ));
+        assert_eq!(plan.offset_guard_size, 0x20_0000);
+
+        // Without a maximum the bound covers the entire 32-bit index space.
+        let plan = MemoryPlan::for_memory_forced_static(memory(1, None), &tunables);
+        assert!(matches!(
+            plan.style,
+            MemoryStyle::Static {
+                bound: crate::WASM32_MAX_PAGES
+            }
+        ));
+
+        // The override is also honored when translating a module.
+        let wasm = wat::parse_str(
+            r#"(module
+                (memory 1 65536)
+                (memory 1 65536))"#,
+        )
+        .unwrap();
+        let mut validator = wasmparser::Validator::new_with_features(wasmparser::WasmFeatures {
+            multi_memory: true,
+            ..Default::default()
+        });
+        let mut types = Default::default();
+        let force = |index: MemoryIndex, _: &Memory| index.as_u32() == 1;
+        let translation = crate::ModuleEnvironment::new(&tunables, &mut validator, &mut types)
+            .force_static_memory(&force)
+            .translate(wasmparser::Parser::new(0), &wasm)
+            .unwrap();
+        let plans = &translation.module.memory_plans;
+        assert!(matches!(
+            plans[MemoryIndex::from_u32(0)].style,
+            MemoryStyle::Dynamic { .. }
+        ));
+        assert!(matches!(
+            plans[MemoryIndex::from_u32(1)].style,
+            MemoryStyle::Static { bound: 0x1_0000 }
+        ));
+    }
+}
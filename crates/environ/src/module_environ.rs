@@ -4,7 +4,7 @@ use crate::module::{
 };
 use crate::{
     DataIndex, DefinedFuncIndex, ElemIndex, EntityIndex, EntityType, FuncIndex, GlobalIndex,
-    GlobalInit, MemoryIndex, ModuleTypesBuilder, PrimaryMap, SignatureIndex, TableIndex,
+    GlobalInit, Memory, MemoryIndex, ModuleTypesBuilder, PrimaryMap, SignatureIndex, TableIndex,
     TableInitialValue, Tunables, TypeConvert, TypeIndex, WasmError, WasmFuncType, WasmHeapType,
     WasmResult, WasmType,
 };
@@ -31,6 +31,61 @@ pub struct ModuleEnvironment<'a, 'data> {
     // Various bits and pieces of configuration
     validator: &'a mut Validator,
     tunables: &'a Tunables,
+
+    /// Predicate deciding which defined memories are forced to be static, see
+    /// `force_static_memory`.
+    force_static_memory: Option<&'a dyn Fn(MemoryIndex, &Memory) -> bool>,
+
+    /// Whether custom sections are recorded in
+    /// `ModuleTranslation::custom_sections`, see `retain_custom_sections`.
+    retain_custom_sections: bool,
+
+    /// Limits on the size of the module being translated, see `limits`.
+    limits: ModuleLimits,
+}
+
+/// Limits on the size of a module that are checked during translation.
+///
+/// Modules exceeding any of these limits are rejected by
+/// [`ModuleEnvironment::translate`] before any of their functions are
+/// compiled, which bounds the work done on behalf of untrusted input. The
+/// defaults match the limits that the validator itself enforces, so only
+/// lowering them has an effect.
+#[derive(Debug, Clone, Copy)]
+pub struct ModuleLimits {
+    /// Maximum number of functions, including imported functions.
+    pub max_functions: usize,
+    /// Maximum number of locals declared by a single function body, not
+    /// counting its parameters.
+    pub max_locals: usize,
+    /// Maximum number of tables, including imported tables.
+    pub max_tables: usize,
+    /// Maximum number of memories, including imported memories.
+    pub max_memories: usize,
+    /// Maximum number of entries in the type section.
+    pub max_types: usize,
+}
+
+impl Default for ModuleLimits {
+    fn default() -> ModuleLimits {
+        ModuleLimits {
+            max_functions: 1_000_000,
+            max_locals: 50_000,
+            max_tables: 100,
+            max_memories: 100,
+            max_types: 1_000_000,
+        }
+    }
+}
+
+fn check_limit(desc: &str, count: usize, max: usize, offset: usize) -> WasmResult<()> {
+    if count > max {
+        return Err(WasmError::InvalidWebAssembly {
+            message: format!("{desc} count of {count} exceeds limit of {max}"),
+            offset,
+        });
+    }
+    Ok(())
 }
 
 /// The result of translating via `ModuleEnvironment`. Function bodies are not
@@ -85,6 +140,13 @@ pub struct ModuleTranslation<'data> {
     /// concatenated for the final artifact.
     pub passive_data: Vec<&'data [u8]>,
 
+    /// The name and contents of every custom section in this module, in the
+    /// order they appear.
+    ///
+    /// This is only populated if `ModuleEnvironment::retain_custom_sections`
+    /// was enabled, and is otherwise empty.
+    pub custom_sections: Vec<(&'data str, &'data [u8])>,
+
     /// Total size of all passive data pushed into `passive_data` so far.
     total_passive_data: u32,
 
@@ -167,9 +229,49 @@ impl<'a, 'data> ModuleEnvironment<'a, 'data> {
             types,
             tunables,
             validator,
+            force_static_memory: None,
+            retain_custom_sections: false,
+            limits: ModuleLimits::default(),
         }
     }
 
+    /// Force some memories defined by this module to use a static memory
+    /// style, regardless of their declared maximum.
+    ///
+    /// `predicate` is called with the index and type of each memory defined
+    /// by the module, and memories for which it returns `true` are planned
+    /// with [`MemoryPlan::for_memory_forced_static`]. This guarantees that
+    /// bounds checks for those memories are elided, at the cost of reserving
+    /// their entire bound plus guard region in virtual address space for each
+    /// instance. Imported memories are unaffected since their style must
+    /// match the memory that is provided at instantiation time. Translation
+    /// fails if `predicate` selects a 64-bit memory.
+    pub fn force_static_memory(
+        mut self,
+        predicate: &'a dyn Fn(MemoryIndex, &Memory) -> bool,
+    ) -> Self {
+        self.force_static_memory = Some(predicate);
+        self
+    }
+
+    /// Record the name and contents of each custom section in the module,
+    /// such as `name` or `producers`, in
+    /// [`ModuleTranslation::custom_sections`].
+    ///
+    /// This is disabled by default. The recorded contents borrow from the
+    /// original wasm binary rather than being copied.
+    pub fn retain_custom_sections(mut self, retain: bool) -> Self {
+        self.retain_custom_sections = retain;
+        self
+    }
+
+    /// Configures the limits that the module is checked against during
+    /// translation, see [`ModuleLimits`].
+    pub fn limits(mut self, limits: ModuleLimits) -> Self {
+        self.limits = limits;
+        self
+    }
+
     /// Translate a wasm module using this environment.
     ///
     /// This function will translate the `data` provided with `parser`,
@@ -193,6 +295,12 @@ impl<'a, 'data> ModuleEnvironment<'a, 'data> {
     }
 
     fn translate_payload(&mut self, payload: Payload<'data>) -> WasmResult<()> {
+        if let Payload::CustomSection(s) = &payload {
+            if self.retain_custom_sections {
+                self.result.custom_sections.push((s.name(), s.data()));
+            }
+        }
+
         match payload {
             Payload::Version {
                 num,
@@ -234,6 +342,7 @@ impl<'a, 'data> ModuleEnvironment<'a, 'data> {
             Payload::TypeSection(types) => {
                 self.validator.type_section(&types)?;
                 let num = usize::try_from(types.count()).unwrap();
+                check_limit("type", num, self.limits.max_types, types.range().start)?;
                 self.result.module.types.reserve(num);
                 self.types.reserve_wasm_signatures(num);
 
@@ -288,12 +397,39 @@ impl<'a, 'data> ModuleEnvironment<'a, 'data> {
                     };
                     self.declare_import(import.module, import.name, ty);
                 }
+
+                let offset = imports.range().start;
+                let module = &self.result.module;
+                check_limit(
+                    "function",
+                    module.functions.len(),
+                    self.limits.max_functions,
+                    offset,
+                )?;
+                check_limit(
+                    "table",
+                    module.table_plans.len(),
+                    self.limits.max_tables,
+                    offset,
+                )?;
+                check_limit(
+                    "memory",
+                    module.memory_plans.len(),
+                    self.limits.max_memories,
+                    offset,
+                )?;
             }
 
             Payload::FunctionSection(functions) => {
                 self.validator.function_section(&functions)?;
 
                 let cnt = usize::try_from(functions.count()).unwrap();
+                check_limit(
+                    "function",
+                    self.result.module.functions.len() + cnt,
+                    self.limits.max_functions,
+                    functions.range().start,
+                )?;
                 self.result.module.functions.reserve_exact(cnt);
 
                 for entry in functions {
@@ -307,6 +443,12 @@ impl<'a, 'data> ModuleEnvironment<'a, 'data> {
             Payload::TableSection(tables) => {
                 self.validator.table_section(&tables)?;
                 let cnt = usize::try_from(tables.count()).unwrap();
+                check_limit(
+                    "table",
+                    self.result.module.table_plans.len() + cnt,
+                    self.limits.max_tables,
+                    tables.range().start,
+                )?;
                 self.result.module.table_plans.reserve_exact(cnt);
 
                 for entry in tables {
@@ -350,11 +492,23 @@ impl<'a, 'data> ModuleEnvironment<'a, 'data> {
                 self.validator.memory_section(&memories)?;
 
                 let cnt = usize::try_from(memories.count()).unwrap();
+                check_limit(
+                    "memory",
+                    self.result.module.memory_plans.len() + cnt,
+                    self.limits.max_memories,
+                    memories.range().start,
+                )?;
                 self.result.module.memory_plans.reserve_exact(cnt);
 
                 for entry in memories {
-                    let memory = entry?;
-                    let plan = MemoryPlan::for_memory(memory.into(), &self.tunables);
+                    let memory: Memory = entry?.into();
+                    let index = self.result.module.memory_plans.next_key();
+                    let plan = match self.force_static_memory {
+                        Some(force) if force(index, &memory) => {
+                            MemoryPlan::for_memory_forced_static(memory, &self.tunables)?
+                        }
+                        _ => MemoryPlan::for_memory(memory, &self.tunables),
+                    };
                     self.result.module.memory_plans.push(plan);
                 }
             }
@@ -546,6 +700,20 @@ impl<'a, 'data> ModuleEnvironment<'a, 'data> {
 
             Payload::CodeSectionEntry(mut body) => {
                 let validator = self.validator.code_section_entry(&body)?;
+                let mut num_locals = 0;
+                for pair in body.get_locals_reader()? {
+                    let (cnt, _ty) = pair?;
+                    // Saturate so that an overflowing count fails the check
+                    // below instead of wrapping around.
+                    num_locals = usize::try_from(cnt)
+                        .map_or(usize::MAX, |cnt| num_locals.saturating_add(cnt));
+                }
+                check_limit(
+                    "local",
+                    num_locals,
+                    self.limits.max_locals,
+                    body.range().start,
+                )?;
                 let func_index =
                     self.result.code_index + self.result.module.num_imported_funcs as u32;
                 let func_index = FuncIndex::from_u32(func_index);
@@ -885,3 +1053,142 @@ impl TypeConvert for ModuleEnvironment<'_, '_> {
         self.result.module.lookup_heap_type(index)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn retain_custom_sections() {
+        let wasm = wat::parse_str(
+            r#"(module
+                (@custom "producers" "\01\08language\01\04Rust\00")
+                (func (export "f")))"#,
+        )
+        .unwrap();
+        let producers = b"\x01\x08language\x01\x04Rust\x00";
+        let tunables = Tunables::default();
+
+        // Custom sections are dropped by default...
+        let mut validator = Validator::new();
+        let mut types = Default::default();
+        let translation = ModuleEnvironment::new(&tunables, &mut validator, &mut types)
+            .translate(Parser::new(0), &wasm)
+            .unwrap();
+        assert!(translation.custom_sections.is_empty());
+
+        // ...and recorded when requested.
+        let mut validator = Validator::new();
+        let mut types = Default::default();
+        let translation = ModuleEnvironment::new(&tunables, &mut validator, &mut types)
+            .retain_custom_sections(true)
+            .translate(Parser::new(0), &wasm)
+            .unwrap();
+        assert_eq!(translation.custom_sections, [("producers", &producers[..])]);
+    }
+
+    #[test]
+    fn module_limits() {
+        let wasm = wat::parse_str(
+            r#"(module
+                (import "" "f" (func))
+                (memory 1)
+                (func (local i32 i64))
+                (func (local f32)))"#,
+        )
+        .unwrap();
+        let tunables = Tunables::default();
+        let translate = |limits: ModuleLimits| {
+            let mut validator = Validator::new();
+            let mut types = Default::default();
+            ModuleEnvironment::new(&tunables, &mut validator, &mut types)
+                .limits(limits)
+                .translate(Parser::new(0), &wasm)
+                .map(|_| ())
+        };
+
+        // A normal module passes the default limits, as well as limits it
+        // exactly fits in.
+        translate(ModuleLimits::default()).unwrap();
+        translate(ModuleLimits {
+            max_functions: 3,
+            max_locals: 2,
+            max_tables: 0,
+            max_memories: 1,
+            max_types: 1,
+        })
+        .unwrap();
+
+        // Imported functions count towards the limit.
+        let err = translate(ModuleLimits {
+            max_functions: 2,
+            ..ModuleLimits::default()
+        })
+        .unwrap_err();
+        assert!(
+            err.to_string()
+                .contains("function count of 3 exceeds limit of 2"),
+            "{err}"
+        );
+
+        let err = translate(ModuleLimits {
+            max_locals: 1,
+            ..ModuleLimits::default()
+        })
+        .unwrap_err();
+        assert!(
+            err.to_string()
+                .contains("local count of 2 exceeds limit of 1"),
+            "{err}"
+        );
+
+        for limits in [
+            ModuleLimits {
+                max_memories: 0,
+                ..ModuleLimits::default()
+            },
+            ModuleLimits {
+                max_types: 0,
+                ..ModuleLimits::default()
+            },
+        ] {
+            assert!(matches!(
+                translate(limits),
+                Err(WasmError::InvalidWebAssembly { .. })
+            ));
+        }
+    }
+
+    #[test]
+    fn force_static_memory() {
+        let tunables = Tunables::default();
+        let translate = |wat: &str| {
+            let wasm = wat::parse_str(wat).unwrap();
+            let mut validator = Validator::new_with_features(wasmparser::WasmFeatures {
+                memory64: true,
+                ..Default::default()
+            });
+            let mut types = Default::default();
+            ModuleEnvironment::new(&tunables, &mut validator, &mut types)
+                .force_static_memory(&|_, _| true)
+                .translate(Parser::new(0), &wasm)
+                .map(|t| {
+                    t.module.memory_plans[MemoryIndex::from_u32(0)]
+                        .style
+                        .clone()
+                })
+        };
+
+        // A 32-bit memory without a maximum is bounded by its index space.
+        match translate("(module (memory 1))").unwrap() {
+            crate::MemoryStyle::Static { bound } => assert_eq!(bound, crate::WASM32_MAX_PAGES),
+            style => panic!("unexpected style {style:?}"),
+        }
+
+        // The bound of a 64-bit memory wouldn't fit in the address space.
+        assert!(matches!(
+            translate("(module (memory i64 1))"),
+            Err(WasmError::Unsupported(_))
+        ));
+    }
+}
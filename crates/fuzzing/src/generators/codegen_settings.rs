@@ -115,6 +115,8 @@ impl<'a> Arbitrary<'a> for CodegenSettings {
                     std:"avx512bitalg" => clif:"has_avx512bitalg" ratio:1 in 1000,
                     std:"avx512dq" => clif:"has_avx512dq" ratio: 1 in 1000,
                     std:"avx512f" => clif:"has_avx512f" ratio: 1 in 1000,
+                    std:"avx512bw" => clif:"has_avx512bw" ratio: 1 in 1000,
+                    std:"avx512cd" => clif:"has_avx512cd" ratio: 1 in 1000,
                     std:"avx512vl" => clif:"has_avx512vl" ratio: 1 in 1000,
                     std:"avx512vbmi" => clif:"has_avx512vbmi" ratio: 1 in 1000,
                 },
@@ -123,6 +125,8 @@ impl<'a> Arbitrary<'a> for CodegenSettings {
 
                     std: "bti" => clif: "use_bti",
                     std: "lse" => clif: "has_lse",
+                    std: "fp16" => clif: "has_fp16",
+                    std: "dotprod" => clif: "has_dotprod",
                     // even though the natural correspondence seems to be
                     // between "paca" and "has_pauth", the latter has no effect
                     // in isolation, so we actually use the setting that affects
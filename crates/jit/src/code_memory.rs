@@ -1,10 +1,11 @@
 //! Memory management for executable code.
 
 use crate::subslice_range;
-use crate::unwind::UnwindRegistration;
+use crate::unwind::{UnwindRecord, UnwindRegistration};
 use anyhow::{anyhow, bail, Context, Result};
 use object::read::{File, Object, ObjectSection};
 use object::ObjectSymbol;
+use once_cell::sync::OnceCell;
 use std::mem::ManuallyDrop;
 use std::ops::Range;
 use wasmtime_environ::obj;
@@ -21,6 +22,7 @@ pub struct CodeMemory {
     // dropped first since it refers to memory owned by `mmap`.
     mmap: ManuallyDrop<MmapVec>,
     unwind_registration: ManuallyDrop<Option<UnwindRegistration>>,
+    unwind_records: OnceCell<Vec<UnwindRecord>>,
     published: bool,
     enable_branch_protection: bool,
 
@@ -133,6 +135,7 @@ impl CodeMemory {
         Ok(Self {
             mmap: ManuallyDrop::new(mmap),
             unwind_registration: ManuallyDrop::new(None),
+            unwind_records: OnceCell::new(),
             published: false,
             enable_branch_protection: enable_branch_protection
                 .ok_or_else(|| anyhow!("missing `{}` section", obj::ELF_WASM_BTI))?,
@@ -159,6 +162,32 @@ impl CodeMemory {
         &self.mmap[self.text.clone()]
     }
 
+    /// Returns the raw unwind information section of this image.
+    ///
+    /// This is an `.eh_frame` section on System V platforms and a `.pdata`
+    /// section on Windows, laid out as it is registered with the system
+    /// unwinder in `publish`. See [`CodeMemory::unwind_records`] for a
+    /// per-function view of this section.
+    pub fn unwind_info(&self) -> &[u8] {
+        &self.mmap[self.unwind.clone()]
+    }
+
+    /// Returns a description of each entry in the unwind information section,
+    /// including the range of the text section it covers.
+    ///
+    /// This is intended for embedders that register unwind information with
+    /// their own exception handling machinery. The section is parsed the
+    /// first time this is called, and an error is returned if it's malformed
+    /// or if parsing it isn't supported on this platform.
+    pub fn unwind_records(&self) -> Result<&[UnwindRecord]> {
+        let records = self.unwind_records.get_or_try_init(|| {
+            let text_len = self.unwind.start.saturating_sub(self.text.start);
+            UnwindRegistration::records(text_len, self.unwind_info())
+                .context("failed to parse unwind information")
+        })?;
+        Ok(records)
+    }
+
     /// Returns the contents of the `ELF_WASMTIME_DWARF` section.
     pub fn dwarf(&self) -> &[u8] {
         &self.mmap[self.dwarf.clone()]
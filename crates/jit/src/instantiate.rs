@@ -6,6 +6,7 @@
 use crate::code_memory::CodeMemory;
 use crate::debug::create_gdbjit_image;
 use crate::profiling::ProfilingAgent;
+use crate::unwind::UnwindRecord;
 use anyhow::{bail, Context, Error, Result};
 use object::write::{Object, SectionId, StandardSegment, WritableBuffer};
 use object::SectionKind;
@@ -16,8 +17,8 @@ use std::str;
 use std::sync::Arc;
 use wasmtime_environ::obj;
 use wasmtime_environ::{
-    DefinedFuncIndex, FuncIndex, FunctionLoc, MemoryInitialization, Module, ModuleTranslation,
-    PrimaryMap, SignatureIndex, StackMapInformation, Tunables, WasmFunctionInfo,
+    CompilationMetrics, DefinedFuncIndex, FuncIndex, FunctionLoc, MemoryInitialization, Module,
+    ModuleTranslation, PrimaryMap, SignatureIndex, StackMapInformation, Tunables, WasmFunctionInfo,
 };
 use wasmtime_runtime::{
     CompiledModuleId, CompiledModuleIdAllocator, GdbJitImageRegistration, MmapVec,
@@ -68,6 +69,10 @@ pub struct CompiledModuleInfo {
     /// callee (e.g. `Func::wrap`) to a Wasm caller. Sorted by signature index.
     wasm_to_native_trampolines: Vec<(SignatureIndex, FunctionLoc)>,
 
+    /// Name and contents of the custom sections of the original wasm module,
+    /// if they were retained during translation.
+    custom_sections: Vec<(String, Vec<u8>)>,
+
     /// General compilation metadata.
     meta: Metadata,
 }
@@ -199,6 +204,7 @@ impl<'a> ObjectBuilder<'a> {
             data,
             data_align,
             passive_data,
+            custom_sections,
             ..
         } = translation;
 
@@ -306,6 +312,10 @@ impl<'a> ObjectBuilder<'a> {
             funcs,
             wasm_to_native_trampolines,
             func_names,
+            custom_sections: custom_sections
+                .into_iter()
+                .map(|(name, data)| (name.to_string(), data.to_vec()))
+                .collect(),
             meta: Metadata {
                 native_debug_info_present: self.tunables.generate_native_debuginfo,
                 has_unparsed_debuginfo,
@@ -430,6 +440,7 @@ pub struct CompiledModule {
     /// A unique ID used to register this module with the engine.
     unique_id: CompiledModuleId,
     func_names: Vec<FunctionName>,
+    custom_sections: Vec<(String, Vec<u8>)>,
 }
 
 impl CompiledModule {
@@ -464,6 +475,7 @@ impl CompiledModule {
             meta: info.meta,
             unique_id: id_allocator.alloc(),
             func_names: info.func_names,
+            custom_sections: info.custom_sections,
         };
         ret.register_debug_and_profiling(profiler)?;
 
@@ -513,6 +525,16 @@ impl CompiledModule {
         self.code_memory.text()
     }
 
+    /// Returns the unwind information for the code of this module, one record
+    /// per function or trampoline that has unwind information.
+    ///
+    /// This is registered with the system unwinder automatically, but is
+    /// exposed for embedders that need to register it with their own
+    /// exception handling machinery as well.
+    pub fn unwind_info(&self) -> Result<&[UnwindRecord]> {
+        self.code_memory.unwind_records()
+    }
+
     /// Return a reference-counting pointer to a module.
     pub fn module(&self) -> &Arc<Module> {
         &self.module
@@ -533,6 +555,17 @@ impl CompiledModule {
         Some(str::from_utf8(&data[name.offset as usize..][..name.len as usize]).unwrap())
     }
 
+    /// Returns the name and contents of each custom section of the original
+    /// wasm module, in the order they appeared.
+    ///
+    /// This is empty unless custom sections were retained when the module was
+    /// translated.
+    pub fn custom_sections(&self) -> impl ExactSizeIterator<Item = (&str, &[u8])> + '_ {
+        self.custom_sections
+            .iter()
+            .map(|(name, data)| (name.as_str(), data.as_slice()))
+    }
+
     /// Return a reference to a mutable module (if possible).
     pub fn module_mut(&mut self) -> Option<&mut Module> {
         Arc::get_mut(&mut self.module)
@@ -660,6 +693,16 @@ impl CompiledModule {
             .wasm_func_info
     }
 
+    /// Returns the metrics recorded while compiling this module's functions,
+    /// or `None` if metrics were not collected for all of them.
+    pub fn compilation_metrics(&self) -> Option<CompilationMetrics> {
+        let mut functions = PrimaryMap::with_capacity(self.funcs.len());
+        for (_, func) in self.funcs.iter() {
+            functions.push(func.wasm_func_info.metrics?);
+        }
+        Some(CompilationMetrics { functions })
+    }
+
     /// Creates a new symbolication context which can be used to further
     /// symbolicate stack traces.
     ///
@@ -32,6 +32,7 @@ pub use crate::instantiate::{
     subslice_range, CompiledFunctionInfo, CompiledModule, CompiledModuleInfo, ObjectBuilder,
     SymbolizeContext,
 };
+pub use crate::unwind::UnwindRecord;
 pub use demangling::*;
 
 /// Version number of this crate.
@@ -1,3 +1,20 @@
+use std::ops::Range;
+
+/// Describes the unwind information for a single range of code within a
+/// module's text section.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct UnwindRecord {
+    /// The range of the text section, in bytes, that this record describes.
+    pub code: Range<usize>,
+    /// The offset of this record's entry within the raw unwind section
+    /// returned by [`CodeMemory::unwind_info`](crate::CodeMemory::unwind_info).
+    ///
+    /// On System V platforms this is the offset of an FDE within `.eh_frame`
+    /// and on Windows this is the offset of a `RUNTIME_FUNCTION` within
+    /// `.pdata`.
+    pub unwind_offset: usize,
+}
+
 cfg_if::cfg_if! {
     if #[cfg(all(windows, any(target_arch = "x86_64", target_arch = "aarch64")))] {
         mod winx64;
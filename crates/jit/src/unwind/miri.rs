@@ -1,3 +1,4 @@
+use super::UnwindRecord;
 use anyhow::Result;
 
 pub struct UnwindRegistration {}
@@ -5,6 +6,10 @@ pub struct UnwindRegistration {}
 impl UnwindRegistration {
     pub const SECTION_NAME: &str = ".eh_frame";
 
+    pub fn records(_text_len: usize, _unwind_info: &[u8]) -> Result<Vec<UnwindRecord>> {
+        Ok(Vec::new())
+    }
+
     pub unsafe fn new(
         _base_address: *const u8,
         _unwind_info: *const u8,
@@ -1,5 +1,6 @@
 //! Module for System V ABI unwind registry.
 
+use super::UnwindRecord;
 use anyhow::Result;
 
 /// Represents a registration of function unwind information for System V ABI.
@@ -16,6 +17,34 @@ extern "C" {
 impl UnwindRegistration {
     pub const SECTION_NAME: &str = ".eh_frame";
 
+    /// Parses the `.eh_frame` section of a module into one record per FDE.
+    ///
+    /// `text_len` is the offset of the `.eh_frame` section from the start of
+    /// the text section. FDE addresses are encoded relative to the section,
+    /// so this is what resolves them to text-section-relative offsets.
+    pub fn records(text_len: usize, unwind_info: &[u8]) -> Result<Vec<UnwindRecord>> {
+        use gimli::UnwindSection;
+
+        let eh_frame = gimli::EhFrame::new(unwind_info, gimli::NativeEndian);
+        let bases = gimli::BaseAddresses::default().set_eh_frame(text_len as u64);
+        let mut entries = eh_frame.entries(&bases);
+        let mut records = Vec::new();
+        while let Some(entry) = entries.next()? {
+            let fde = match entry {
+                gimli::CieOrFde::Cie(_) => continue,
+                gimli::CieOrFde::Fde(partial) => partial
+                    .parse(|section, bases, offset| section.cie_from_offset(bases, offset))?,
+            };
+            let start = usize::try_from(fde.initial_address())?;
+            let len = usize::try_from(fde.len())?;
+            records.push(UnwindRecord {
+                code: start..start + len,
+                unwind_offset: fde.offset(),
+            });
+        }
+        Ok(records)
+    }
+
     /// Registers precompiled unwinding information with the system.
     ///
     /// The `_base_address` field is ignored here (only used on other
@@ -1,5 +1,6 @@
 //! Module for Windows x64 ABI unwind registry.
 
+use super::UnwindRecord;
 use anyhow::{bail, Result};
 use std::mem;
 use windows_sys::Win32::System::Diagnostics::Debug::*;
@@ -12,6 +13,44 @@ pub struct UnwindRegistration {
 impl UnwindRegistration {
     pub const SECTION_NAME: &str = ".pdata";
 
+    /// Parses the `.pdata` section of a module into one record per
+    /// `RUNTIME_FUNCTION`, whose addresses are already relative to the start
+    /// of the text section.
+    #[cfg(target_arch = "x86_64")]
+    pub fn records(_text_len: usize, unwind_info: &[u8]) -> Result<Vec<UnwindRecord>> {
+        // On x64 each entry is a `BeginAddress`, `EndAddress` and
+        // `UnwindInfoAddress` triple of 32-bit values.
+        const UNIT_LEN: usize = 12;
+        if unwind_info.len() % UNIT_LEN != 0 {
+            bail!("invalid `{}` section length", Self::SECTION_NAME);
+        }
+        let field = |entry: &[u8], i: usize| {
+            u32::from_le_bytes(entry[i * 4..][..4].try_into().unwrap()) as usize
+        };
+        Ok(unwind_info
+            .chunks_exact(UNIT_LEN)
+            .enumerate()
+            .map(|(i, entry)| UnwindRecord {
+                code: field(entry, 0)..field(entry, 1),
+                unwind_offset: i * UNIT_LEN,
+            })
+            .collect())
+    }
+
+    /// AArch64 `RUNTIME_FUNCTION` entries don't record where the function
+    /// ends, so there's nothing to describe here. Cranelift doesn't emit
+    /// `.pdata` for this target anyway.
+    #[cfg(target_arch = "aarch64")]
+    pub fn records(_text_len: usize, unwind_info: &[u8]) -> Result<Vec<UnwindRecord>> {
+        if !unwind_info.is_empty() {
+            bail!(
+                "parsing `{}` is not supported on aarch64 Windows",
+                Self::SECTION_NAME
+            );
+        }
+        Ok(Vec::new())
+    }
+
     pub unsafe fn new(
         base_address: *const u8,
         unwind_info: *const u8,
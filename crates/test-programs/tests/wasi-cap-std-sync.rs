@@ -119,6 +119,10 @@ fn fd_advise() {
     run("fd_advise", true).unwrap()
 }
 #[test_log::test]
+fn fd_fdstat_set_rights() {
+    run("fd_fdstat_set_rights", true).unwrap()
+}
+#[test_log::test]
 fn fd_filestat_get() {
     run("fd_filestat_get", true).unwrap()
 }
@@ -135,8 +139,8 @@ fn fd_readdir() {
     run("fd_readdir", true).unwrap()
 }
 #[test_log::test]
-fn file_allocate() {
-    run("file_allocate", true).unwrap()
+fn file_allocate_grow() {
+    run("file_allocate_grow", true).unwrap()
 }
 #[test_log::test]
 fn file_pread_pwrite() {
@@ -152,6 +152,12 @@ async fn fd_advise() {
     run("fd_advise", false).await.unwrap()
 }
 #[test_log::test(tokio::test(flavor = "multi_thread"))]
+// Rights can not yet be dropped with the preview 2 implementation:
+#[should_panic]
+async fn fd_fdstat_set_rights() {
+    run("fd_fdstat_set_rights", false).await.unwrap()
+}
+#[test_log::test(tokio::test(flavor = "multi_thread"))]
 async fn fd_filestat_get() {
     run("fd_filestat_get", false).await.unwrap()
 }
@@ -121,6 +121,10 @@ async fn fd_advise() {
     run("fd_advise", true).await.unwrap()
 }
 #[test_log::test(tokio::test(flavor = "multi_thread"))]
+async fn fd_fdstat_set_rights() {
+    run("fd_fdstat_set_rights", true).await.unwrap()
+}
+#[test_log::test(tokio::test(flavor = "multi_thread"))]
 async fn fd_filestat_get() {
     run("fd_filestat_get", true).await.unwrap()
 }
@@ -137,8 +141,8 @@ async fn fd_readdir() {
     run("fd_readdir", true).await.unwrap()
 }
 #[test_log::test(tokio::test(flavor = "multi_thread"))]
-async fn file_allocate() {
-    run("file_allocate", true).await.unwrap()
+async fn file_allocate_grow() {
+    run("file_allocate_grow", true).await.unwrap()
 }
 #[test_log::test(tokio::test(flavor = "multi_thread"))]
 async fn file_pread_pwrite() {
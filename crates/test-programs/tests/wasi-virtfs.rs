@@ -0,0 +1,160 @@
+#![cfg(feature = "test_programs")]
+use anyhow::Result;
+use wasi_common::dir::DirAccessMode;
+use wasi_common::virtfs::{VirtfsBuilder, VirtualDir};
+use wasmtime::{Config, Engine, Linker, Module, Store};
+use wasmtime_wasi::sync::{add_to_linker, WasiCtxBuilder};
+
+lazy_static::lazy_static! {
+    static ref ENGINE: Engine = {
+        let mut config = Config::new();
+        config.wasm_backtrace_details(wasmtime::WasmBacktraceDetails::Enable);
+        config.wasm_component_model(false);
+        config.async_support(false);
+
+        let engine = Engine::new(&config).unwrap();
+        engine
+    };
+}
+// uses ENGINE, creates a fn get_module(&str) -> Module
+include!(concat!(env!("OUT_DIR"), "/wasi_tests_modules.rs"));
+
+// Runs a wasi-tests program with an in-memory directory preopened as its
+// scratch directory, so every filesystem call it makes goes through virtfs.
+fn run(name: &str, dir: VirtualDir) -> Result<()> {
+    run_module(name, &get_module(name), dir)
+}
+
+fn run_module(name: &str, module: &Module, dir: VirtualDir) -> Result<()> {
+    let mut linker = Linker::new(&ENGINE);
+    add_to_linker(&mut linker, |cx| cx)?;
+
+    let ctx = WasiCtxBuilder::new()
+        .inherit_stdio()
+        .arg(name)?
+        .arg(".")?
+        .build();
+    ctx.push_preopened_dir(Box::new(dir), ".")?;
+
+    let mut store = Store::new(&ENGINE, ctx);
+    let instance = linker.instantiate(&mut store, module)?;
+    let start = instance.get_typed_func::<(), ()>(&mut store, "_start")?;
+    start.call(&mut store, ())?;
+    Ok(())
+}
+
+#[test_log::test]
+fn file_pread_pwrite() {
+    let fs = VirtfsBuilder::new();
+    run("file_pread_pwrite", fs.build()).unwrap();
+    // The guest removes its file once it has read it back.
+    assert!(fs.build().read_file("file").is_err());
+}
+
+#[test_log::test]
+fn file_seek_tell() {
+    let fs = VirtfsBuilder::new();
+    run("file_seek_tell", fs.build()).unwrap();
+    assert!(fs.build().read_file("file").is_err());
+}
+
+#[test_log::test]
+fn guest_writes_are_visible_to_the_host() {
+    // Creates `file` in the preopened directory, which is fd 3, and writes a
+    // greeting to it.
+    let wasm = wat::parse_str(
+        r#"
+            (module
+                (import "wasi_snapshot_preview1" "path_open"
+                    (func $path_open (param i32 i32 i32 i32 i32 i64 i64 i32 i32) (result i32)))
+                (import "wasi_snapshot_preview1" "fd_write"
+                    (func $fd_write (param i32 i32 i32 i32) (result i32)))
+                (import "wasi_snapshot_preview1" "fd_close"
+                    (func $fd_close (param i32) (result i32)))
+                (memory (export "memory") 1)
+                (data (i32.const 0) "file")
+                (data (i32.const 16) "hello from the guest")
+                (data (i32.const 64) "\10\00\00\00\14\00\00\00")
+                (func (export "_start")
+                    ;; oflags = O_CREAT, rights = FD_WRITE
+                    (if (call $path_open (i32.const 3) (i32.const 0) (i32.const 0) (i32.const 4)
+                            (i32.const 1) (i64.const 64) (i64.const 0) (i32.const 0) (i32.const 80))
+                        (then unreachable))
+                    (if (call $fd_write (i32.load (i32.const 80)) (i32.const 64) (i32.const 1) (i32.const 84))
+                        (then unreachable))
+                    (if (call $fd_close (i32.load (i32.const 80)))
+                        (then unreachable)))
+            )
+        "#,
+    )
+    .unwrap();
+    let module = Module::new(&ENGINE, wasm).unwrap();
+
+    let fs = VirtfsBuilder::new();
+    let root = fs.build();
+    run_module("write", &module, fs.build()).unwrap();
+    assert_eq!(root.read_file("file").unwrap(), b"hello from the guest");
+}
+
+#[test_log::test]
+fn read_only_preopen_rejects_mutation() {
+    let wasm = wat::parse_str(
+        r#"
+            (module
+                (import "wasi_snapshot_preview1" "path_create_directory"
+                    (func $path_create_directory (param i32 i32 i32) (result i32)))
+                (import "wasi_snapshot_preview1" "path_open"
+                    (func $path_open (param i32 i32 i32 i32 i32 i64 i64 i32 i32) (result i32)))
+                (memory (export "memory") 1)
+                (data (i32.const 0) "file")
+                (data (i32.const 16) "dir")
+                (func (export "mkdir") (result i32)
+                    (call $path_create_directory (i32.const 3) (i32.const 16) (i32.const 3)))
+                (func (export "open") (param $oflags i32) (param $rights i64) (result i32)
+                    (call $path_open (i32.const 3) (i32.const 0) (i32.const 0) (i32.const 4)
+                        (local.get $oflags) (local.get $rights) (i64.const 0) (i32.const 0)
+                        (i32.const 32)))
+            )
+        "#,
+    )
+    .unwrap();
+    let module = Module::new(&ENGINE, wasm).unwrap();
+
+    let mut fs = VirtfsBuilder::new();
+    fs.file("file", "contents").unwrap();
+    let mut linker = Linker::new(&ENGINE);
+    add_to_linker(&mut linker, |cx| cx).unwrap();
+    let ctx = WasiCtxBuilder::new().build();
+    ctx.push_preopened_dir_with_access_mode(Box::new(fs.build()), ".", DirAccessMode::READ)
+        .unwrap();
+    let mut store = Store::new(&ENGINE, ctx);
+    let instance = linker.instantiate(&mut store, &module).unwrap();
+    let mkdir = instance
+        .get_typed_func::<(), i32>(&mut store, "mkdir")
+        .unwrap();
+    let open = instance
+        .get_typed_func::<(i32, i64), i32>(&mut store, "open")
+        .unwrap();
+
+    const ERRNO_SUCCESS: i32 = 0;
+    const ERRNO_NOTCAPABLE: i32 = 76;
+    const OFLAGS_CREAT: i32 = 1;
+    const RIGHTS_FD_READ: i64 = 1 << 1;
+    const RIGHTS_FD_WRITE: i64 = 1 << 6;
+
+    assert_eq!(mkdir.call(&mut store, ()).unwrap(), ERRNO_NOTCAPABLE);
+    assert_eq!(
+        open.call(&mut store, (OFLAGS_CREAT, RIGHTS_FD_READ))
+            .unwrap(),
+        ERRNO_NOTCAPABLE
+    );
+    assert_eq!(
+        open.call(&mut store, (0, RIGHTS_FD_WRITE)).unwrap(),
+        ERRNO_NOTCAPABLE
+    );
+    assert_eq!(
+        open.call(&mut store, (0, RIGHTS_FD_READ)).unwrap(),
+        ERRNO_SUCCESS
+    );
+    assert_eq!(fs.build().read_file("file").unwrap(), b"contents");
+}
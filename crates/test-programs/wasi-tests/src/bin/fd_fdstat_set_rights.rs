@@ -0,0 +1,88 @@
+use std::{env, process};
+use wasi_tests::{assert_errno, create_file, open_scratch_directory};
+
+unsafe fn test_fd_fdstat_set_rights(dir_fd: wasi::Fd) {
+    create_file(dir_fd, "file");
+
+    let file_fd = wasi::path_open(
+        dir_fd,
+        0,
+        "file",
+        0,
+        wasi::RIGHTS_FD_READ | wasi::RIGHTS_FD_WRITE,
+        0,
+        0,
+    )
+    .expect("open file readwrite");
+
+    let data = &[1u8; 50];
+    let ciovec = wasi::Ciovec {
+        buf: data.as_ptr(),
+        buf_len: data.len(),
+    };
+    let nwritten = wasi::fd_write(file_fd, &[ciovec]).expect("write before dropping rights");
+    assert_eq!(nwritten, data.len());
+
+    // Drop the write right.
+    wasi::fd_fdstat_set_rights(file_fd, wasi::RIGHTS_FD_READ, 0).expect("dropping write right");
+
+    let stat = wasi::fd_fdstat_get(file_fd).expect("get fdstat after dropping rights");
+    assert!(
+        stat.fs_rights_base & wasi::RIGHTS_FD_READ == wasi::RIGHTS_FD_READ,
+        "read right is kept"
+    );
+    assert!(
+        stat.fs_rights_base & wasi::RIGHTS_FD_WRITE == 0,
+        "write right is dropped"
+    );
+
+    assert_errno!(
+        wasi::fd_write(file_fd, &[ciovec])
+            .err()
+            .expect("write after dropping write right fails"),
+        wasi::ERRNO_BADF
+    );
+
+    // Rights can't be added back.
+    assert_errno!(
+        wasi::fd_fdstat_set_rights(file_fd, wasi::RIGHTS_FD_READ | wasi::RIGHTS_FD_WRITE, 0)
+            .expect_err("regaining write right fails"),
+        wasi::ERRNO_NOTCAPABLE
+    );
+
+    wasi::fd_seek(file_fd, 0, wasi::WHENCE_SET).expect("seeking file");
+    let buffer = &mut [0u8; 50];
+    let iovec = wasi::Iovec {
+        buf: buffer.as_mut_ptr(),
+        buf_len: buffer.len(),
+    };
+    let nread = wasi::fd_read(file_fd, &[iovec]).expect("read after dropping write right");
+    assert_eq!(nread, data.len());
+    assert_eq!(&data[..], &buffer[..]);
+
+    wasi::fd_close(file_fd).expect("close file");
+    wasi::path_unlink_file(dir_fd, "file").expect("unlinking file");
+}
+
+fn main() {
+    let mut args = env::args();
+    let prog = args.next().unwrap();
+    let arg = if let Some(arg) = args.next() {
+        arg
+    } else {
+        eprintln!("usage: {} <scratch directory>", prog);
+        process::exit(1);
+    };
+
+    let dir_fd = match open_scratch_directory(&arg) {
+        Ok(dir_fd) => dir_fd,
+        Err(err) => {
+            eprintln!("{}", err);
+            process::exit(1)
+        }
+    };
+
+    unsafe {
+        test_fd_fdstat_set_rights(dir_fd);
+    }
+}
@@ -256,11 +256,74 @@ unsafe fn test_fd_readwrite_invalid_fd() {
     assert_eq!(err, wasi::ERRNO_BADF)
 }
 
+unsafe fn test_fd_read_before_timeout(dir_fd: wasi::Fd) {
+    let file = wasi::path_open(
+        dir_fd,
+        0,
+        "readable_file",
+        wasi::OFLAGS_CREAT,
+        wasi::RIGHTS_FD_READ | wasi::RIGHTS_FD_WRITE,
+        0,
+        0,
+    )
+    .expect("create readable file");
+    let contents = &[1u8];
+    let ciovec = wasi::Ciovec {
+        buf: contents.as_ptr() as *const _,
+        buf_len: contents.len(),
+    };
+    wasi::fd_write(file, &[ciovec]).expect("write");
+
+    let r#in = [
+        wasi::Subscription {
+            userdata: 1,
+            u: wasi::SubscriptionU {
+                tag: wasi::EVENTTYPE_FD_READ.raw(),
+                u: wasi::SubscriptionUU {
+                    fd_read: wasi::SubscriptionFdReadwrite {
+                        file_descriptor: file,
+                    },
+                },
+            },
+        },
+        wasi::Subscription {
+            userdata: CLOCK_ID,
+            u: wasi::SubscriptionU {
+                tag: wasi::EVENTTYPE_CLOCK.raw(),
+                u: wasi::SubscriptionUU {
+                    clock: wasi::SubscriptionClock {
+                        id: wasi::CLOCKID_MONOTONIC,
+                        timeout: 10_000_000_000, // 10 seconds
+                        precision: 0,
+                        flags: 0,
+                    },
+                },
+            },
+        },
+    ];
+    let out = poll_oneoff_impl(&r#in).unwrap();
+    assert_eq!(out.len(), 1, "should return 1 event, got: {:?}", out);
+    assert_eq!(
+        out[0].userdata, 1,
+        "the readable file should be ready before the timeout"
+    );
+    assert_errno!(out[0].error, wasi::ERRNO_SUCCESS);
+    assert_eq!(
+        out[0].type_,
+        wasi::EVENTTYPE_FD_READ,
+        "the event.type_ should equal FD_READ"
+    );
+
+    wasi::fd_close(file).expect("closing readable_file");
+    wasi::path_unlink_file(dir_fd, "readable_file").expect("removing readable_file");
+}
+
 unsafe fn test_poll_oneoff(dir_fd: wasi::Fd) {
     test_timeout();
     test_sleep();
     test_empty_poll();
     test_fd_readwrite_valid_fd(dir_fd);
+    test_fd_read_before_timeout(dir_fd);
     test_fd_readwrite_invalid_fd();
 }
 fn main() {
@@ -425,6 +425,58 @@ mod test {
         );
     }
 
+    // Symlinks that lead outside of a preopen must not be followed, whether
+    // they are absolute or climb out with `..`.
+    #[cfg(unix)]
+    #[test]
+    fn symlink_escape() {
+        use wasi_common::dir::WasiDir;
+
+        let outside = tempfile::Builder::new()
+            .prefix("cap-std-sync-outside")
+            .tempdir()
+            .expect("create temporary dir");
+        std::fs::write(outside.path().join("secret"), b"secret").expect("create secret");
+        let outside_name = outside.path().file_name().unwrap().to_str().unwrap();
+
+        let tempdir = tempfile::Builder::new()
+            .prefix("cap-std-sync")
+            .tempdir_in(outside.path().parent().unwrap())
+            .expect("create temporary dir");
+        std::os::unix::fs::symlink(outside.path().join("secret"), tempdir.path().join("abs"))
+            .expect("create absolute symlink");
+        std::os::unix::fs::symlink(
+            format!("../{outside_name}/secret"),
+            tempdir.path().join("rel"),
+        )
+        .expect("create relative symlink");
+        std::fs::create_dir(tempdir.path().join("sub")).expect("create sub");
+        std::os::unix::fs::symlink("../..", tempdir.path().join("sub/up"))
+            .expect("create directory symlink");
+
+        let preopen_dir = cap_std::fs::Dir::open_ambient_dir(tempdir.path(), ambient_authority())
+            .expect("open ambient temporary dir");
+        let preopen_dir = Dir::from_cap_std(preopen_dir);
+
+        for path in [
+            "abs",
+            "rel",
+            "sub/up",
+            &format!("sub/up/{outside_name}/secret"),
+            &format!("../{outside_name}/secret"),
+        ] {
+            let result = run(preopen_dir.open_file(
+                true,
+                path,
+                OFlags::empty(),
+                true,
+                false,
+                FdFlags::empty(),
+            ));
+            assert!(result.is_err(), "opening `{path}` escaped the preopen");
+        }
+    }
+
     fn run<F: std::future::Future>(future: F) -> F::Output {
         use std::pin::Pin;
         use std::task::{Context, Poll, RawWaker, RawWakerVTable, Waker};
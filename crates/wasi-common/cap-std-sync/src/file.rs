@@ -84,6 +84,22 @@ impl WasiFile for File {
         self.0.advise(offset, len, convert_advice(advice))?;
         Ok(())
     }
+    async fn allocate(&self, offset: u64, len: u64) -> Result<(), Error> {
+        let end = offset.checked_add(len).ok_or_else(Error::too_big)?;
+        match self.0.allocate(offset, len) {
+            Ok(()) => Ok(()),
+            // Preallocation isn't supported by every platform or filesystem,
+            // so fall back to extending the file, which at least guarantees
+            // the resulting length.
+            Err(e) if is_unsupported(&e) => {
+                if self.0.metadata()?.len() < end {
+                    self.0.set_len(end)?;
+                }
+                Ok(())
+            }
+            Err(e) => Err(e.into()),
+        }
+    }
     async fn set_times(
         &self,
         atime: Option<wasi_common::SystemTimeSpec>,
@@ -240,6 +256,16 @@ pub fn get_fd_flags<Filelike: AsFilelike>(f: Filelike) -> io::Result<wasi_common
     Ok(out)
 }
 
+fn is_unsupported(err: &io::Error) -> bool {
+    #[cfg(unix)]
+    if let Some(errno) = rustix::io::Errno::from_io_error(err) {
+        if errno == rustix::io::Errno::OPNOTSUPP || errno == rustix::io::Errno::NOSYS {
+            return true;
+        }
+    }
+    err.kind() == io::ErrorKind::Unsupported
+}
+
 fn convert_advice(advice: Advice) -> system_interface::fs::Advice {
     match advice {
         Advice::Normal => system_interface::fs::Advice::Normal,
@@ -48,9 +48,16 @@ pub use sched::sched_ctx;
 
 use crate::net::Socket;
 use cap_rand::{Rng, RngCore, SeedableRng};
+use std::io;
 use std::mem;
 use std::path::Path;
-use wasi_common::{file::FileAccessMode, table::Table, Error, WasiCtx, WasiFile};
+use wasi_common::{
+    file::FileAccessMode,
+    pipe::{ReadPipe, WritePipe},
+    table::Table,
+    tty::StripEscapes,
+    Error, WasiClocks, WasiCtx, WasiFile,
+};
 
 pub struct WasiCtxBuilder {
     ctx: WasiCtx,
@@ -115,6 +122,26 @@ impl WasiCtxBuilder {
         self.ctx.set_stderr(f);
         self
     }
+    /// Use `bytes` as the guest's stdin. Reads return the contents of the
+    /// buffer and then report end-of-file.
+    pub fn stdin_bytes(&mut self, bytes: Vec<u8>) -> &mut Self {
+        self.stdin(Box::new(ReadPipe::from(bytes)))
+    }
+    /// Capture the guest's stdout in memory.
+    ///
+    /// The returned pipe shares its buffer with the guest's stdout, so
+    /// [`WritePipe::contents`] returns everything the guest has written.
+    pub fn stdout_pipe(&mut self) -> WritePipe<io::Cursor<Vec<u8>>> {
+        let pipe = WritePipe::new_in_memory();
+        self.stdout(Box::new(pipe.clone()));
+        pipe
+    }
+    /// Capture the guest's stderr in memory, like `stdout_pipe`.
+    pub fn stderr_pipe(&mut self) -> WritePipe<io::Cursor<Vec<u8>>> {
+        let pipe = WritePipe::new_in_memory();
+        self.stderr(Box::new(pipe.clone()));
+        pipe
+    }
     pub fn inherit_stdin(&mut self) -> &mut Self {
         self.stdin(Box::new(crate::stdio::stdin()))
     }
@@ -127,6 +154,24 @@ impl WasiCtxBuilder {
     pub fn inherit_stdio(&mut self) -> &mut Self {
         self.inherit_stdin().inherit_stdout().inherit_stderr()
     }
+    /// Like `inherit_stdio`, but terminal escape sequences written by the
+    /// guest to stdout and stderr are removed before reaching the host.
+    pub fn inherit_stdio_strip_escapes(&mut self) -> &mut Self {
+        self.inherit_stdin()
+            .stdout(Box::new(StripEscapes::new(crate::stdio::stdout())))
+            .stderr(Box::new(StripEscapes::new(crate::stdio::stderr())))
+    }
+    pub fn clocks(&mut self, clocks: WasiClocks) -> &mut Self {
+        *self
+            .ctx
+            .clocks_mut()
+            .expect("builder holds the only reference to its context") = clocks;
+        self
+    }
+    pub fn random(&mut self, random: Box<dyn RngCore + Send + Sync>) -> &mut Self {
+        *self.ctx.random.lock().unwrap() = random;
+        self
+    }
     pub fn preopened_dir(
         &mut self,
         dir: Dir,
@@ -159,3 +204,59 @@ pub fn random_ctx() -> Box<dyn RngCore + Send + Sync> {
     let mut rng = cap_rand::thread_rng(cap_rand::ambient_authority());
     Box::new(cap_rand::rngs::StdRng::from_seed(rng.gen()))
 }
+
+#[cfg(test)]
+mod test {
+    use super::WasiCtxBuilder;
+    use cap_rand::{rngs::StdRng, SeedableRng};
+    use std::time::Duration;
+    use wasi_common::clocks::ManualClock;
+    use wasi_common::snapshots::preview_1::types::Clockid;
+    use wasi_common::snapshots::preview_1::wasi_snapshot_preview1::WasiSnapshotPreview1;
+    use wasi_common::WasiClocks;
+    use wiggle::GuestPtr;
+    use wiggle_test::HostMemory;
+
+    #[test]
+    fn manual_clock() {
+        let clock = ManualClock::new(Duration::from_secs(1_000_000_000));
+        let mut ctx = WasiCtxBuilder::new()
+            .clocks(
+                WasiClocks::new()
+                    .with_system(clock.clone())
+                    .with_monotonic(clock.clone()),
+            )
+            .build();
+
+        let now = run(ctx.clock_time_get(Clockid::Realtime, 0)).unwrap();
+        assert_eq!(now, 1_000_000_000_000_000_000);
+        let now = run(ctx.clock_time_get(Clockid::Monotonic, 0)).unwrap();
+        assert_eq!(now, 0);
+
+        clock.advance(Duration::from_nanos(1234));
+        let now = run(ctx.clock_time_get(Clockid::Realtime, 0)).unwrap();
+        assert_eq!(now, 1_000_000_000_000_001_234);
+        let now = run(ctx.clock_time_get(Clockid::Monotonic, 0)).unwrap();
+        assert_eq!(now, 1234);
+        let res = run(ctx.clock_res_get(Clockid::Monotonic)).unwrap();
+        assert_eq!(res, 1);
+    }
+
+    #[test]
+    fn seeded_random() {
+        let fill = || {
+            let mut ctx = WasiCtxBuilder::new()
+                .random(Box::new(StdRng::seed_from_u64(42)))
+                .build();
+            let memory = HostMemory::new();
+            let buf = GuestPtr::<u8>::new(&memory, 0);
+            run(ctx.random_get(&buf, 64)).unwrap();
+            buf.as_array(64).to_vec().unwrap()
+        };
+        assert_eq!(fill(), fill());
+    }
+
+    fn run<F: std::future::Future>(future: F) -> F::Output {
+        wiggle::run_in_dummy_executor(future).unwrap()
+    }
+}
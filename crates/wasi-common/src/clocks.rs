@@ -1,5 +1,6 @@
 use crate::{Error, ErrorExt};
 use cap_std::time::{Duration, Instant, SystemTime};
+use std::sync::{Arc, Mutex};
 
 pub enum SystemTimeSpec {
     SymbolicNow,
@@ -16,6 +17,64 @@ pub trait WasiMonotonicClock: Send + Sync {
     fn now(&self, precision: Duration) -> Instant;
 }
 
+/// A clock which only moves when told to, for deterministic execution.
+///
+/// It implements both [`WasiSystemClock`] and [`WasiMonotonicClock`]. Clones
+/// share the same time, so a clone kept by the embedder can advance the clock
+/// seen by a running guest.
+#[derive(Clone)]
+pub struct ManualClock {
+    /// Time elapsed since the unix epoch.
+    since_epoch: Arc<Mutex<Duration>>,
+    /// Arbitrary base for the monotonic readings of this clock.
+    base: Instant,
+}
+
+impl ManualClock {
+    /// Creates a clock reading `since_epoch` after the unix epoch.
+    pub fn new(since_epoch: Duration) -> Self {
+        Self {
+            since_epoch: Arc::new(Mutex::new(since_epoch)),
+            base: Instant::from_std(std::time::Instant::now()),
+        }
+    }
+
+    /// Sets the time to `since_epoch` after the unix epoch.
+    ///
+    /// Note that moving the time backwards also moves the monotonic readings
+    /// of this clock backwards.
+    pub fn set(&self, since_epoch: Duration) {
+        *self.since_epoch.lock().unwrap() = since_epoch;
+    }
+
+    /// Moves the time forward by `by`.
+    pub fn advance(&self, by: Duration) {
+        *self.since_epoch.lock().unwrap() += by;
+    }
+
+    fn elapsed(&self) -> Duration {
+        *self.since_epoch.lock().unwrap()
+    }
+}
+
+impl WasiSystemClock for ManualClock {
+    fn resolution(&self) -> Duration {
+        Duration::from_nanos(1)
+    }
+    fn now(&self, _precision: Duration) -> SystemTime {
+        SystemTime::from_std(std::time::UNIX_EPOCH + self.elapsed())
+    }
+}
+
+impl WasiMonotonicClock for ManualClock {
+    fn resolution(&self) -> Duration {
+        Duration::from_nanos(1)
+    }
+    fn now(&self, _precision: Duration) -> Instant {
+        self.base + self.elapsed()
+    }
+}
+
 pub struct WasiMonotonicOffsetClock {
     pub creation_time: cap_std::time::Instant,
     pub abs_clock: Box<dyn WasiMonotonicClock>,
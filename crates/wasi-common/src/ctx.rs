@@ -1,5 +1,5 @@
 use crate::clocks::WasiClocks;
-use crate::dir::{DirEntry, WasiDir};
+use crate::dir::{DirAccessMode, DirEntry, WasiDir};
 use crate::file::{FileAccessMode, FileEntry, WasiFile};
 use crate::sched::WasiSched;
 use crate::string_array::StringArray;
@@ -66,12 +66,18 @@ impl WasiCtx {
     }
 
     pub fn insert_dir(&self, fd: u32, dir: Box<dyn WasiDir>, path: PathBuf) {
-        self.table()
-            .insert_at(fd, Arc::new(DirEntry::new(Some(path), dir)));
+        self.table().insert_at(
+            fd,
+            Arc::new(DirEntry::new(Some(path), dir, DirAccessMode::all())),
+        );
     }
 
     pub fn push_dir(&self, dir: Box<dyn WasiDir>, path: PathBuf) -> Result<u32, Error> {
-        self.table().push(Arc::new(DirEntry::new(Some(path), dir)))
+        self.table().push(Arc::new(DirEntry::new(
+            Some(path),
+            dir,
+            DirAccessMode::all(),
+        )))
     }
 
     pub fn table(&self) -> &Table {
@@ -82,6 +88,10 @@ impl WasiCtx {
         Arc::get_mut(&mut self.0).map(|c| &mut c.table)
     }
 
+    pub fn clocks_mut(&mut self) -> Option<&mut WasiClocks> {
+        Arc::get_mut(&mut self.0).map(|c| &mut c.clocks)
+    }
+
     pub fn push_arg(&mut self, arg: &str) -> Result<(), StringArrayError> {
         let s = Arc::get_mut(&mut self.0).expect(
             "`push_arg` should only be used during initialization before the context is cloned",
@@ -114,8 +124,22 @@ impl WasiCtx {
         dir: Box<dyn WasiDir>,
         path: impl AsRef<Path>,
     ) -> Result<(), Error> {
-        self.table()
-            .push(Arc::new(DirEntry::new(Some(path.as_ref().to_owned()), dir)))?;
+        self.push_preopened_dir_with_access_mode(dir, path, DirAccessMode::all())
+    }
+
+    /// Like [`WasiCtx::push_preopened_dir`], but only permits the accesses in
+    /// `access_mode` through the directory and the directories opened from it.
+    pub fn push_preopened_dir_with_access_mode(
+        &self,
+        dir: Box<dyn WasiDir>,
+        path: impl AsRef<Path>,
+        access_mode: DirAccessMode,
+    ) -> Result<(), Error> {
+        self.table().push(Arc::new(DirEntry::new(
+            Some(path.as_ref().to_owned()),
+            dir,
+            access_mode,
+        )))?;
         Ok(())
     }
 }
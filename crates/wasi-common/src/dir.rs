@@ -1,5 +1,6 @@
 use crate::file::{FdFlags, FileType, Filestat, OFlags, WasiFile};
 use crate::{Error, ErrorExt, SystemTimeSpec};
+use bitflags::bitflags;
 use std::any::Any;
 use std::path::PathBuf;
 use std::sync::Arc;
@@ -97,15 +98,48 @@ pub trait WasiDir: Send + Sync {
 pub(crate) struct DirEntry {
     preopen_path: Option<PathBuf>, // precondition: PathBuf is valid unicode
     pub dir: Box<dyn WasiDir>,
+    pub access_mode: DirAccessMode,
+}
+
+bitflags! {
+    #[derive(Copy, Clone, Debug, PartialEq, Eq)]
+    pub struct DirAccessMode : u32 {
+        /// Listing entries, reading their metadata and links, and opening
+        /// them without creating, truncating or writing.
+        const READ = 0b1;
+        /// Creating, removing, renaming and linking entries, setting their
+        /// times, and opening them for writing.
+        const MUTATE = 0b10;
+    }
 }
 
 impl DirEntry {
-    pub fn new(preopen_path: Option<PathBuf>, dir: Box<dyn WasiDir>) -> Self {
-        DirEntry { preopen_path, dir }
+    pub fn new(
+        preopen_path: Option<PathBuf>,
+        dir: Box<dyn WasiDir>,
+        access_mode: DirAccessMode,
+    ) -> Self {
+        DirEntry {
+            preopen_path,
+            dir,
+            access_mode,
+        }
     }
     pub fn preopen_path(&self) -> &Option<PathBuf> {
         &self.preopen_path
     }
+
+    /// Check that this entry permits every access in `mode`.
+    ///
+    /// Fails with `ENOTCAPABLE`, as the directory handle lacks the rights for
+    /// the operation.
+    pub fn capable(&self, mode: DirAccessMode) -> Result<(), Error> {
+        if self.access_mode.contains(mode) {
+            Ok(())
+        } else {
+            Err(Error::not_capable())
+        }
+    }
 }
 
 pub(crate) trait TableDirExt {
@@ -138,3 +172,19 @@ impl From<ReaddirCursor> for u64 {
         c.0
     }
 }
+
+#[cfg(test)]
+mod test {
+    use super::{DirAccessMode, DirEntry};
+    use crate::virtfs::VirtfsBuilder;
+
+    #[test]
+    fn capable() {
+        let dir = VirtfsBuilder::new().build();
+        let entry = DirEntry::new(None, Box::new(dir), DirAccessMode::READ);
+        assert!(entry.capable(DirAccessMode::READ).is_ok());
+        assert!(entry.capable(DirAccessMode::empty()).is_ok());
+        assert!(entry.capable(DirAccessMode::MUTATE).is_err());
+        assert!(entry.capable(DirAccessMode::all()).is_err());
+    }
+}
@@ -27,8 +27,8 @@
 //! This design makes it possible for `wasi-common` embedders to statically
 //! reason about access to the local filesystem by examining what impls are
 //! linked into an application. We found that this separation of concerns also
-//! makes it pretty enjoyable to write alternative implementations, e.g. a
-//! virtual filesystem (which will land in a future PR).
+//! makes it pretty enjoyable to write alternative implementations, e.g. the
+//! in-memory filesystem in `crate::virtfs`.
 //!
 //! ## Traits for the rest of WASI's features
 //!
@@ -61,6 +61,8 @@ pub mod sched;
 pub mod snapshots;
 mod string_array;
 pub mod table;
+pub mod tty;
+pub mod virtfs;
 
 pub use cap_rand::RngCore;
 pub use clocks::{SystemTimeSpec, WasiClocks, WasiMonotonicClock, WasiSystemClock};
@@ -182,6 +182,14 @@ impl WritePipe<io::Cursor<Vec<u8>>> {
     pub fn new_in_memory() -> Self {
         Self::new(io::Cursor::new(vec![]))
     }
+
+    /// Return a copy of everything written to this pipe so far.
+    ///
+    /// Unlike `try_into_inner` this works while other references to the pipe,
+    /// such as the one held by a `WasiCtx`, are still alive.
+    pub fn contents(&self) -> Vec<u8> {
+        self.borrow().get_ref().clone()
+    }
 }
 
 #[wiggle::async_trait]
@@ -1,4 +1,4 @@
-use crate::file::TableFileExt;
+use crate::file::{FileAccessMode, TableFileExt};
 use crate::sched::{
     subscription::{RwEventFlags, SubscriptionResult},
     Poll, Userdata,
@@ -66,6 +66,9 @@ impl ErrorExt for Error {
     fn perm() -> Self {
         types::Errno::Perm.into()
     }
+    fn not_capable() -> Self {
+        types::Errno::Notcapable.into()
+    }
 }
 
 impl wiggle::GuestErrorType for types::Errno {
@@ -529,6 +532,7 @@ impl wasi_unstable::WasiUnstable for WasiCtx {
         iovs: &types::IovecArray<'a>,
     ) -> Result<types::Size, Error> {
         let f = self.table().get_file(u32::from(fd))?;
+        f.capable(FileAccessMode::READ)?;
 
         let iovs: Vec<wiggle::GuestPtr<[u8]>> = iovs
             .iter()
@@ -602,6 +606,7 @@ impl wasi_unstable::WasiUnstable for WasiCtx {
         offset: types::Filesize,
     ) -> Result<types::Size, Error> {
         let f = self.table().get_file(u32::from(fd))?;
+        f.capable(FileAccessMode::READ)?;
 
         let iovs: Vec<wiggle::GuestPtr<[u8]>> = iovs
             .iter()
@@ -674,6 +679,7 @@ impl wasi_unstable::WasiUnstable for WasiCtx {
         ciovs: &types::CiovecArray<'a>,
     ) -> Result<types::Size, Error> {
         let f = self.table().get_file(u32::from(fd))?;
+        f.capable(FileAccessMode::WRITE)?;
 
         let guest_slices: Vec<wiggle::GuestCow<u8>> = ciovs
             .iter()
@@ -700,6 +706,7 @@ impl wasi_unstable::WasiUnstable for WasiCtx {
         offset: types::Filesize,
     ) -> Result<types::Size, Error> {
         let f = self.table().get_file(u32::from(fd))?;
+        f.capable(FileAccessMode::WRITE)?;
 
         let guest_slices: Vec<wiggle::GuestCow<u8>> = ciovs
             .iter()
@@ -1,5 +1,5 @@
 use crate::{
-    dir::{DirEntry, OpenResult, ReaddirCursor, ReaddirEntity, TableDirExt},
+    dir::{DirAccessMode, DirEntry, OpenResult, ReaddirCursor, ReaddirEntity, TableDirExt},
     file::{
         Advice, FdFlags, FdStat, FileAccessMode, FileEntry, FileType, Filestat, OFlags, RiFlags,
         RoFlags, SdFlags, SiFlags, TableFileExt, WasiFile,
@@ -123,18 +123,13 @@ impl wasi_snapshot_preview1::WasiSnapshotPreview1 for WasiCtx {
     async fn fd_allocate(
         &mut self,
         fd: types::Fd,
-        _offset: types::Filesize,
-        _len: types::Filesize,
+        offset: types::Filesize,
+        len: types::Filesize,
     ) -> Result<(), Error> {
-        // Check if fd is a file, and has rights, just to reject those cases
-        // with the errors expected:
-        let _ = self.table().get_file(u32::from(fd))?;
-        // This operation from cloudabi is linux-specific, isn't even
-        // supported across all linux filesystems, and has no support on macos
-        // or windows. Rather than ship spotty support, it has been removed
-        // from preview 2, and we are no longer supporting it in preview 1 as
-        // well.
-        Err(Error::not_supported())
+        let f = self.table().get_file(u32::from(fd))?;
+        f.capable(FileAccessMode::WRITE)?;
+        f.file.allocate(offset, len).await?;
+        Ok(())
     }
 
     async fn fd_close(&mut self, fd: types::Fd) -> Result<(), Error> {
@@ -207,15 +202,33 @@ impl wasi_snapshot_preview1::WasiSnapshotPreview1 for WasiCtx {
     async fn fd_fdstat_set_rights(
         &mut self,
         fd: types::Fd,
-        _fs_rights_base: types::Rights,
+        fs_rights_base: types::Rights,
         _fs_rights_inheriting: types::Rights,
     ) -> Result<(), Error> {
-        let table = self.table();
         let fd = u32::from(fd);
-        if table.is::<FileEntry>(fd) {
-            let _file_entry: Arc<FileEntry> = table.get(fd)?;
-            Ok(())
-        } else if table.is::<DirEntry>(fd) {
+        if self.table().is::<FileEntry>(fd) {
+            // Rights may only be dropped: the new access mode must be a subset
+            // of the current one.
+            let mut access_mode = FileAccessMode::empty();
+            if fs_rights_base.contains(types::Rights::FD_READ) {
+                access_mode |= FileAccessMode::READ;
+            }
+            if fs_rights_base.contains(types::Rights::FD_WRITE) {
+                access_mode |= FileAccessMode::WRITE;
+            }
+            if let Some(table) = self.table_mut() {
+                let file_entry = table.get_file_mut(fd)?;
+                if !file_entry.access_mode.contains(access_mode) {
+                    return Err(types::Errno::Notcapable.into());
+                }
+                file_entry.access_mode = access_mode;
+                Ok(())
+            } else {
+                log::warn!("`fd_fdstat_set_rights` does not work with wasi-threads enabled");
+                Err(Error::not_supported())
+            }
+        } else if self.table().is::<DirEntry>(fd) {
+            let table = self.table();
             let _dir_entry: Arc<DirEntry> = table.get(fd)?;
             Ok(())
         } else {
@@ -242,11 +255,9 @@ impl wasi_snapshot_preview1::WasiSnapshotPreview1 for WasiCtx {
         fd: types::Fd,
         size: types::Filesize,
     ) -> Result<(), Error> {
-        self.table()
-            .get_file(u32::from(fd))?
-            .file
-            .set_filestat_size(size)
-            .await?;
+        let f = self.table().get_file(u32::from(fd))?;
+        f.capable(FileAccessMode::WRITE)?;
+        f.file.set_filestat_size(size).await?;
         Ok(())
     }
 
@@ -269,19 +280,13 @@ impl wasi_snapshot_preview1::WasiSnapshotPreview1 for WasiCtx {
         let mtim = systimespec(set_mtim, mtim, set_mtim_now).map_err(|e| e.context("mtim"))?;
 
         if table.is::<FileEntry>(fd) {
-            table
-                .get_file(fd)
-                .expect("checked that entry is file")
-                .file
-                .set_times(atim, mtim)
-                .await
+            let f = table.get_file(fd).expect("checked that entry is file");
+            f.capable(FileAccessMode::WRITE)?;
+            f.file.set_times(atim, mtim).await
         } else if table.is::<DirEntry>(fd) {
-            table
-                .get_dir(fd)
-                .expect("checked that entry is dir")
-                .dir
-                .set_times(".", atim, mtim, false)
-                .await
+            let d = table.get_dir(fd).expect("checked that entry is dir");
+            d.capable(DirAccessMode::MUTATE)?;
+            d.dir.set_times(".", atim, mtim, false).await
         } else {
             Err(Error::badf())
         }
@@ -293,10 +298,7 @@ impl wasi_snapshot_preview1::WasiSnapshotPreview1 for WasiCtx {
         iovs: &types::IovecArray<'a>,
     ) -> Result<types::Size, Error> {
         let f = self.table().get_file(u32::from(fd))?;
-        // Access mode check normalizes error returned (windows would prefer ACCES here)
-        if !f.access_mode.contains(FileAccessMode::READ) {
-            Err(types::Errno::Badf)?
-        }
+        f.capable(FileAccessMode::READ)?;
         let f = &f.file;
 
         let iovs: Vec<wiggle::GuestPtr<[u8]>> = iovs
@@ -368,10 +370,7 @@ impl wasi_snapshot_preview1::WasiSnapshotPreview1 for WasiCtx {
         offset: types::Filesize,
     ) -> Result<types::Size, Error> {
         let f = self.table().get_file(u32::from(fd))?;
-        // Access mode check normalizes error returned (windows would prefer ACCES here)
-        if !f.access_mode.contains(FileAccessMode::READ) {
-            Err(types::Errno::Badf)?
-        }
+        f.capable(FileAccessMode::READ)?;
         let f = &f.file;
 
         let iovs: Vec<wiggle::GuestPtr<[u8]>> = iovs
@@ -444,10 +443,7 @@ impl wasi_snapshot_preview1::WasiSnapshotPreview1 for WasiCtx {
         ciovs: &types::CiovecArray<'a>,
     ) -> Result<types::Size, Error> {
         let f = self.table().get_file(u32::from(fd))?;
-        // Access mode check normalizes error returned (windows would prefer ACCES here)
-        if !f.access_mode.contains(FileAccessMode::WRITE) {
-            Err(types::Errno::Badf)?
-        }
+        f.capable(FileAccessMode::WRITE)?;
         let f = &f.file;
 
         let guest_slices: Vec<wiggle::GuestCow<u8>> = ciovs
@@ -475,10 +471,7 @@ impl wasi_snapshot_preview1::WasiSnapshotPreview1 for WasiCtx {
         offset: types::Filesize,
     ) -> Result<types::Size, Error> {
         let f = self.table().get_file(u32::from(fd))?;
-        // Access mode check normalizes error returned (windows would prefer ACCES here)
-        if !f.access_mode.contains(FileAccessMode::WRITE) {
-            Err(types::Errno::Badf)?
-        }
+        f.capable(FileAccessMode::WRITE)?;
         let f = &f.file;
 
         let guest_slices: Vec<wiggle::GuestCow<u8>> = ciovs
@@ -589,13 +582,9 @@ impl wasi_snapshot_preview1::WasiSnapshotPreview1 for WasiCtx {
     ) -> Result<types::Size, Error> {
         let mut bufused = 0;
         let mut buf = buf.clone();
-        for entity in self
-            .table()
-            .get_dir(u32::from(fd))?
-            .dir
-            .readdir(ReaddirCursor::from(cookie))
-            .await?
-        {
+        let d = self.table().get_dir(u32::from(fd))?;
+        d.capable(DirAccessMode::READ)?;
+        for entity in d.dir.readdir(ReaddirCursor::from(cookie)).await? {
             let entity = entity?;
             let dirent_raw = dirent_bytes(types::Dirent::try_from(&entity)?);
             let dirent_len: types::Size = dirent_raw.len().try_into()?;
@@ -639,11 +628,9 @@ impl wasi_snapshot_preview1::WasiSnapshotPreview1 for WasiCtx {
         dirfd: types::Fd,
         path: &GuestPtr<'a, str>,
     ) -> Result<(), Error> {
-        self.table()
-            .get_dir(u32::from(dirfd))?
-            .dir
-            .create_dir(path.as_cow()?.deref())
-            .await
+        let d = self.table().get_dir(u32::from(dirfd))?;
+        d.capable(DirAccessMode::MUTATE)?;
+        d.dir.create_dir(path.as_cow()?.deref()).await
     }
 
     async fn path_filestat_get<'a>(
@@ -652,9 +639,9 @@ impl wasi_snapshot_preview1::WasiSnapshotPreview1 for WasiCtx {
         flags: types::Lookupflags,
         path: &GuestPtr<'a, str>,
     ) -> Result<types::Filestat, Error> {
-        let filestat = self
-            .table()
-            .get_dir(u32::from(dirfd))?
+        let d = self.table().get_dir(u32::from(dirfd))?;
+        d.capable(DirAccessMode::READ)?;
+        let filestat = d
             .dir
             .get_path_filestat(
                 path.as_cow()?.deref(),
@@ -680,9 +667,9 @@ impl wasi_snapshot_preview1::WasiSnapshotPreview1 for WasiCtx {
 
         let atim = systimespec(set_atim, atim, set_atim_now).map_err(|e| e.context("atim"))?;
         let mtim = systimespec(set_mtim, mtim, set_mtim_now).map_err(|e| e.context("mtim"))?;
-        self.table()
-            .get_dir(u32::from(dirfd))?
-            .dir
+        let d = self.table().get_dir(u32::from(dirfd))?;
+        d.capable(DirAccessMode::MUTATE)?;
+        d.dir
             .set_times(
                 path.as_cow()?.deref(),
                 atim,
@@ -703,6 +690,8 @@ impl wasi_snapshot_preview1::WasiSnapshotPreview1 for WasiCtx {
         let table = self.table();
         let src_dir = table.get_dir(u32::from(src_fd))?;
         let target_dir = table.get_dir(u32::from(target_fd))?;
+        src_dir.capable(DirAccessMode::READ)?;
+        target_dir.capable(DirAccessMode::MUTATE)?;
         let symlink_follow = src_flags.contains(types::Lookupflags::SYMLINK_FOLLOW);
         if symlink_follow {
             return Err(Error::invalid_argument()
@@ -754,15 +743,24 @@ impl wasi_snapshot_preview1::WasiSnapshotPreview1 for WasiCtx {
             FileAccessMode::empty()
         };
 
+        dir_entry.capable(DirAccessMode::READ)?;
+        if write || oflags.intersects(OFlags::CREATE | OFlags::TRUNCATE) {
+            dir_entry.capable(DirAccessMode::MUTATE)?;
+        }
+
         let file = dir_entry
             .dir
             .open_file(symlink_follow, path.deref(), oflags, read, write, fdflags)
             .await?;
+        // Directories opened from this one can't be granted more access than it has.
+        let dir_access_mode = dir_entry.access_mode;
         drop(dir_entry);
 
         let fd = match file {
             OpenResult::File(file) => table.push(Arc::new(FileEntry::new(file, access_mode)))?,
-            OpenResult::Dir(child_dir) => table.push(Arc::new(DirEntry::new(None, child_dir)))?,
+            OpenResult::Dir(child_dir) => {
+                table.push(Arc::new(DirEntry::new(None, child_dir, dir_access_mode)))?
+            }
         };
         Ok(types::Fd::from(fd))
     }
@@ -774,9 +772,9 @@ impl wasi_snapshot_preview1::WasiSnapshotPreview1 for WasiCtx {
         buf: &GuestPtr<'a, u8>,
         buf_len: types::Size,
     ) -> Result<types::Size, Error> {
-        let link = self
-            .table()
-            .get_dir(u32::from(dirfd))?
+        let d = self.table().get_dir(u32::from(dirfd))?;
+        d.capable(DirAccessMode::READ)?;
+        let link = d
             .dir
             .read_link(path.as_cow()?.deref())
             .await?
@@ -797,11 +795,9 @@ impl wasi_snapshot_preview1::WasiSnapshotPreview1 for WasiCtx {
         dirfd: types::Fd,
         path: &GuestPtr<'a, str>,
     ) -> Result<(), Error> {
-        self.table()
-            .get_dir(u32::from(dirfd))?
-            .dir
-            .remove_dir(path.as_cow()?.deref())
-            .await
+        let d = self.table().get_dir(u32::from(dirfd))?;
+        d.capable(DirAccessMode::MUTATE)?;
+        d.dir.remove_dir(path.as_cow()?.deref()).await
     }
 
     async fn path_rename<'a>(
@@ -814,6 +810,8 @@ impl wasi_snapshot_preview1::WasiSnapshotPreview1 for WasiCtx {
         let table = self.table();
         let src_dir = table.get_dir(u32::from(src_fd))?;
         let dest_dir = table.get_dir(u32::from(dest_fd))?;
+        src_dir.capable(DirAccessMode::MUTATE)?;
+        dest_dir.capable(DirAccessMode::MUTATE)?;
         src_dir
             .dir
             .rename(
@@ -830,9 +828,9 @@ impl wasi_snapshot_preview1::WasiSnapshotPreview1 for WasiCtx {
         dirfd: types::Fd,
         dest_path: &GuestPtr<'a, str>,
     ) -> Result<(), Error> {
-        self.table()
-            .get_dir(u32::from(dirfd))?
-            .dir
+        let d = self.table().get_dir(u32::from(dirfd))?;
+        d.capable(DirAccessMode::MUTATE)?;
+        d.dir
             .symlink(src_path.as_cow()?.deref(), dest_path.as_cow()?.deref())
             .await
     }
@@ -842,11 +840,9 @@ impl wasi_snapshot_preview1::WasiSnapshotPreview1 for WasiCtx {
         dirfd: types::Fd,
         path: &GuestPtr<'a, str>,
     ) -> Result<(), Error> {
-        self.table()
-            .get_dir(u32::from(dirfd))?
-            .dir
-            .unlink_file(path.as_cow()?.deref())
-            .await
+        let d = self.table().get_dir(u32::from(dirfd))?;
+        d.capable(DirAccessMode::MUTATE)?;
+        d.dir.unlink_file(path.as_cow()?.deref()).await
     }
 
     async fn poll_oneoff<'a>(
@@ -1124,6 +1120,7 @@ impl wasi_snapshot_preview1::WasiSnapshotPreview1 for WasiCtx {
         ri_flags: types::Riflags,
     ) -> Result<(types::Size, types::Roflags), Error> {
         let f = self.table().get_file(u32::from(fd))?;
+        f.capable(FileAccessMode::READ)?;
 
         let iovs: Vec<wiggle::GuestPtr<[u8]>> = ri_data
             .iter()
@@ -1199,6 +1196,7 @@ impl wasi_snapshot_preview1::WasiSnapshotPreview1 for WasiCtx {
         _si_flags: types::Siflags,
     ) -> Result<types::Size, Error> {
         let f = self.table().get_file(u32::from(fd))?;
+        f.capable(FileAccessMode::WRITE)?;
 
         let guest_slices: Vec<wiggle::GuestCow<u8>> = si_data
             .iter()
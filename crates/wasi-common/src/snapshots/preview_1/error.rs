@@ -15,6 +15,7 @@ pub trait ErrorExt {
     fn range() -> Self;
     fn seek_pipe() -> Self;
     fn perm() -> Self;
+    fn not_capable() -> Self;
 }
 
 impl ErrorExt for Error {
@@ -60,6 +61,9 @@ impl ErrorExt for Error {
     fn perm() -> Self {
         Errno::Perm.into()
     }
+    fn not_capable() -> Self {
+        Errno::Notcapable.into()
+    }
 }
 
 #[cfg(unix)]
@@ -253,6 +257,9 @@ impl From<wiggle::GuestError> for Error {
             TryFromIntError { .. } => Errno::Overflow.into(),
             SliceLengthsDiffer { .. } => Errno::Fault.into(),
             BorrowCheckerOutOfHandles { .. } => Errno::Fault.into(),
+            // Host code re-entered the instance while holding borrows of its
+            // memory, which the guest could then have mutated underneath it.
+            BorrowCheckerReentered { .. } => Error::trap(err.into()),
             InFunc { err, .. } => Error::from(*err),
         }
     }
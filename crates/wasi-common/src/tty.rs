@@ -0,0 +1,215 @@
+//! Filtering of terminal escape sequences written by a guest.
+//!
+//! A guest that can write to the host's terminal can also emit control
+//! sequences that clear the screen, move the cursor, or set the window title.
+//! [`StripEscapes`] wraps an output `WasiFile` and drops CSI (`ESC [`) and OSC
+//! (`ESC ]`) sequences, as well as any other two-byte escape, before they reach
+//! the underlying file. Sequences split across several writes are handled.
+use crate::file::{Advice, FdFlags, FileType, Filestat, WasiFile};
+use crate::{Error, SystemTimeSpec};
+use std::any::Any;
+use std::io;
+use std::sync::Mutex;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum State {
+    Ground,
+    Escape,
+    Csi,
+    Osc,
+    OscEscape,
+}
+
+/// An output `WasiFile` which removes terminal escape sequences from
+/// everything written to it.
+pub struct StripEscapes<F> {
+    inner: F,
+    state: Mutex<State>,
+}
+
+impl<F: WasiFile> StripEscapes<F> {
+    /// Wrap `inner`, filtering escape sequences out of every write.
+    pub fn new(inner: F) -> Self {
+        StripEscapes {
+            inner,
+            state: Mutex::new(State::Ground),
+        }
+    }
+
+    /// Return the wrapped file.
+    pub fn into_inner(self) -> F {
+        self.inner
+    }
+
+    fn filter(&self, bufs: &[io::IoSlice<'_>]) -> Vec<u8> {
+        let mut state = self.state.lock().unwrap();
+        let mut out = Vec::new();
+        for b in bufs.iter().flat_map(|buf| buf.iter().copied()) {
+            *state = match (*state, b) {
+                (State::Ground, 0x1b) => State::Escape,
+                (State::Ground, _) => {
+                    out.push(b);
+                    State::Ground
+                }
+                (State::Escape | State::OscEscape, b'[') => State::Csi,
+                (State::Escape | State::OscEscape, b']') => State::Osc,
+                (State::Escape | State::OscEscape, _) => State::Ground,
+                // A CSI sequence ends with a byte in the range `@` to `~`.
+                (State::Csi, 0x40..=0x7e) => State::Ground,
+                (State::Csi, _) => State::Csi,
+                // An OSC sequence ends with BEL or with ST (`ESC \`).
+                (State::Osc, 0x07) => State::Ground,
+                (State::Osc, 0x1b) => State::OscEscape,
+                (State::Osc, _) => State::Osc,
+            };
+        }
+        out
+    }
+}
+
+#[wiggle::async_trait]
+impl<F: WasiFile + 'static> WasiFile for StripEscapes<F> {
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+    #[cfg(unix)]
+    fn pollable(&self) -> Option<rustix::fd::BorrowedFd> {
+        self.inner.pollable()
+    }
+    #[cfg(windows)]
+    fn pollable(&self) -> Option<io_extras::os::windows::RawHandleOrSocket> {
+        self.inner.pollable()
+    }
+    fn isatty(&self) -> bool {
+        self.inner.isatty()
+    }
+    async fn get_filetype(&self) -> Result<FileType, Error> {
+        self.inner.get_filetype().await
+    }
+    async fn datasync(&self) -> Result<(), Error> {
+        self.inner.datasync().await
+    }
+    async fn sync(&self) -> Result<(), Error> {
+        self.inner.sync().await
+    }
+    async fn get_fdflags(&self) -> Result<FdFlags, Error> {
+        self.inner.get_fdflags().await
+    }
+    async fn set_fdflags(&mut self, flags: FdFlags) -> Result<(), Error> {
+        self.inner.set_fdflags(flags).await
+    }
+    async fn get_filestat(&self) -> Result<Filestat, Error> {
+        self.inner.get_filestat().await
+    }
+    async fn set_filestat_size(&self, size: u64) -> Result<(), Error> {
+        self.inner.set_filestat_size(size).await
+    }
+    async fn advise(&self, offset: u64, len: u64, advice: Advice) -> Result<(), Error> {
+        self.inner.advise(offset, len, advice).await
+    }
+    async fn allocate(&self, offset: u64, len: u64) -> Result<(), Error> {
+        self.inner.allocate(offset, len).await
+    }
+    async fn set_times(
+        &self,
+        atime: Option<SystemTimeSpec>,
+        mtime: Option<SystemTimeSpec>,
+    ) -> Result<(), Error> {
+        self.inner.set_times(atime, mtime).await
+    }
+    async fn write_vectored<'a>(&self, bufs: &[io::IoSlice<'a>]) -> Result<u64, Error> {
+        let len: usize = bufs.iter().map(|buf| buf.len()).sum();
+        let filtered = self.filter(bufs);
+        let mut written = 0;
+        while written < filtered.len() {
+            let n = self
+                .inner
+                .write_vectored(&[io::IoSlice::new(&filtered[written..])])
+                .await?;
+            if n == 0 {
+                return Err(io::Error::from(io::ErrorKind::WriteZero).into());
+            }
+            written += usize::try_from(n)?;
+        }
+        // The escape sequences were consumed too, so report them as written.
+        Ok(len.try_into()?)
+    }
+    async fn writable(&self) -> Result<(), Error> {
+        self.inner.writable().await
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::StripEscapes;
+    use crate::file::{FileType, WasiFile};
+    use crate::pipe::WritePipe;
+    use crate::Error;
+    use std::any::Any;
+    use std::io::{self, IoSlice};
+
+    fn write(file: &dyn WasiFile, bytes: &[u8]) {
+        let n = wiggle::run_in_dummy_executor(file.write_vectored(&[IoSlice::new(bytes)]))
+            .unwrap()
+            .unwrap();
+        assert_eq!(n, bytes.len() as u64);
+    }
+
+    fn contents(pipe: WritePipe<std::io::Cursor<Vec<u8>>>) -> Vec<u8> {
+        pipe.try_into_inner().unwrap().into_inner()
+    }
+
+    #[test]
+    fn raw_passthrough() {
+        let pipe = WritePipe::new_in_memory();
+        write(&pipe.clone(), b"a\x1b[2Jb");
+        assert_eq!(contents(pipe), b"a\x1b[2Jb");
+    }
+
+    #[test]
+    fn strips_csi() {
+        let pipe = WritePipe::new_in_memory();
+        let file = StripEscapes::new(pipe.clone());
+        write(&file, b"a\x1b[2Jb\x1b[1;31mc");
+        drop(file);
+        assert_eq!(contents(pipe), b"abc");
+    }
+
+    #[test]
+    fn strips_osc_across_writes() {
+        let pipe = WritePipe::new_in_memory();
+        let file = StripEscapes::new(pipe.clone());
+        write(&file, b"a\x1b]0;ti");
+        write(&file, b"tle\x07b\x1b]2;x\x1b");
+        write(&file, b"\\c\x1b");
+        write(&file, b"7d");
+        drop(file);
+        assert_eq!(contents(pipe), b"abcd");
+    }
+
+    struct Full;
+
+    #[wiggle::async_trait]
+    impl WasiFile for Full {
+        fn as_any(&self) -> &dyn Any {
+            self
+        }
+        async fn get_filetype(&self) -> Result<FileType, Error> {
+            Ok(FileType::CharacterDevice)
+        }
+        async fn write_vectored<'a>(&self, _bufs: &[IoSlice<'a>]) -> Result<u64, Error> {
+            Ok(0)
+        }
+    }
+
+    #[test]
+    fn write_zero_is_an_error() {
+        let file = StripEscapes::new(Full);
+        let err = wiggle::run_in_dummy_executor(file.write_vectored(&[IoSlice::new(b"a")]))
+            .unwrap()
+            .unwrap_err();
+        let err = err.downcast().unwrap_err();
+        let err = err.downcast_ref::<io::Error>().unwrap();
+        assert_eq!(err.kind(), io::ErrorKind::WriteZero);
+    }
+}
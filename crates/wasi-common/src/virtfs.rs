@@ -0,0 +1,662 @@
+//! Virtual in-memory filesystem.
+//!
+//! [`VirtfsBuilder`] assembles a tree of files and directories held entirely in memory, and
+//! produces a [`VirtualDir`] which implements `WasiDir` and can be preopened in a `WasiCtx`. This
+//! lets WASI programs run, for example in tests, without any access to the host filesystem:
+//!
+//! ```
+//! use wasi_common::virtfs::VirtfsBuilder;
+//! let mut fs = VirtfsBuilder::new();
+//! fs.max_file_size(1 << 20)
+//!     .file("etc/motd", "hello from memory!")?
+//!     .dir("tmp")?;
+//! let ctx = wasi_cap_std_sync::WasiCtxBuilder::new().build();
+//! ctx.push_preopened_dir(Box::new(fs.build()), "/")?;
+//! # Ok::<(), wasi_common::Error>(())
+//! ```
+//!
+//! Files may grow through writes up to the configured maximum size, after which writes fail with
+//! `EFBIG`.
+use crate::dir::{OpenResult, ReaddirCursor, ReaddirEntity, WasiDir};
+use crate::error::Errno;
+use crate::file::{FdFlags, FileType, Filestat, OFlags, WasiFile};
+use crate::{Error, ErrorExt};
+use std::any::Any;
+use std::collections::BTreeMap;
+use std::convert::TryInto;
+use std::io::{self, Read};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex, RwLock};
+
+fn next_inode() -> u64 {
+    static NEXT_INODE: AtomicU64 = AtomicU64::new(1);
+    NEXT_INODE.fetch_add(1, Ordering::Relaxed)
+}
+
+struct FileNode {
+    inode: u64,
+    contents: RwLock<Vec<u8>>,
+}
+
+struct DirNode {
+    inode: u64,
+    entries: RwLock<BTreeMap<String, Node>>,
+}
+
+#[derive(Clone)]
+enum Node {
+    File(Arc<FileNode>),
+    Dir(Arc<DirNode>),
+}
+
+impl Node {
+    fn new_file(contents: Vec<u8>) -> Node {
+        Node::File(Arc::new(FileNode {
+            inode: next_inode(),
+            contents: RwLock::new(contents),
+        }))
+    }
+
+    fn filetype(&self) -> FileType {
+        match self {
+            Node::File(_) => FileType::RegularFile,
+            Node::Dir(_) => FileType::Directory,
+        }
+    }
+
+    fn filestat(&self) -> Filestat {
+        let (inode, size) = match self {
+            Node::File(f) => (f.inode, f.contents.read().unwrap().len() as u64),
+            Node::Dir(d) => (d.inode, 0),
+        };
+        Filestat {
+            device_id: 0,
+            inode,
+            filetype: self.filetype(),
+            nlink: 1,
+            size,
+            atim: None,
+            mtim: None,
+            ctim: None,
+        }
+    }
+}
+
+impl DirNode {
+    fn new() -> Arc<DirNode> {
+        Arc::new(DirNode {
+            inode: next_inode(),
+            entries: RwLock::new(BTreeMap::new()),
+        })
+    }
+}
+
+/// Splits a guest path into the directory it names an entry of, plus the name of that entry.
+///
+/// Paths are resolved relative to `root`, and may not escape it through `..`. A path naming `root`
+/// itself resolves to `root` with no entry name.
+fn resolve_parent(
+    root: &Arc<DirNode>,
+    path: &str,
+) -> Result<(Arc<DirNode>, Option<String>), Error> {
+    if path.starts_with('/') {
+        return Err(Error::perm().context("absolute paths are not permitted"));
+    }
+    let mut components = Vec::new();
+    for component in path.split('/') {
+        match component {
+            "" | "." => {}
+            ".." => {
+                if components.pop().is_none() {
+                    return Err(Error::perm().context("path escapes the directory"));
+                }
+            }
+            name => components.push(name),
+        }
+    }
+    let name = match components.pop() {
+        Some(name) => name.to_string(),
+        None => return Ok((root.clone(), None)),
+    };
+    let mut dir = root.clone();
+    for component in components {
+        let next = match dir.entries.read().unwrap().get(component) {
+            Some(Node::Dir(d)) => d.clone(),
+            Some(Node::File(_)) => return Err(Error::not_dir()),
+            None => return Err(Error::not_found()),
+        };
+        dir = next;
+    }
+    Ok((dir, Some(name)))
+}
+
+/// Returns whether `target` is `dir` itself or any directory beneath it.
+fn contains_dir(dir: &Arc<DirNode>, target: &Arc<DirNode>) -> bool {
+    Arc::ptr_eq(dir, target)
+        || dir.entries.read().unwrap().values().any(|node| match node {
+            Node::Dir(d) => contains_dir(d, target),
+            Node::File(_) => false,
+        })
+}
+
+fn lookup(root: &Arc<DirNode>, path: &str) -> Result<Node, Error> {
+    match resolve_parent(root, path)? {
+        (dir, None) => Ok(Node::Dir(dir)),
+        (dir, Some(name)) => dir
+            .entries
+            .read()
+            .unwrap()
+            .get(&name)
+            .cloned()
+            .ok_or_else(Error::not_found),
+    }
+}
+
+/// A builder for an in-memory directory tree.
+pub struct VirtfsBuilder {
+    root: Arc<DirNode>,
+    max_file_size: u64,
+}
+
+impl VirtfsBuilder {
+    /// Create a builder for an empty directory with no limit on file sizes.
+    pub fn new() -> Self {
+        VirtfsBuilder {
+            root: DirNode::new(),
+            max_file_size: u64::MAX,
+        }
+    }
+
+    /// Limit the size, in bytes, that files may grow to through writes or truncation.
+    pub fn max_file_size(&mut self, size: u64) -> &mut Self {
+        self.max_file_size = size;
+        self
+    }
+
+    /// Insert a file at `path` with the given contents, creating any missing parent directories.
+    ///
+    /// An existing file at `path` is replaced.
+    pub fn file(&mut self, path: &str, contents: impl Into<Vec<u8>>) -> Result<&mut Self, Error> {
+        let (parent, name) = self.create_parents(path)?;
+        let mut entries = parent.entries.write().unwrap();
+        if let Some(Node::Dir(_)) = entries.get(&name) {
+            return Err(Errno::Isdir.into());
+        }
+        entries.insert(name, Node::new_file(contents.into()));
+        Ok(self)
+    }
+
+    /// Insert a directory at `path`, creating any missing parent directories.
+    pub fn dir(&mut self, path: &str) -> Result<&mut Self, Error> {
+        let (parent, name) = self.create_parents(path)?;
+        let mut entries = parent.entries.write().unwrap();
+        match entries.get(&name) {
+            Some(Node::Dir(_)) => {}
+            Some(Node::File(_)) => return Err(Error::exist()),
+            None => {
+                entries.insert(name, Node::Dir(DirNode::new()));
+            }
+        }
+        Ok(self)
+    }
+
+    /// Build a `WasiDir` for the root of the tree.
+    ///
+    /// The returned directory shares its contents with this builder, so files written by a guest
+    /// through it can be inspected with [`VirtualDir::read_file`] afterwards.
+    pub fn build(&self) -> VirtualDir {
+        VirtualDir {
+            node: self.root.clone(),
+            max_file_size: self.max_file_size,
+        }
+    }
+
+    fn create_parents(&self, path: &str) -> Result<(Arc<DirNode>, String), Error> {
+        let mut components = path
+            .split('/')
+            .filter(|c| !c.is_empty())
+            .collect::<Vec<_>>();
+        if components.iter().any(|c| *c == "." || *c == "..") {
+            return Err(Error::invalid_argument().context("path must be normalized"));
+        }
+        let name = components
+            .pop()
+            .ok_or_else(|| Error::invalid_argument().context("empty path"))?;
+        let mut dir = self.root.clone();
+        for component in components {
+            let next = dir
+                .entries
+                .write()
+                .unwrap()
+                .entry(component.to_string())
+                .or_insert_with(|| Node::Dir(DirNode::new()))
+                .clone();
+            dir = match next {
+                Node::Dir(d) => d,
+                Node::File(_) => return Err(Error::not_dir()),
+            };
+        }
+        Ok((dir, name.to_string()))
+    }
+}
+
+impl Default for VirtfsBuilder {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// A directory in a virtual filesystem built by [`VirtfsBuilder`].
+pub struct VirtualDir {
+    node: Arc<DirNode>,
+    max_file_size: u64,
+}
+
+impl VirtualDir {
+    /// Read the contents of the file at `path`, relative to this directory.
+    pub fn read_file(&self, path: &str) -> Result<Vec<u8>, Error> {
+        match lookup(&self.node, path)? {
+            Node::File(f) => Ok(f.contents.read().unwrap().clone()),
+            Node::Dir(_) => Err(Errno::Isdir.into()),
+        }
+    }
+
+    fn child(&self, node: Arc<DirNode>) -> VirtualDir {
+        VirtualDir {
+            node,
+            max_file_size: self.max_file_size,
+        }
+    }
+}
+
+#[wiggle::async_trait]
+impl WasiDir for VirtualDir {
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    async fn open_file(
+        &self,
+        _symlink_follow: bool,
+        path: &str,
+        oflags: OFlags,
+        read: bool,
+        write: bool,
+        fdflags: FdFlags,
+    ) -> Result<OpenResult, Error> {
+        let (parent, name) = resolve_parent(&self.node, path)?;
+        let existing = match &name {
+            Some(name) => parent.entries.read().unwrap().get(name).cloned(),
+            None => Some(Node::Dir(parent.clone())),
+        };
+        let node = match existing {
+            Some(_) if oflags.contains(OFlags::CREATE | OFlags::EXCLUSIVE) => {
+                return Err(Error::exist())
+            }
+            Some(node) => node,
+            None if oflags.contains(OFlags::DIRECTORY) => return Err(Error::not_found()),
+            None if oflags.contains(OFlags::CREATE) => {
+                let node = Node::new_file(Vec::new());
+                parent
+                    .entries
+                    .write()
+                    .unwrap()
+                    .insert(name.unwrap(), node.clone());
+                node
+            }
+            None => return Err(Error::not_found()),
+        };
+        match node {
+            Node::Dir(dir) => {
+                if write || oflags.contains(OFlags::TRUNCATE) {
+                    return Err(Errno::Isdir.into());
+                }
+                Ok(OpenResult::Dir(Box::new(self.child(dir))))
+            }
+            Node::File(file) => {
+                if oflags.contains(OFlags::DIRECTORY) {
+                    return Err(Error::not_dir());
+                }
+                if oflags.contains(OFlags::TRUNCATE) {
+                    file.contents.write().unwrap().clear();
+                }
+                Ok(OpenResult::File(Box::new(VirtualFile {
+                    node: file,
+                    position: Mutex::new(0),
+                    append: fdflags.contains(FdFlags::APPEND),
+                    read,
+                    write,
+                    max_file_size: self.max_file_size,
+                })))
+            }
+        }
+    }
+
+    async fn create_dir(&self, path: &str) -> Result<(), Error> {
+        let (parent, name) = resolve_parent(&self.node, path)?;
+        let name = name.ok_or_else(Error::exist)?;
+        let mut entries = parent.entries.write().unwrap();
+        if entries.contains_key(&name) {
+            return Err(Error::exist());
+        }
+        entries.insert(name, Node::Dir(DirNode::new()));
+        Ok(())
+    }
+
+    async fn readdir(
+        &self,
+        cursor: ReaddirCursor,
+    ) -> Result<Box<dyn Iterator<Item = Result<ReaddirEntity, Error>> + Send>, Error> {
+        let mut entries = vec![
+            (".".to_string(), self.node.inode, FileType::Directory),
+            ("..".to_string(), self.node.inode, FileType::Directory),
+        ];
+        for (name, node) in self.node.entries.read().unwrap().iter() {
+            entries.push((name.clone(), node.filestat().inode, node.filetype()));
+        }
+        let cursor = u64::from(cursor);
+        let entities = entries
+            .into_iter()
+            .enumerate()
+            .map(|(ix, (name, inode, filetype))| ReaddirEntity {
+                next: ReaddirCursor::from(ix as u64 + 1),
+                inode,
+                name,
+                filetype,
+            })
+            .skip(cursor.try_into()?)
+            .map(Ok)
+            .collect::<Vec<_>>();
+        Ok(Box::new(entities.into_iter()))
+    }
+
+    async fn remove_dir(&self, path: &str) -> Result<(), Error> {
+        let (parent, name) = resolve_parent(&self.node, path)?;
+        let name = name.ok_or_else(|| Error::from(Errno::Busy))?;
+        let mut entries = parent.entries.write().unwrap();
+        match entries.get(&name) {
+            Some(Node::Dir(d)) if d.entries.read().unwrap().is_empty() => {}
+            Some(Node::Dir(_)) => return Err(Errno::Notempty.into()),
+            Some(Node::File(_)) => return Err(Error::not_dir()),
+            None => return Err(Error::not_found()),
+        }
+        entries.remove(&name);
+        Ok(())
+    }
+
+    async fn unlink_file(&self, path: &str) -> Result<(), Error> {
+        let (parent, name) = resolve_parent(&self.node, path)?;
+        let name = name.ok_or_else(|| Error::from(Errno::Isdir))?;
+        let mut entries = parent.entries.write().unwrap();
+        match entries.get(&name) {
+            Some(Node::File(_)) => {}
+            Some(Node::Dir(_)) => return Err(Errno::Isdir.into()),
+            None => return Err(Error::not_found()),
+        }
+        entries.remove(&name);
+        Ok(())
+    }
+
+    async fn get_filestat(&self) -> Result<Filestat, Error> {
+        Ok(Node::Dir(self.node.clone()).filestat())
+    }
+
+    async fn get_path_filestat(
+        &self,
+        path: &str,
+        _follow_symlinks: bool,
+    ) -> Result<Filestat, Error> {
+        Ok(lookup(&self.node, path)?.filestat())
+    }
+
+    async fn rename(
+        &self,
+        src_path: &str,
+        dest_dir: &dyn WasiDir,
+        dest_path: &str,
+    ) -> Result<(), Error> {
+        let dest_dir = dest_dir
+            .as_any()
+            .downcast_ref::<VirtualDir>()
+            .ok_or_else(|| Error::from(Errno::Xdev))?;
+        let (src_parent, src_name) = resolve_parent(&self.node, src_path)?;
+        let (dest_parent, dest_name) = resolve_parent(&dest_dir.node, dest_path)?;
+        let src_name = src_name.ok_or_else(|| Error::from(Errno::Busy))?;
+        let dest_name = dest_name.ok_or_else(|| Error::from(Errno::Busy))?;
+
+        let node = src_parent
+            .entries
+            .read()
+            .unwrap()
+            .get(&src_name)
+            .cloned()
+            .ok_or_else(Error::not_found)?;
+        // A directory can't be moved into itself or any of its descendants.
+        if let Node::Dir(d) = &node {
+            if contains_dir(d, &dest_parent) {
+                return Err(Error::invalid_argument());
+            }
+        }
+        match (&node, dest_parent.entries.read().unwrap().get(&dest_name)) {
+            (Node::File(_), Some(Node::Dir(_))) => return Err(Errno::Isdir.into()),
+            (Node::Dir(_), Some(Node::File(_))) => return Err(Error::not_dir()),
+            (Node::Dir(_), Some(Node::Dir(d))) if !d.entries.read().unwrap().is_empty() => {
+                return Err(Errno::Notempty.into())
+            }
+            _ => {}
+        }
+        src_parent.entries.write().unwrap().remove(&src_name);
+        dest_parent.entries.write().unwrap().insert(dest_name, node);
+        Ok(())
+    }
+}
+
+/// An open file in a virtual filesystem built by [`VirtfsBuilder`].
+pub struct VirtualFile {
+    node: Arc<FileNode>,
+    position: Mutex<u64>,
+    append: bool,
+    read: bool,
+    write: bool,
+    max_file_size: u64,
+}
+
+impl VirtualFile {
+    fn read_at(&self, bufs: &mut [io::IoSliceMut<'_>], offset: u64) -> Result<u64, Error> {
+        if !self.read {
+            return Err(Error::badf());
+        }
+        let contents = self.node.contents.read().unwrap();
+        let start = contents.len().min(offset.try_into()?);
+        let n = (&contents[start..]).read_vectored(bufs)?;
+        Ok(n.try_into()?)
+    }
+
+    fn write_at(&self, bufs: &[io::IoSlice<'_>], offset: Option<u64>) -> Result<(u64, u64), Error> {
+        if !self.write {
+            return Err(Error::badf());
+        }
+        let mut contents = self.node.contents.write().unwrap();
+        let offset = offset.unwrap_or(contents.len() as u64);
+        let len = bufs.iter().map(|b| b.len() as u64).sum::<u64>();
+        let end = offset.checked_add(len).ok_or_else(Error::overflow)?;
+        if end > self.max_file_size {
+            return Err(Errno::Fbig.into());
+        }
+        let (mut pos, end): (usize, usize) = (offset.try_into()?, end.try_into()?);
+        if contents.len() < end {
+            contents.resize(end, 0);
+        }
+        for buf in bufs {
+            contents[pos..pos + buf.len()].copy_from_slice(buf);
+            pos += buf.len();
+        }
+        Ok((len, end as u64))
+    }
+}
+
+#[wiggle::async_trait]
+impl WasiFile for VirtualFile {
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+    async fn get_filetype(&self) -> Result<FileType, Error> {
+        Ok(FileType::RegularFile)
+    }
+    async fn get_fdflags(&self) -> Result<FdFlags, Error> {
+        Ok(if self.append {
+            FdFlags::APPEND
+        } else {
+            FdFlags::empty()
+        })
+    }
+    async fn get_filestat(&self) -> Result<Filestat, Error> {
+        Ok(Node::File(self.node.clone()).filestat())
+    }
+    async fn set_filestat_size(&self, size: u64) -> Result<(), Error> {
+        if !self.write {
+            return Err(Error::badf());
+        }
+        if size > self.max_file_size {
+            return Err(Errno::Fbig.into());
+        }
+        self.node
+            .contents
+            .write()
+            .unwrap()
+            .resize(size.try_into()?, 0);
+        Ok(())
+    }
+    async fn read_vectored<'a>(&self, bufs: &mut [io::IoSliceMut<'a>]) -> Result<u64, Error> {
+        let mut position = self.position.lock().unwrap();
+        let n = self.read_at(bufs, *position)?;
+        *position += n;
+        Ok(n)
+    }
+    async fn read_vectored_at<'a>(
+        &self,
+        bufs: &mut [io::IoSliceMut<'a>],
+        offset: u64,
+    ) -> Result<u64, Error> {
+        self.read_at(bufs, offset)
+    }
+    async fn write_vectored<'a>(&self, bufs: &[io::IoSlice<'a>]) -> Result<u64, Error> {
+        let mut position = self.position.lock().unwrap();
+        let offset = if self.append { None } else { Some(*position) };
+        let (n, end) = self.write_at(bufs, offset)?;
+        *position = end;
+        Ok(n)
+    }
+    async fn write_vectored_at<'a>(
+        &self,
+        bufs: &[io::IoSlice<'a>],
+        offset: u64,
+    ) -> Result<u64, Error> {
+        let (n, _) = self.write_at(bufs, Some(offset))?;
+        Ok(n)
+    }
+    async fn seek(&self, pos: io::SeekFrom) -> Result<u64, Error> {
+        let mut position = self.position.lock().unwrap();
+        let len = self.node.contents.read().unwrap().len() as u64;
+        let new = match pos {
+            io::SeekFrom::Start(offset) => Some(offset),
+            io::SeekFrom::Current(delta) => position.checked_add_signed(delta),
+            io::SeekFrom::End(delta) => len.checked_add_signed(delta),
+        };
+        *position = new.ok_or_else(Error::invalid_argument)?;
+        Ok(*position)
+    }
+    fn num_ready_bytes(&self) -> Result<u64, Error> {
+        let len = self.node.contents.read().unwrap().len() as u64;
+        Ok(len.saturating_sub(*self.position.lock().unwrap()))
+    }
+    async fn readable(&self) -> Result<(), Error> {
+        Ok(())
+    }
+    async fn writable(&self) -> Result<(), Error> {
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn run<F: std::future::Future>(future: F) -> F::Output {
+        wiggle::run_in_dummy_executor(future).expect("future is ready")
+    }
+
+    fn open(dir: &VirtualDir, path: &str, oflags: OFlags, write: bool) -> Box<dyn WasiFile> {
+        match run(dir.open_file(false, path, oflags, true, write, FdFlags::empty())) {
+            Ok(OpenResult::File(f)) => f,
+            Ok(OpenResult::Dir(_)) => panic!("{path} is a directory"),
+            Err(e) => panic!("failed to open {path}: {e:?}"),
+        }
+    }
+
+    #[test]
+    fn write_then_read_back() {
+        let mut fs = VirtfsBuilder::new();
+        fs.file("data/input.txt", "hello").unwrap();
+        let dir = fs.build();
+
+        let file = open(&dir, "data/output.txt", OFlags::CREATE, true);
+        let n = run(file.write_vectored(&[io::IoSlice::new(b"written in memory")])).unwrap();
+        assert_eq!(n, 17);
+        drop(file);
+
+        let file = open(&dir, "data/../data/output.txt", OFlags::empty(), false);
+        let mut buf = [0; 32];
+        let n = run(file.read_vectored(&mut [io::IoSliceMut::new(&mut buf)])).unwrap();
+        assert_eq!(&buf[..n as usize], b"written in memory");
+        assert_eq!(dir.read_file("data/input.txt").unwrap(), b"hello");
+
+        let names = run(dir.readdir(ReaddirCursor::from(0)))
+            .unwrap()
+            .map(|e| e.unwrap().name)
+            .collect::<Vec<_>>();
+        assert_eq!(names, [".", "..", "data"]);
+    }
+
+    #[test]
+    fn growth_is_bounded() {
+        let mut fs = VirtfsBuilder::new();
+        fs.max_file_size(8);
+        let dir = fs.build();
+
+        let file = open(&dir, "f", OFlags::CREATE, true);
+        run(file.write_vectored(&[io::IoSlice::new(b"12345678")])).unwrap();
+        let err = run(file.write_vectored(&[io::IoSlice::new(b"9")])).unwrap_err();
+        assert_eq!(err.downcast().unwrap(), Errno::Fbig);
+        assert_eq!(dir.read_file("f").unwrap(), b"12345678");
+    }
+
+    #[test]
+    fn paths_stay_inside_the_tree() {
+        let dir = VirtfsBuilder::new().build();
+        for path in ["..", "../f", "/f"] {
+            let err = run(dir.open_file(false, path, OFlags::CREATE, true, true, FdFlags::empty()))
+                .err()
+                .unwrap();
+            assert_eq!(err.downcast().unwrap(), Errno::Perm);
+        }
+    }
+
+    #[test]
+    fn rename_into_descendant_is_rejected() {
+        let mut fs = VirtfsBuilder::new();
+        fs.dir("a/b/c").unwrap();
+        let dir = fs.build();
+
+        for dest in ["a/x", "a/b/x", "a/b/c/x"] {
+            let err = run(dir.rename("a", &dir, dest)).unwrap_err();
+            assert_eq!(err.downcast().unwrap(), Errno::Inval);
+        }
+        let err = run(dir.rename("a/b", &dir, "a/b/c/x")).unwrap_err();
+        assert_eq!(err.downcast().unwrap(), Errno::Inval);
+        assert!(run(dir.get_path_filestat("a/b/c", false)).is_ok());
+
+        run(dir.rename("a/b/c", &dir, "c")).unwrap();
+        assert!(run(dir.get_path_filestat("c", false)).is_ok());
+    }
+}
@@ -527,6 +527,7 @@ impl From<GuestError> for types::Error {
             TryFromIntError { .. } => types::Errno::Overflow.into(),
             SliceLengthsDiffer { .. } => types::Errno::Fault.into(),
             BorrowCheckerOutOfHandles { .. } => types::Errno::Fault.into(),
+            BorrowCheckerReentered { .. } => types::Error::trap(err.into()),
             InFunc { err, .. } => types::Error::from(*err),
         }
     }
@@ -400,13 +400,26 @@ impl<T> LinkerInstance<'_, T> {
         &mut self,
         name: &str,
         dtor: impl Fn(StoreContextMut<'_, T>, u32) + Send + Sync + 'static,
+    ) -> Result<()> {
+        self.resource_fallible::<U>(name, move |cx, param| {
+            dtor(cx, param);
+            Ok(())
+        })
+    }
+
+    /// Same as [`LinkerInstance::resource`], except that `dtor` may fail.
+    ///
+    /// An error returned from `dtor` is propagated to the guest that dropped
+    /// the resource as a trap.
+    pub fn resource_fallible<U: 'static>(
+        &mut self,
+        name: &str,
+        dtor: impl Fn(StoreContextMut<'_, T>, u32) -> Result<()> + Send + Sync + 'static,
     ) -> Result<()> {
         let name = self.strings.intern(name);
         let dtor = Arc::new(crate::func::HostFunc::wrap(
             &self.engine,
-            move |mut cx: crate::Caller<'_, T>, param: u32| {
-                dtor(cx.as_context_mut(), param);
-            },
+            move |mut cx: crate::Caller<'_, T>, param: u32| dtor(cx.as_context_mut(), param),
         ));
         self.insert(name, Definition::Resource(ResourceType::host::<U>(), dtor))
     }
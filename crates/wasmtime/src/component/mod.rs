@@ -46,6 +46,35 @@ pub mod __internal {
     pub use async_trait::async_trait;
     pub use wasmtime_environ;
     pub use wasmtime_environ::component::{CanonicalAbiInfo, ComponentTypes, InterfaceType};
+
+    /// Runs `future` to completion on the current thread, parking the thread
+    /// whenever the future is pending.
+    ///
+    /// Used by the blocking wrappers generated by `bindgen!`.
+    #[cfg(feature = "async")]
+    pub fn block_on<F: std::future::Future>(future: F) -> F::Output {
+        use std::sync::Arc;
+        use std::task::{Context, Poll, Wake};
+        use std::thread::Thread;
+
+        struct ThreadWaker(Thread);
+
+        impl Wake for ThreadWaker {
+            fn wake(self: Arc<Self>) {
+                self.0.unpark();
+            }
+        }
+
+        let waker = Arc::new(ThreadWaker(std::thread::current())).into();
+        let mut cx = Context::from_waker(&waker);
+        let mut future = Box::pin(future);
+        loop {
+            match future.as_mut().poll(&mut cx) {
+                Poll::Ready(result) => return result,
+                Poll::Pending => std::thread::park(),
+            }
+        }
+    }
 }
 
 pub(crate) use self::store::ComponentStoreData;
@@ -283,6 +312,14 @@ pub(crate) use self::store::ComponentStoreData;
 ///     // This option defaults to `false`.
 ///     async: true,
 ///
+///     // When `async` is enabled, also emit a synchronous
+///     // `call_*_blocking` wrapper for each export. These block the current
+///     // thread until the call finishes. They must not be used from within an
+///     // async executor or from a host function called by the same store.
+///     //
+///     // This option defaults to `false`.
+///     generate_blocking_wrappers: true,
+///
 ///     // This can be used to translate WIT return values of the form
 ///     // `result<T, error-type>` into `Result<T, RustErrorType>` in Rust.
 ///     // The `RustErrorType` structure will have an automatically generated
@@ -309,6 +346,31 @@ pub(crate) use self::store::ComponentStoreData;
 ///     with: {
 ///         "a": somewhere::else::a,
 ///     },
+///
+///     // Don't generate a `Debug` impl for the named WIT types, for example
+///     // to avoid printing very large lists or to provide a custom impl.
+///     // Types which are used as errors still require a `Debug` impl.
+///     //
+///     // By default all types get a generated `Debug` impl.
+///     skip_debug: ["big-blob"],
+///
+///     // Wrap the generated code for the named imports in
+///     // `#[cfg(feature = "...")]`. Interfaces are named as in the world and
+///     // functions within them as `interface#function`. Gated interfaces
+///     // aren't included in the world's `add_to_linker`.
+///     //
+///     // By default nothing is gated.
+///     feature_gates: {
+///         "unstable-interface": "unstable",
+///         "a#unstable-function": "unstable",
+///     },
+///
+///     // Generate host trait methods taking `&self` instead of `&mut self`
+///     // for the named imports, named in the same way as `feature_gates`.
+///     // With `async: true` host types then also need to be `Sync`.
+///     //
+///     // By default all host trait methods take `&mut self`.
+///     immutable_imports: ["a", "b#read-only-function"],
 /// });
 /// ```
 ///
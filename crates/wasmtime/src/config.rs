@@ -16,7 +16,7 @@ use wasmtime_environ::Tunables;
 use wasmtime_jit::profiling::{self, ProfilingAgent};
 use wasmtime_runtime::{InstanceAllocator, OnDemandInstanceAllocator, RuntimeMemoryCreator};
 
-pub use wasmtime_environ::CacheStore;
+pub use wasmtime_environ::{CacheStore, ModuleLimits};
 
 /// Represents the module instance allocation strategy to use.
 #[derive(Clone)]
@@ -112,6 +112,8 @@ pub struct Config {
     pub(crate) force_memory_init_memfd: bool,
     pub(crate) coredump_on_trap: bool,
     pub(crate) macos_use_mach_ports: bool,
+    pub(crate) module_limits: ModuleLimits,
+    pub(crate) retain_custom_sections: bool,
 }
 
 /// User-provided configuration for the compiler.
@@ -125,6 +127,7 @@ struct CompilerConfig {
     #[cfg(any(feature = "cranelift", feature = "winch"))]
     cache_store: Option<Arc<dyn CacheStore>>,
     clif_dir: Option<std::path::PathBuf>,
+    collect_metrics: bool,
 }
 
 #[cfg(any(feature = "cranelift", feature = "winch"))]
@@ -137,6 +140,7 @@ impl CompilerConfig {
             flags: HashSet::new(),
             cache_store: None,
             clif_dir: None,
+            collect_metrics: false,
         }
     }
 
@@ -203,6 +207,8 @@ impl Config {
             force_memory_init_memfd: false,
             coredump_on_trap: false,
             macos_use_mach_ports: true,
+            module_limits: ModuleLimits::default(),
+            retain_custom_sections: false,
         };
         #[cfg(any(feature = "cranelift", feature = "winch"))]
         {
@@ -1480,6 +1486,34 @@ impl Config {
         self
     }
 
+    /// Configures limits on the size of core wasm modules that are compiled,
+    /// such as the number of functions or the number of locals in a single
+    /// function.
+    ///
+    /// Modules exceeding any of these limits fail to compile before any of
+    /// their functions are translated, which can be used to bound the work
+    /// done on behalf of untrusted modules. See [`ModuleLimits`] for the
+    /// individual limits.
+    ///
+    /// By default the limits match those already enforced by the validator.
+    pub fn module_limits(&mut self, limits: ModuleLimits) -> &mut Self {
+        self.module_limits = limits;
+        self
+    }
+
+    /// Configures whether the custom sections of compiled core wasm modules,
+    /// such as `name` or `producers`, are kept so they can be inspected with
+    /// [`Module::custom_sections`](crate::Module::custom_sections).
+    ///
+    /// Retained sections are copied into the compiled artifact, so enabling
+    /// this increases the size of compiled and serialized modules.
+    ///
+    /// This option is disabled by default.
+    pub fn retain_custom_sections(&mut self, enable: bool) -> &mut Self {
+        self.retain_custom_sections = enable;
+        self
+    }
+
     /// Configures the "guaranteed dense image size" for copy-on-write
     /// initialized memories.
     ///
@@ -1601,6 +1635,10 @@ impl Config {
             compiler.clif_dir(path)?;
         }
 
+        if self.compiler_config.collect_metrics {
+            compiler.collect_metrics(true)?;
+        }
+
         // If probestack is enabled for a target, Wasmtime will always use the
         // inline strategy which doesn't require us to define a `__probestack`
         // function or similar.
@@ -1696,6 +1734,22 @@ impl Config {
         self
     }
 
+    /// Configures whether the size and compile time of each function are
+    /// recorded during compilation.
+    ///
+    /// When enabled the recorded metrics are available through
+    /// [`Module::function_metrics`](crate::Module::function_metrics). This is
+    /// useful for finding functions which are slow to compile or produce
+    /// large amounts of code.
+    ///
+    /// This is disabled by default and is not supported by the Winch
+    /// compiler.
+    #[cfg(any(feature = "cranelift", feature = "winch"))]
+    pub fn collect_compilation_metrics(&mut self, enable: bool) -> &mut Self {
+        self.compiler_config.collect_metrics = enable;
+        self
+    }
+
     /// Configures whether, when on macOS, Mach ports are used for exception
     /// handling instead of traditional Unix-based signal handling.
     ///
@@ -250,6 +250,34 @@ impl Engine {
         crate::module::HashedEngineCompileEnv(self)
     }
 
+    /// Returns the names of the ISA-specific features, such as `sse42` or
+    /// `avx2` on x86_64 and `lse` on aarch64, that code compiled by this
+    /// engine may use.
+    ///
+    /// Features which Cranelift always requires of a target, such as `sse2`
+    /// on x86_64 and `neon` on aarch64, are included as well.
+    ///
+    /// Unless overridden through [`Config::target`] or
+    /// [`Config::cranelift_flag_set`], these are the features that were
+    /// detected on the host when the engine was created. This can be useful to
+    /// diagnose performance differences or portability issues of precompiled
+    /// modules between machines.
+    #[cfg(any(feature = "cranelift", feature = "winch"))]
+    #[cfg_attr(nightlydoc, doc(cfg(any(feature = "cranelift", feature = "winch"))))]
+    pub fn enabled_features(&self) -> Vec<&'static str> {
+        let baseline: &[&'static str] = match self.compiler().triple().architecture {
+            target_lexicon::Architecture::X86_64 => &["sse", "sse2"],
+            target_lexicon::Architecture::Aarch64(_) => &["neon"],
+            _ => &[],
+        };
+        let detected = self
+            .compiler()
+            .enabled_isa_flags()
+            .into_iter()
+            .filter_map(|name| name.strip_prefix("has_"));
+        baseline.iter().copied().chain(detected).collect()
+    }
+
     pub(crate) fn run_maybe_parallel<
         A: Send,
         B: Send,
@@ -480,6 +508,8 @@ impl Engine {
         {
             enabled = match flag {
                 "has_lse" => Some(std::arch::is_aarch64_feature_detected!("lse")),
+                "has_fp16" => Some(std::arch::is_aarch64_feature_detected!("fp16")),
+                "has_dotprod" => Some(std::arch::is_aarch64_feature_detected!("dotprod")),
                 // No effect on its own, but in order to simplify the code on a
                 // platform without pointer authentication support we fail if
                 // "has_pauth" is enabled, but "sign_return_address" is not.
@@ -547,6 +577,8 @@ impl Engine {
                 "has_avx512bitalg" => Some(std::is_x86_feature_detected!("avx512bitalg")),
                 "has_avx512dq" => Some(std::is_x86_feature_detected!("avx512dq")),
                 "has_avx512f" => Some(std::is_x86_feature_detected!("avx512f")),
+                "has_avx512bw" => Some(std::is_x86_feature_detected!("avx512bw")),
+                "has_avx512cd" => Some(std::is_x86_feature_detected!("avx512cd")),
                 "has_avx512vl" => Some(std::is_x86_feature_detected!("avx512vl")),
                 "has_avx512vbmi" => Some(std::is_x86_feature_detected!("avx512vbmi")),
                 "has_lzcnt" => Some(std::is_x86_feature_detected!("lzcnt")),
@@ -648,6 +680,7 @@ mod tests {
 
     use anyhow::Result;
     use tempfile::TempDir;
+    use wasmtime_environ::FlagValue;
 
     #[test]
     #[cfg_attr(miri, ignore)]
@@ -711,6 +744,43 @@ mod tests {
         Ok(())
     }
 
+    #[test]
+    #[cfg_attr(miri, ignore)]
+    fn cache_persists_across_engines() -> Result<()> {
+        let td = TempDir::new()?;
+        let config_path = td.path().join("config.toml");
+        std::fs::write(
+            &config_path,
+            &format!(
+                "
+                    [cache]
+                    enabled = true
+                    directory = '{}'
+                ",
+                td.path().join("cache").display()
+            ),
+        )?;
+        let new_engine = || -> Result<Engine> {
+            let mut cfg = Config::new();
+            cfg.cache_config_load(&config_path)?;
+            Engine::new(&cfg)
+        };
+
+        let engine = new_engine()?;
+        Module::new(&engine, "(module (func))")?;
+        assert_eq!(engine.config().cache_config.cache_hits(), 0);
+        assert_eq!(engine.config().cache_config.cache_misses(), 1);
+
+        // A fresh engine, as in a new process, loads the compiled module from
+        // disk instead of compiling it again.
+        let engine = new_engine()?;
+        Module::new(&engine, "(module (func))")?;
+        assert_eq!(engine.config().cache_config.cache_hits(), 1);
+        assert_eq!(engine.config().cache_config.cache_misses(), 0);
+
+        Ok(())
+    }
+
     #[test]
     fn precompile_compatibility_key_accounts_for_opt_level() {
         fn hash_for_config(cfg: &Config) -> u64 {
@@ -753,4 +823,48 @@ mod tests {
 
         Ok(())
     }
+
+    #[test]
+    #[cfg_attr(miri, ignore)]
+    fn enabled_features_reflect_host() -> Result<()> {
+        let engine = Engine::default();
+        let features = engine.enabled_features();
+        let flags = engine.compiler().isa_flags();
+        for feature in &features {
+            if let Some(value) = flags.get(&format!("has_{feature}")) {
+                assert_eq!(*value, FlagValue::Bool(true));
+            }
+        }
+        #[allow(unused_variables)]
+        let enabled = |name: &str| features.contains(&name);
+
+        #[cfg(target_arch = "x86_64")]
+        {
+            assert!(enabled("sse2"));
+            assert_eq!(enabled("sse3"), std::is_x86_feature_detected!("sse3"));
+            assert_eq!(enabled("sse42"), std::is_x86_feature_detected!("sse4.2"));
+            assert_eq!(enabled("avx2"), std::is_x86_feature_detected!("avx2"));
+
+            // Explicitly disabling a feature removes it from the list.
+            if enabled("avx2") {
+                let mut cfg = Config::new();
+                unsafe {
+                    cfg.cranelift_flag_set("has_avx2", "false");
+                }
+                let features = Engine::new(&cfg)?.enabled_features();
+                assert!(!features.contains(&"avx2"));
+                assert!(features.contains(&"sse3"));
+            }
+        }
+        #[cfg(target_arch = "aarch64")]
+        {
+            assert!(enabled("neon"));
+            assert_eq!(
+                enabled("lse"),
+                std::arch::is_aarch64_feature_detected!("lse")
+            );
+        }
+
+        Ok(())
+    }
 }
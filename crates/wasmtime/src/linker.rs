@@ -571,6 +571,14 @@ impl<T> Linker<T> {
     /// defined within this store will belong to the `store` provided, and only
     /// the `store` provided.
     ///
+    /// With [`Linker::allow_shadowing`] enabled this can also be used to
+    /// replace a previously defined instance, for example to reload code.
+    /// Later lookups and instantiations will use the exports of the new
+    /// `instance`, while instances which already imported items from the old
+    /// one, and any handles to its exports, remain valid and keep using the
+    /// old definitions. Exports of the old instance which the new one lacks
+    /// are not removed from the linker.
+    ///
     /// # Errors
     ///
     /// Returns an error if the any item is redefined twice in this linker (for
@@ -13,6 +13,7 @@ use std::ops::Range;
 use std::path::Path;
 use std::ptr::NonNull;
 use std::sync::Arc;
+use std::time::Duration;
 use wasmparser::{Parser, ValidPayload, Validator};
 use wasmtime_environ::{
     DefinedFuncIndex, DefinedMemoryIndex, HostPtr, ModuleEnvironment, ModuleTypes, ObjectKind,
@@ -403,6 +404,8 @@ impl Module {
         let parser = wasmparser::Parser::new(0);
         let mut types = Default::default();
         let mut translation = ModuleEnvironment::new(tunables, &mut validator, &mut types)
+            .limits(engine.config().module_limits)
+            .retain_custom_sections(engine.config().retain_custom_sections)
             .translate(parser, wasm)
             .context("failed to parse WebAssembly module")?;
         let functions = mem::take(&mut translation.function_body_inputs);
@@ -723,6 +726,31 @@ impl Module {
         self.compiled_module().module().name.as_deref()
     }
 
+    /// Returns the name and contents of each custom section in this
+    /// [`Module`], in the order they appeared in the original binary.
+    ///
+    /// Custom sections are only retained if
+    /// [`Config::retain_custom_sections`] was enabled when this module was
+    /// compiled, and this is otherwise empty.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use wasmtime::*;
+    /// # fn main() -> anyhow::Result<()> {
+    /// let mut config = Config::new();
+    /// config.retain_custom_sections(true);
+    /// let engine = Engine::new(&config)?;
+    /// let module = Module::new(&engine, "(module (@custom "hello" "world"))")?;
+    /// let sections = module.custom_sections().collect::<Vec<_>>();
+    /// assert_eq!(sections, [("hello", &b"world"[..])]);
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn custom_sections(&self) -> impl ExactSizeIterator<Item = (&str, &[u8])> + '_ {
+        self.compiled_module().custom_sections()
+    }
+
     /// Returns the list of imports that this [`Module`] has and must be
     /// satisfied.
     ///
@@ -1092,6 +1120,24 @@ impl Module {
             (loc.start as usize, loc.length as usize)
         })
     }
+
+    /// Get the compilation metrics of this module's functions, in the same
+    /// order as [`Module::function_locations`].
+    ///
+    /// Each function's metrics are a (code size in bytes, compile time) pair.
+    /// Returns `None` unless the module was compiled with
+    /// [`Config::collect_compilation_metrics`](crate::Config::collect_compilation_metrics)
+    /// enabled.
+    pub fn function_metrics(&self) -> Option<Vec<(usize, Duration)>> {
+        let metrics = self.compiled_module().compilation_metrics()?;
+        Some(
+            metrics
+                .functions
+                .values()
+                .map(|m| (m.code_size as usize, m.compile_time))
+                .collect(),
+        )
+    }
 }
 
 impl ModuleInner {
@@ -1344,3 +1390,96 @@ fn memory_images(engine: &Engine, module: &CompiledModule) -> Result<Option<Modu
     };
     ModuleMemoryImages::new(module.module(), module.code_memory().wasm_data(), mmap)
 }
+
+#[cfg(test)]
+mod tests {
+    use crate::{Engine, Module};
+    use anyhow::Result;
+    use gimli::UnwindSection;
+    use std::collections::HashSet;
+    use wasmtime_environ::DefinedFuncIndex;
+
+    /// Returns the set of registers which the FDE for `func` records as saved
+    /// somewhere on the stack.
+    fn saved_registers(module: &Module, func: u32) -> Result<HashSet<u16>> {
+        let compiled = module.compiled_module();
+        let loc = compiled.func_loc(DefinedFuncIndex::from_u32(func));
+        let record = compiled
+            .unwind_info()?
+            .iter()
+            .find(|r| r.code.start == loc.start as usize)
+            .expect("function should have unwind info");
+        assert_eq!(record.code.len(), loc.length as usize);
+
+        let code = compiled.code_memory();
+        let text_len = code.unwind_info().as_ptr() as usize - code.text().as_ptr() as usize;
+        let eh_frame = gimli::EhFrame::new(code.unwind_info(), gimli::NativeEndian);
+        let bases = gimli::BaseAddresses::default().set_eh_frame(text_len as u64);
+        let fde = eh_frame.fde_from_offset(
+            &bases,
+            gimli::EhFrameOffset(record.unwind_offset),
+            |section, bases, offset| section.cie_from_offset(bases, offset),
+        )?;
+        let mut ctx = gimli::UnwindContext::new();
+        let mut table = fde.rows(&eh_frame, &bases, &mut ctx)?;
+        let mut saved = HashSet::new();
+        while let Some(row) = table.next_row()? {
+            for (reg, rule) in row.registers() {
+                if let gimli::RegisterRule::Offset(_) = rule {
+                    saved.insert(reg.0);
+                }
+            }
+        }
+        Ok(saved)
+    }
+
+    #[test]
+    #[cfg(unix)]
+    #[cfg_attr(miri, ignore)]
+    fn unwind_info_describes_callee_saves() -> Result<()> {
+        let engine = Engine::default();
+        let module = Module::new(
+            &engine,
+            r#"
+                (module
+                    (func $leaf (param i64) (result i64)
+                        local.get 0)
+
+                    ;; All of `$a` through `$f` are live across the call to
+                    ;; `$leaf`, so they're kept in callee-saved registers.
+                    (func $busy (param $p i64) (result i64)
+                        (local $a i64) (local $b i64) (local $c i64)
+                        (local $d i64) (local $e i64) (local $f i64)
+                        (local.set $a (i64.mul (local.get $p) (i64.const 3)))
+                        (local.set $b (i64.mul (local.get $p) (i64.const 5)))
+                        (local.set $c (i64.mul (local.get $p) (i64.const 7)))
+                        (local.set $d (i64.mul (local.get $p) (i64.const 11)))
+                        (local.set $e (i64.mul (local.get $p) (i64.const 13)))
+                        (local.set $f (i64.mul (local.get $p) (i64.const 17)))
+                        (call $leaf (local.get $p))
+                        local.get $a
+                        i64.add
+                        local.get $b
+                        i64.add
+                        local.get $c
+                        i64.add
+                        local.get $d
+                        i64.add
+                        local.get $e
+                        i64.add
+                        local.get $f
+                        i64.add)
+                    (export "busy" (func $busy)))
+            "#,
+        )?;
+
+        let leaf = saved_registers(&module, 0)?;
+        let busy = saved_registers(&module, 1)?;
+        assert!(
+            busy.len() > leaf.len(),
+            "expected callee-saved registers in {busy:?} beyond the frame setup in {leaf:?}"
+        );
+        assert!(busy.is_superset(&leaf));
+        Ok(())
+    }
+}
@@ -23,6 +23,25 @@ impl ExternRef {
     }
 
     /// Get the underlying data for this `ExternRef`.
+    ///
+    /// Use `downcast_ref` on the returned value to recover the concrete type;
+    /// it returns `None` if the data is of a different type.
+    ///
+    /// An `ExternRef` may be cloned, and held by WebAssembly, any number of
+    /// times, so only shared access to its data is ever handed out. Data that
+    /// needs to be mutated should be wrapped in a type with interior
+    /// mutability such as `Mutex` or `RwLock`, whose locking detects
+    /// conflicting accesses:
+    ///
+    /// ```
+    /// # use std::sync::RwLock;
+    /// # use wasmtime::ExternRef;
+    /// let r = ExternRef::new(RwLock::new(1_u32));
+    /// let lock = r.data().downcast_ref::<RwLock<u32>>().unwrap();
+    /// *lock.write().unwrap() += 1;
+    /// assert_eq!(*lock.read().unwrap(), 2);
+    /// assert!(r.data().downcast_ref::<u32>().is_none());
+    /// ```
     pub fn data(&self) -> &dyn Any {
         &*self.inner
     }
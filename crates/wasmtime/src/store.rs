@@ -83,6 +83,7 @@ use crate::trampoline::VMHostGlobalContext;
 use crate::{module::ModuleRegistry, Engine, Module, Trap, Val, ValRaw};
 use crate::{Global, Instance, Memory};
 use anyhow::{anyhow, bail, Result};
+use std::any::Any;
 use std::cell::UnsafeCell;
 use std::convert::TryFrom;
 use std::fmt;
@@ -348,6 +349,10 @@ pub struct StoreOpaque {
     component_host_table: wasmtime_runtime::component::ResourceTable,
     #[cfg(feature = "component-model")]
     component_calls: wasmtime_runtime::component::CallContexts,
+
+    /// State kept on behalf of crates layered on top of Wasmtime, at most one
+    /// value per type. See `StoreContextMut::__host_state`.
+    host_state: Vec<Arc<dyn Any + Send + Sync>>,
 }
 
 #[cfg(feature = "async")]
@@ -488,6 +493,7 @@ impl<T> Store<T> {
                 component_host_table: Default::default(),
                 #[cfg(feature = "component-model")]
                 component_calls: Default::default(),
+                host_state: Vec::new(),
             },
             limiter: None,
             call_hook: None,
@@ -1048,6 +1054,31 @@ impl<'a, T> StoreContextMut<'a, T> {
         self.0.engine()
     }
 
+    /// Returns the value of type `S` kept in this store, creating it with
+    /// `init` the first time it's requested.
+    ///
+    /// This is used by crates such as `wiggle` which need state that lives
+    /// exactly as long as the store but have no control over its `T`.
+    #[doc(hidden)]
+    pub fn __host_state<S>(&mut self, init: impl FnOnce() -> S) -> Arc<S>
+    where
+        S: Any + Send + Sync,
+    {
+        let state = &mut self.0.host_state;
+        let state = match state.iter().find(|s| (***s).is::<S>()) {
+            Some(s) => s.clone(),
+            None => {
+                let s: Arc<dyn Any + Send + Sync> = Arc::new(init());
+                state.push(s.clone());
+                s
+            }
+        };
+        match state.downcast() {
+            Ok(s) => s,
+            Err(_) => unreachable!(),
+        }
+    }
+
     /// Perform garbage collection of `ExternRef`s.
     ///
     /// Same as [`Store::gc`].
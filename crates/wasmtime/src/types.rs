@@ -1,4 +1,6 @@
+use anyhow::{bail, Result};
 use std::fmt;
+use std::str::FromStr;
 use wasmtime_environ::{
     EntityType, Global, Memory, ModuleTypes, Table, WasmFuncType, WasmRefType, WasmType,
 };
@@ -55,6 +57,23 @@ impl fmt::Display for ValType {
     }
 }
 
+impl FromStr for ValType {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<ValType> {
+        Ok(match s {
+            "i32" => ValType::I32,
+            "i64" => ValType::I64,
+            "f32" => ValType::F32,
+            "f64" => ValType::F64,
+            "v128" => ValType::V128,
+            "externref" => ValType::ExternRef,
+            "funcref" => ValType::FuncRef,
+            _ => bail!("unknown value type `{s}`"),
+        })
+    }
+}
+
 impl ValType {
     /// Returns true if `ValType` matches any of the numeric types. (e.g. `I32`,
     /// `I64`, `F32`, `F64`).
@@ -239,6 +258,50 @@ impl FuncType {
     }
 }
 
+/// Renders the signature as `(params) -> (results)`, for example
+/// `(i32, i32) -> (i64)`.
+impl fmt::Display for FuncType {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        fn list(f: &mut fmt::Formatter, tys: impl Iterator<Item = ValType>) -> fmt::Result {
+            write!(f, "(")?;
+            for (i, ty) in tys.enumerate() {
+                if i > 0 {
+                    write!(f, ", ")?;
+                }
+                write!(f, "{ty}")?;
+            }
+            write!(f, ")")
+        }
+        list(f, self.params())?;
+        write!(f, " -> ")?;
+        list(f, self.results())
+    }
+}
+
+/// Parses the format produced by the `Display` impl of [`FuncType`].
+impl FromStr for FuncType {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<FuncType> {
+        fn list(s: &str) -> Result<Vec<ValType>> {
+            let s = s.trim();
+            let inner = match s.strip_prefix('(').and_then(|s| s.strip_suffix(')')) {
+                Some(inner) => inner.trim(),
+                None => bail!("expected a parenthesized list of types, found `{s}`"),
+            };
+            if inner.is_empty() {
+                return Ok(Vec::new());
+            }
+            inner.split(',').map(|ty| ty.trim().parse()).collect()
+        }
+        let (params, results) = match s.split_once("->") {
+            Some(pair) => pair,
+            None => bail!("expected `->` in function type `{s}`"),
+        };
+        Ok(FuncType::new(list(params)?, list(results)?))
+    }
+}
+
 // Global Types
 
 /// A WebAssembly global descriptor.
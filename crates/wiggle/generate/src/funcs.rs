@@ -51,6 +51,21 @@ fn _define_func(
         _ => unimplemented!(),
     };
 
+    // The witx name of the parameter that each wasm parameter was lowered
+    // from, used to give context to errors. Lists are lowered to a pointer
+    // and a length, and any trailing wasm parameters are return pointers.
+    let mut arg_names = Vec::with_capacity(wasm_params.len());
+    for param in func.params.iter() {
+        let slots = match &**param.tref.type_() {
+            witx::Type::List(_) => 2,
+            _ => 1,
+        };
+        for _ in 0..slots {
+            arg_names.push(Some(param.name.as_str().to_string()));
+        }
+    }
+    arg_names.resize(wasm_params.len(), None);
+
     let mut body = TokenStream::new();
     let mut bounds = vec![names::trait_name(&module.name)];
     func.call_interface(
@@ -58,6 +73,7 @@ fn _define_func(
         &mut Rust {
             src: &mut body,
             params: &param_names,
+            arg_names: &arg_names,
             block_storage: Vec::new(),
             blocks: Vec::new(),
             module,
@@ -143,6 +159,7 @@ fn _define_func(
 struct Rust<'a> {
     src: &'a mut TokenStream,
     params: &'a [Ident],
+    arg_names: &'a [Option<String>],
     block_storage: Vec<TokenStream>,
     blocks: Vec<TokenStream>,
     module: &'a witx::Module,
@@ -157,6 +174,17 @@ impl Rust<'_> {
             self.bounds.push(i);
         }
     }
+
+    /// Describes the wasm argument that `operand` refers to, if it refers to
+    /// one directly.
+    fn describe_arg(&self, operand: &TokenStream) -> Option<String> {
+        let operand = operand.to_string();
+        let nth = self.params.iter().position(|p| p.to_string() == operand)?;
+        match &self.arg_names[nth] {
+            Some(name) => Some(format!("argument `{}`", name)),
+            None => Some("return pointer".to_string()),
+        }
+    }
 }
 
 impl witx::Bindgen for Rust<'_> {
@@ -208,9 +236,14 @@ impl witx::Bindgen for Rust<'_> {
             }
         };
 
+        let describe_arg = |operand: &TokenStream| self.describe_arg(operand);
+
         let mut try_from = |ty: TokenStream| {
             let val = operands.pop().unwrap();
-            let wrap_err = wrap_err(&format!("convert {}", ty));
+            let wrap_err = match describe_arg(&val) {
+                Some(arg) => wrap_err(&format!("convert {} of {}", ty, arg)),
+                None => wrap_err(&format!("convert {}", ty)),
+            };
             results.push(quote!(#ty::try_from(#val).map_err(#wrap_err)?));
         };
 
@@ -365,7 +398,10 @@ impl witx::Bindgen for Rust<'_> {
             Instruction::Store { ty } => {
                 let ptr = operands.pop().unwrap();
                 let val = operands.pop().unwrap();
-                let wrap_err = wrap_err(&format!("write {}", ty.name.as_str()));
+                let wrap_err = match describe_arg(&ptr) {
+                    Some(arg) => wrap_err(&format!("write {} to {}", ty.name.as_str(), arg)),
+                    None => wrap_err(&format!("write {}", ty.name.as_str())),
+                };
                 let pointee_type = names::type_(&ty.name);
                 self.src.extend(quote! {
                     wiggle::GuestPtr::<#pointee_type>::new(memory, #ptr as u32)
@@ -376,7 +412,10 @@ impl witx::Bindgen for Rust<'_> {
 
             Instruction::Load { ty } => {
                 let ptr = operands.pop().unwrap();
-                let wrap_err = wrap_err(&format!("read {}", ty.name.as_str()));
+                let wrap_err = match describe_arg(&ptr) {
+                    Some(arg) => wrap_err(&format!("read {} from {}", ty.name.as_str(), arg)),
+                    None => wrap_err(&format!("read {}", ty.name.as_str())),
+                };
                 let pointee_type = names::type_(&ty.name);
                 results.push(quote! {
                     wiggle::GuestPtr::<#pointee_type>::new(memory, #ptr as u32)
@@ -115,9 +115,13 @@ fn generate_func(
         let export = caller.get_export("memory");
         let (mem, ctx) = match &export {
             Some(wiggle::wasmtime_crate::Extern::Memory(m)) => {
+                // The borrow checker lives in the store so that host calls
+                // which re-enter the instance share it.
+                let bc = wiggle::wasmtime_crate::AsContextMut::as_context_mut(&mut caller)
+                    .__host_state(wiggle::borrow::BorrowChecker::new);
                 let (mem, ctx) = m.data_and_store_mut(&mut caller);
                 let ctx = get_cx(ctx);
-                (wiggle::wasmtime::WasmtimeGuestMemory::new(mem), ctx)
+                (wiggle::wasmtime::WasmtimeGuestMemory::with_borrow_checker(mem, bc), ctx)
             }
             Some(wiggle::wasmtime_crate::Extern::SharedMemory(m)) => {
                 let ctx = get_cx(caller.data_mut());
@@ -17,6 +17,8 @@ pub enum GuestError {
     PtrBorrowed(Region),
     #[error("Borrow checker out of handles")]
     BorrowCheckerOutOfHandles,
+    #[error("Instance re-entered while its memory has outstanding borrows")]
+    BorrowCheckerReentered,
     #[error("Slice length mismatch")]
     SliceLengthsDiffer,
     #[error("In func {modulename}::{funcname} at {location}: {err}")]
@@ -1,13 +1,19 @@
 use crate::borrow::BorrowChecker;
 use crate::{BorrowHandle, GuestError, GuestMemory, Region};
 use std::cell::UnsafeCell;
+use std::sync::Arc;
 
 /// Lightweight `wasmtime::Memory` wrapper so we can implement the
 /// `wiggle::GuestMemory` trait on it.
 pub struct WasmtimeGuestMemory<'a> {
     mem: &'a [UnsafeCell<u8>],
-    bc: BorrowChecker,
+    bc: Arc<BorrowChecker>,
     shared: bool,
+    /// Whether this wrapper was created for a re-entrant call while the
+    /// outer call still had borrows of the memory outstanding. All borrows
+    /// made through such a wrapper fail with
+    /// [`GuestError::BorrowCheckerReentered`].
+    reentered: bool,
 }
 
 // These need to be reapplied due to the usage of `UnsafeCell` internally.
@@ -15,7 +21,28 @@ unsafe impl Send for WasmtimeGuestMemory<'_> {}
 unsafe impl Sync for WasmtimeGuestMemory<'_> {}
 
 impl<'a> WasmtimeGuestMemory<'a> {
+    /// Wraps `mem` with a borrow checker of its own.
+    ///
+    /// Nothing else knows about the borrows made through this wrapper, so
+    /// it's only suitable when the memory can't be re-entered while it's
+    /// alive. Use [`WasmtimeGuestMemory::with_borrow_checker`] otherwise.
     pub fn new(mem: &'a mut [u8]) -> Self {
+        Self::with_borrow_checker(mem, Arc::new(BorrowChecker::new()))
+    }
+
+    /// Wraps `mem` using the borrow checker `bc`, which is shared with every
+    /// other wrapper for the same memory.
+    ///
+    /// Host code which re-enters an instance ends up creating a second
+    /// wrapper for the same linear memory further up the stack. Sharing `bc`
+    /// lets that nested wrapper see the borrows of the outer one: if any are
+    /// outstanding then the nested wrapper refuses to hand out borrows of
+    /// its own, see [`WasmtimeGuestMemory::is_reentered`].
+    ///
+    /// The bindings generated by `wiggle` keep one checker per
+    /// `wasmtime::Store`, which is shared by all of the store's memories.
+    pub fn with_borrow_checker(mem: &'a mut [u8], bc: Arc<BorrowChecker>) -> Self {
+        let reentered = bc.has_outstanding_borrows();
         Self {
             // SAFETY: here the `&mut [u8]` is casted to `&[UnsafeCell<u8>]`
             // which is losing in effect the `&mut` access but retaining the
@@ -30,24 +57,28 @@ impl<'a> WasmtimeGuestMemory<'a> {
             // Wiggle does not expose any methods for functions to re-enter
             // the WebAssembly instance, or expose the memory via non-wiggle
             // mechanisms. However, the user-defined code may end up
-            // re-entering the instance, in which case this is an incorrect
-            // implementation - we require exactly one BorrowChecker exist per
-            // instance.
-            // This BorrowChecker construction is a holdover until it is
-            // integrated fully with wasmtime:
-            // https://github.com/bytecodealliance/wasmtime/issues/1917
-            bc: BorrowChecker::new(),
+            // re-entering the instance, in which case there must be exactly
+            // one BorrowChecker for the memory, which the caller provides.
+            bc,
             shared: false,
+            reentered,
         }
     }
 
     pub fn shared(mem: &'a [UnsafeCell<u8>]) -> Self {
         Self {
             mem,
-            bc: BorrowChecker::new(),
+            bc: Arc::new(BorrowChecker::new()),
             shared: true,
+            reentered: false,
         }
     }
+
+    /// Returns whether this memory was entered re-entrantly while an outer
+    /// call still held borrows of it.
+    pub fn is_reentered(&self) -> bool {
+        self.reentered
+    }
 }
 
 unsafe impl GuestMemory for WasmtimeGuestMemory<'_> {
@@ -77,11 +108,17 @@ unsafe impl GuestMemory for WasmtimeGuestMemory<'_> {
     #[inline]
     fn shared_borrow(&self, r: Region) -> Result<BorrowHandle, GuestError> {
         debug_assert!(!self.shared);
+        if self.reentered {
+            return Err(GuestError::BorrowCheckerReentered);
+        }
         self.bc.shared_borrow(r)
     }
     #[inline]
     fn mut_borrow(&self, r: Region) -> Result<BorrowHandle, GuestError> {
         debug_assert!(!self.shared);
+        if self.reentered {
+            return Err(GuestError::BorrowCheckerReentered);
+        }
         self.bc.mut_borrow(r)
     }
     #[inline]
@@ -99,3 +136,72 @@ unsafe impl GuestMemory for WasmtimeGuestMemory<'_> {
         self.shared
     }
 }
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn reentry_shares_borrow_state() {
+        let mut buf = vec![0u8; 64];
+        let ptr = buf.as_mut_ptr();
+        let bc = Arc::new(BorrowChecker::new());
+        let outer = WasmtimeGuestMemory::with_borrow_checker(&mut buf, bc.clone());
+        assert!(!outer.is_reentered());
+
+        // Nothing is borrowed yet, so re-entering is fine and the nested
+        // memory sees the same borrow state as the outer one.
+        let h = {
+            let inner = WasmtimeGuestMemory::with_borrow_checker(
+                unsafe { std::slice::from_raw_parts_mut(ptr, 64) },
+                bc.clone(),
+            );
+            assert!(!inner.is_reentered());
+            inner.mut_borrow(Region::new(0, 8)).expect("can borrow")
+        };
+        assert!(outer.is_mut_borrowed(Region::new(4, 4)));
+        outer.mut_unborrow(h);
+        assert!(!outer.has_outstanding_borrows());
+    }
+
+    #[test]
+    fn reentry_with_outstanding_borrows_is_detected() {
+        let mut buf = vec![0u8; 64];
+        let ptr = buf.as_mut_ptr();
+        let bc = Arc::new(BorrowChecker::new());
+        let outer = WasmtimeGuestMemory::with_borrow_checker(&mut buf, bc.clone());
+        let h = outer.shared_borrow(Region::new(0, 8)).expect("can borrow");
+
+        // Simulates a host function calling back into wasm while it still
+        // holds a borrow of the memory.
+        let inner = WasmtimeGuestMemory::with_borrow_checker(
+            unsafe { std::slice::from_raw_parts_mut(ptr, 64) },
+            bc.clone(),
+        );
+        assert!(inner.is_reentered());
+        assert!(inner.is_shared_borrowed(Region::new(0, 1)));
+        assert_eq!(
+            inner.mut_borrow(Region::new(32, 8)),
+            Err(GuestError::BorrowCheckerReentered)
+        );
+        assert_eq!(
+            inner.shared_borrow(Region::new(32, 8)),
+            Err(GuestError::BorrowCheckerReentered)
+        );
+        drop(inner);
+
+        // Once the outer borrow is released the memory may be re-entered.
+        outer.shared_unborrow(h);
+        let inner = WasmtimeGuestMemory::with_borrow_checker(
+            unsafe { std::slice::from_raw_parts_mut(ptr, 64) },
+            bc,
+        );
+        assert!(!inner.is_reentered());
+
+        // A memory with a checker of its own knows nothing about `outer`.
+        let h = outer.shared_borrow(Region::new(0, 8)).expect("can borrow");
+        let other = WasmtimeGuestMemory::new(unsafe { std::slice::from_raw_parts_mut(ptr, 64) });
+        assert!(!other.is_reentered());
+        outer.shared_unborrow(h);
+    }
+}
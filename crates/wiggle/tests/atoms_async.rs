@@ -104,6 +104,51 @@ proptest! {
     }
 }
 
+// Only the functions listed in `async` get `async fn` trait methods, the
+// remaining ones stay synchronous.
+mod partially_async {
+    use super::run;
+    use wiggle_test::{impl_errno, HostMemory, WasiCtx};
+
+    wiggle::from_witx!({
+        witx: ["$CARGO_MANIFEST_DIR/tests/atoms.witx"],
+        async: {
+            atoms::int_float_args,
+        },
+    });
+
+    impl_errno!(types::Errno);
+
+    #[wiggle::async_trait]
+    impl<'a> atoms::Atoms for WasiCtx<'a> {
+        async fn int_float_args(
+            &mut self,
+            _an_int: u32,
+            _an_float: f32,
+        ) -> Result<(), types::Errno> {
+            Ok(())
+        }
+        fn double_int_return_float(
+            &mut self,
+            an_int: u32,
+        ) -> Result<types::AliasToFloat, types::Errno> {
+            Ok((an_int as f32) * 2.0)
+        }
+    }
+
+    #[test]
+    fn only_listed_functions_are_async() {
+        let mut ctx = WasiCtx::new();
+        let host_memory = HostMemory::new();
+
+        let e = run(atoms::int_float_args(&mut ctx, &host_memory, 1, 2.0)).unwrap();
+        assert_eq!(e, types::Errno::Ok as i32, "int_float_args error");
+
+        let e = atoms::double_int_return_float(&mut ctx, &host_memory, 1, 0).unwrap();
+        assert_eq!(e, types::Errno::Ok as i32, "double_int_return_float error");
+    }
+}
+
 fn run<F: Future>(future: F) -> F::Output {
     let mut f = Pin::from(Box::new(future));
     let waker = dummy_waker();
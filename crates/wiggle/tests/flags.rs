@@ -111,3 +111,23 @@ fn flags_fmt() {
     let all = format!("{}", types::CarConfig::all());
     assert_eq!(all, "CarConfig(CarConfig(AUTOMATIC | AWD | SUV) (0x7))");
 }
+
+#[test]
+fn invalid_flags_names_argument() {
+    let mut ctx = WasiCtx::new();
+    let host_memory = HostMemory::new();
+    let err = flags::configure_car(&mut ctx, &host_memory, 0xff, 0, 8).unwrap_err();
+    let msg = err.to_string();
+    assert!(msg.contains("flags::configure_car"), "{}", msg);
+    assert!(msg.contains("argument `old_config`"), "{}", msg);
+}
+
+#[test]
+fn out_of_bounds_return_pointer() {
+    let mut ctx = WasiCtx::new();
+    let host_memory = HostMemory::new();
+    let err = flags::configure_car(&mut ctx, &host_memory, 1, 0, -1).unwrap_err();
+    let msg = err.to_string();
+    assert!(msg.contains("flags::configure_car"), "{}", msg);
+    assert!(msg.contains("return pointer"), "{}", msg);
+}
@@ -79,6 +79,7 @@ impl wasmtime_environ::Compiler for Compiler {
             WasmFunctionInfo {
                 start_srcloc,
                 stack_maps: Box::new([]),
+                metrics: None,
             },
             Box::new(compiled_function),
         ))
@@ -193,6 +194,10 @@ impl wasmtime_environ::Compiler for Compiler {
         wasmtime_cranelift_shared::clif_flags_to_wasmtime(self.isa.isa_flags())
     }
 
+    fn enabled_isa_flags(&self) -> Vec<&'static str> {
+        wasmtime_cranelift_shared::enabled_clif_flags(self.isa.isa_flags())
+    }
+
     fn is_branch_protection_enabled(&self) -> bool {
         self.isa.is_branch_protection_enabled()
     }
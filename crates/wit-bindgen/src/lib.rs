@@ -3,7 +3,7 @@ use crate::types::{TypeInfo, Types};
 use anyhow::{anyhow, bail, Context};
 use heck::*;
 use indexmap::IndexMap;
-use std::collections::{BTreeMap, HashMap};
+use std::collections::{BTreeMap, HashMap, HashSet};
 use std::fmt::Write as _;
 use std::io::{Read, Write};
 use std::mem;
@@ -47,15 +47,27 @@ struct Wasmtime {
     sizes: SizeAlign,
     interface_names: HashMap<InterfaceId, InterfaceName>,
     with_name_counter: usize,
+    /// Resources defined in exported interfaces, which are implemented by
+    /// the guest rather than the host.
+    exported_resources: HashSet<TypeId>,
 }
 
 struct ImportInterface {
     snake: String,
     module: String,
+    /// Whether this interface is gated behind a feature with
+    /// `Opts::feature_gates`.
+    gated: bool,
+    /// Whether any host trait method of this interface takes `&self`.
+    immutable: bool,
 }
 struct ImportFunction {
     add_to_linker: String,
     sig: String,
+    /// Whether the host trait method for this function takes `&self`.
+    immutable: bool,
+    /// The Rust method name and WIT name of this function.
+    names: (String, String),
 }
 
 #[derive(Default)]
@@ -63,6 +75,8 @@ struct Exports {
     fields: BTreeMap<String, (String, String)>,
     modules: BTreeMap<Option<PackageName>, Vec<String>>,
     funcs: Vec<String>,
+    /// WIT names of every exported function, in declaration order.
+    names: Vec<String>,
 }
 
 #[derive(Default, Debug, Clone, Copy)]
@@ -95,6 +109,16 @@ pub struct Opts {
     /// Whether or not to use async rust functions and traits.
     pub async_: bool,
 
+    /// Whether or not to emit a synchronous `call_*_blocking` wrapper next
+    /// to each async export. Only used when `async_` is set.
+    ///
+    /// The wrappers block the current thread until the export completes, so
+    /// they must not be called from within an async executor or from a host
+    /// function running on the same store. Futures which rely on a specific
+    /// runtime, such as host functions awaiting tokio timers, will not make
+    /// progress either.
+    pub generate_blocking_wrappers: bool,
+
     /// A list of "trappable errors" which are used to replace the `E` in
     /// `result<T, E>` found in WIT.
     pub trappable_error_type: Vec<TrappableError>,
@@ -108,6 +132,35 @@ pub struct Opts {
     /// Remapping of interface names to rust module names.
     /// TODO: is there a better type to use for the value of this map?
     pub with: HashMap<String, String>,
+
+    /// WIT names of types which should not get a generated `Debug` impl,
+    /// leaving it to be derived or implemented by hand. Note that error
+    /// types still require `Debug` for their generated `Display` and `Error`
+    /// impls.
+    pub skip_debug: Vec<String>,
+
+    /// Pairs of WIT import names and Cargo features. Generated code for a
+    /// listed import is wrapped in `#[cfg(feature = "...")]`.
+    ///
+    /// Interfaces are named as they are in the world, for example `foo` or
+    /// `wasi:io/streams`, and gating one removes its module entirely. Gated
+    /// interfaces are also left out of the world's `add_to_linker` and
+    /// `add_to_linker_with` and must be added to a linker through their own
+    /// `add_to_linker` function. Functions are named either by their name
+    /// for world-level imports or `interface#function` for functions within
+    /// an interface, and gating one removes both its host trait method and
+    /// its linker registration.
+    pub feature_gates: Vec<(String, String)>,
+
+    /// WIT imports whose host trait methods take `&self` rather than
+    /// `&mut self`, allowing them to be implemented on shared, possibly
+    /// `Sync`, host state.
+    ///
+    /// Entries are named like those of `feature_gates`: an interface name
+    /// covers all of its functions, and individual functions are named
+    /// either by their name for world-level imports or `interface#function`.
+    /// With `async_` enabled host types must then also be `Sync`.
+    pub immutable_imports: Vec<String>,
 }
 
 #[derive(Debug, Clone)]
@@ -123,14 +176,56 @@ pub struct TrappableError {
 }
 
 impl Opts {
-    pub fn generate(&self, resolve: &Resolve, world: WorldId) -> String {
+    pub fn generate(&self, resolve: &Resolve, world: WorldId) -> anyhow::Result<String> {
+        check_supported(resolve, world)?;
         let mut r = Wasmtime::default();
         r.sizes.fill(resolve);
         r.opts = self.clone();
-        r.generate(resolve, world)
+        Ok(r.generate(resolve, world))
     }
 }
 
+/// Returns an error if `world` uses a feature which bindings can't yet be
+/// generated for.
+fn check_supported(resolve: &Resolve, world: WorldId) -> anyhow::Result<()> {
+    let is_resource = |id: TypeId| matches!(resolve.types[id].kind, TypeDefKind::Resource);
+    let world = &resolve.worlds[world];
+    for (name, item) in world.imports.iter() {
+        if let WorldItem::Type(id) = item {
+            if is_resource(*id) {
+                bail!(
+                    "resource `{}` is imported directly into a world, which is not supported yet",
+                    resolve.name_world_key(name)
+                );
+            }
+        }
+    }
+    for (name, item) in world.exports.iter() {
+        let iface = match item {
+            WorldItem::Interface(id) => *id,
+            _ => continue,
+        };
+        // A resource is generated differently depending on whether the host
+        // or the guest implements it, so it can't be both.
+        let imported = world
+            .imports
+            .values()
+            .any(|item| matches!(item, WorldItem::Interface(id) if *id == iface));
+        let resource = resolve.interfaces[iface]
+            .types
+            .iter()
+            .find(|(_, id)| is_resource(**id));
+        if let (true, Some((resource, _))) = (imported, resource) {
+            bail!(
+                "resource `{resource}` is both imported and exported through `{}`, \
+                 which is not supported yet",
+                resolve.name_world_key(name)
+            );
+        }
+    }
+    Ok(())
+}
+
 impl Wasmtime {
     fn name_interface(&mut self, resolve: &Resolve, id: InterfaceId, name: &WorldKey) -> bool {
         let with_name = resolve.name_world_key(name);
@@ -171,6 +266,16 @@ impl Wasmtime {
     fn generate(&mut self, resolve: &Resolve, id: WorldId) -> String {
         self.types.analyze(resolve, id);
         let world = &resolve.worlds[id];
+        for export in world.exports.values() {
+            if let WorldItem::Interface(iface) = export {
+                self.exported_resources.extend(
+                    resolve.interfaces[*iface]
+                        .types
+                        .values()
+                        .filter(|id| matches!(resolve.types[**id].kind, TypeDefKind::Resource)),
+                );
+            }
+        }
         for (name, import) in world.imports.iter() {
             if !self.opts.only_interfaces || matches!(import, WorldItem::Interface(_)) {
                 self.import(resolve, name, import);
@@ -188,12 +293,21 @@ impl Wasmtime {
         let mut gen = InterfaceGenerator::new(self, resolve);
         match item {
             WorldItem::Function(func) => {
+                let cfg = gen.gen.cfg_feature(&func.name);
+                gen.push_str(&cfg);
                 gen.generate_function_trait_sig(func);
                 let sig = mem::take(&mut gen.src).into();
+                gen.push_str(&cfg);
                 gen.generate_add_function_to_linker(TypeOwner::None, func, "linker");
                 let add_to_linker = gen.src.into();
-                self.import_functions
-                    .push(ImportFunction { sig, add_to_linker });
+                let names = (func_rust_name(func), func.name.clone());
+                let immutable = gen.is_immutable_import(func);
+                self.import_functions.push(ImportFunction {
+                    sig,
+                    add_to_linker,
+                    names,
+                    immutable,
+                });
             }
             WorldItem::Interface(id) => {
                 if gen.gen.name_interface(resolve, *id, name) {
@@ -203,6 +317,7 @@ impl Wasmtime {
                 gen.types(*id);
                 let key_name = resolve.name_world_key(name);
                 gen.generate_add_to_linker(*id, &key_name);
+                let immutable = gen.has_immutable_imports(*id);
 
                 let module = &gen.src[..];
 
@@ -214,10 +329,15 @@ impl Wasmtime {
                         .unwrap()
                         .to_snake_case(),
                 };
+                let cfg = gen.gen.cfg_feature(&key_name);
+                let gated = !cfg.is_empty();
+                let docs = module_docs(&resolve.interfaces[*id].docs);
                 let module = format!(
                     "
+                        {cfg}
                         #[allow(clippy::all)]
                         pub mod {snake} {{
+                            {docs}
                             #[allow(unused_imports)]
                             use wasmtime::component::__internal::anyhow;
 
@@ -233,7 +353,12 @@ impl Wasmtime {
                 self.import_interfaces
                     .entry(pkgname)
                     .or_insert(Vec::new())
-                    .push(ImportInterface { snake, module });
+                    .push(ImportInterface {
+                        snake,
+                        module,
+                        gated,
+                        immutable,
+                    });
             }
             WorldItem::Type(ty) => {
                 let name = match name {
@@ -256,6 +381,7 @@ impl Wasmtime {
                 let (_name, getter) = gen.extract_typed_function(func);
                 assert!(gen.src.is_empty());
                 self.exports.funcs.push(body);
+                self.exports.names.push(func.name.clone());
                 (
                     func.name.to_snake_case(),
                     "wasmtime::component::Func".to_string(),
@@ -311,11 +437,13 @@ impl Wasmtime {
 
                 let module = &gen.src[..];
                 let snake = iface_name.to_snake_case();
+                let docs = module_docs(&iface.docs);
 
                 let module = format!(
                     "
                         #[allow(clippy::all)]
                         pub mod {snake} {{
+                            {docs}
                             #[allow(unused_imports)]
                             use wasmtime::component::__internal::anyhow;
 
@@ -336,6 +464,9 @@ impl Wasmtime {
                     .push(module);
 
                 let name = resolve.name_world_key(name);
+                for (_, func) in iface.functions.iter() {
+                    self.exports.names.push(format!("{name}#{}", func.name));
+                }
                 let (path, method_name) = match pkgname {
                     Some(pkgname) => (
                         format!(
@@ -389,6 +520,7 @@ impl Wasmtime {
         };
 
         self.toplevel_import_trait(resolve, world);
+        self.toplevel_getters_struct(resolve, world);
 
         uwriteln!(self.src, "const _: () = {{");
         uwriteln!(self.src, "use wasmtime::component::__internal::anyhow;");
@@ -448,6 +580,24 @@ impl Wasmtime {
         uwriteln!(self.src, "}})");
         uwriteln!(self.src, "}}"); // close `fn new`
 
+        uwrite!(
+            self.src,
+            "
+                /// Returns the names of all exported functions bound by this
+                /// structure.
+                ///
+                /// Functions exported from an interface are named
+                /// `interface#function`.
+                pub fn export_names(&self) -> &'static [&'static str] {{
+                    &[
+            "
+        );
+        for name in self.exports.names.iter() {
+            uwriteln!(self.src, "{name:?},");
+        }
+        uwriteln!(self.src, "]");
+        uwriteln!(self.src, "}}"); // close `fn export_names`
+
         for func in self.exports.funcs.iter() {
             self.src.push_str(func);
         }
@@ -537,12 +687,50 @@ impl Wasmtime {
 }
 
 impl Wasmtime {
+    /// Returns the `#[cfg]` attribute for the import `name` if it's gated
+    /// through `Opts::feature_gates`, or an empty string otherwise.
+    fn cfg_feature(&self, name: &str) -> String {
+        match self.opts.feature_gates.iter().find(|(n, _)| n == name) {
+            Some((_, feature)) => format!("#[cfg(feature = {feature:?})]\n"),
+            None => String::new(),
+        }
+    }
+
+    /// Returns the bound placed on host types in addition to their host
+    /// traits: async host functions are `Send`, and hosts with `&self`
+    /// methods, as indicated by `immutable`, must also be `Sync`.
+    fn host_bound(&self, immutable: bool) -> &'static str {
+        if !self.opts.async_ {
+            ""
+        } else if immutable {
+            " + Send + Sync"
+        } else {
+            " + Send"
+        }
+    }
+
+    /// Returns whether any of the root-level imported functions take `&self`.
+    fn root_is_immutable(&self) -> bool {
+        self.import_functions.iter().any(|f| f.immutable)
+    }
+
     fn toplevel_import_trait(&mut self, resolve: &Resolve, world: WorldId) {
         if self.import_functions.is_empty() {
             return;
         }
 
         let world_camel = to_rust_upper_camel_case(&resolve.worlds[world].name);
+        let names = self
+            .import_functions
+            .iter()
+            .map(|f| f.names.clone())
+            .collect::<Vec<_>>();
+        uwriteln!(
+            self.src,
+            "/// Functions imported directly by the `{}` world.",
+            resolve.worlds[world].name
+        );
+        push_required_methods_doc(&mut self.src, &names);
         if self.opts.async_ {
             uwriteln!(self.src, "#[wasmtime::component::__internal::async_trait]")
         }
@@ -554,13 +742,13 @@ impl Wasmtime {
         uwriteln!(self.src, "}}");
     }
 
-    fn toplevel_add_to_linker(&mut self, resolve: &Resolve, world: WorldId) {
-        if self.import_interfaces.is_empty() && self.import_functions.is_empty() {
-            return;
-        }
+    /// Returns the module paths of all imported interfaces which aren't gated
+    /// behind a feature, along with whether any of their host methods take
+    /// `&self`.
+    fn import_interface_paths(&self) -> Vec<(String, bool)> {
         let mut interfaces = Vec::new();
         for (pkg, imports) in self.import_interfaces.iter() {
-            for import in imports {
+            for import in imports.iter().filter(|i| !i.gated) {
                 let mut path = String::new();
                 if let Some(pkg) = pkg {
                     path.push_str(&pkg.namespace.to_snake_case());
@@ -569,9 +757,76 @@ impl Wasmtime {
                     path.push_str("::");
                 }
                 path.push_str(&import.snake);
-                interfaces.push(path)
+                interfaces.push((path, import.immutable))
             }
         }
+        interfaces
+    }
+
+    /// Returns the `(field, host trait, add_to_linker function, immutable)`
+    /// for each getter accepted by the generated `add_to_linker_with`.
+    fn import_getters(
+        &self,
+        resolve: &Resolve,
+        world: WorldId,
+    ) -> Vec<(String, String, String, bool)> {
+        let mut getters = self
+            .import_interface_paths()
+            .into_iter()
+            .map(|(path, immutable)| {
+                (
+                    path.replace("::", "_"),
+                    format!("{path}::Host"),
+                    format!("{path}::add_to_linker"),
+                    immutable,
+                )
+            })
+            .collect::<Vec<_>>();
+        if !self.import_functions.is_empty() {
+            let world_camel = to_rust_upper_camel_case(&resolve.worlds[world].name);
+            getters.push((
+                "root".to_string(),
+                format!("{world_camel}Imports"),
+                "Self::add_root_to_linker".to_string(),
+                self.root_is_immutable(),
+            ));
+        }
+        getters
+    }
+
+    fn toplevel_getters_struct(&mut self, resolve: &Resolve, world: WorldId) {
+        let getters = self.import_getters(resolve, world);
+        if getters.is_empty() {
+            return;
+        }
+        let world_camel = to_rust_upper_camel_case(&resolve.worlds[world].name);
+        let generics = (0..getters.len())
+            .map(|i| format!("G{i}"))
+            .collect::<Vec<_>>()
+            .join(", ");
+        uwriteln!(
+            self.src,
+            "
+                /// Accessors for each imported interface, used with
+                /// `{world_camel}::add_to_linker_with` to project the store's
+                /// data to a different host type per interface.
+                pub struct {world_camel}Getters<{generics}> {{
+            "
+        );
+        for (i, (field, host, _, _)) in getters.iter().enumerate() {
+            uwriteln!(
+                self.src,
+                "/// Accessor for the type implementing `{host}`.\npub {field}: G{i},"
+            );
+        }
+        uwriteln!(self.src, "}}");
+    }
+
+    fn toplevel_add_to_linker(&mut self, resolve: &Resolve, world: WorldId) {
+        let interfaces = self.import_interface_paths();
+        if interfaces.is_empty() && self.import_functions.is_empty() {
+            return;
+        }
 
         uwrite!(
             self.src,
@@ -587,7 +842,7 @@ impl Wasmtime {
         let world_trait = format!("{world_camel}Imports");
         for (i, name) in interfaces
             .iter()
-            .map(|n| format!("{n}::Host"))
+            .map(|(n, _)| format!("{n}::Host"))
             .chain(if self.import_functions.is_empty() {
                 None
             } else {
@@ -600,24 +855,68 @@ impl Wasmtime {
             }
             self.src.push_str(&name);
         }
-        let maybe_send = if self.opts.async_ {
-            " + Send, T: Send"
-        } else {
-            ""
-        };
-        self.src.push_str(maybe_send);
+        let immutable =
+            interfaces.iter().any(|(_, immutable)| *immutable) || self.root_is_immutable();
+        if self.opts.async_ {
+            uwrite!(self.src, "{}, T: Send", self.host_bound(immutable));
+        }
         self.src.push_str(",\n{\n");
-        for name in interfaces.iter() {
+        for (name, _) in interfaces.iter() {
             uwriteln!(self.src, "{name}::add_to_linker(linker, get)?;");
         }
         if !self.import_functions.is_empty() {
             uwriteln!(self.src, "Self::add_root_to_linker(linker, get)?;");
         }
         uwriteln!(self.src, "Ok(())\n}}");
+
+        let getters = self.import_getters(resolve, world);
+        let generics = (0..getters.len())
+            .map(|i| format!("G{i}, U{i}"))
+            .collect::<Vec<_>>()
+            .join(", ");
+        let getter_generics = (0..getters.len())
+            .map(|i| format!("G{i}"))
+            .collect::<Vec<_>>()
+            .join(", ");
+        uwrite!(
+            self.src,
+            "
+                /// Like `add_to_linker`, but each imported interface is given
+                /// its own accessor so that each can be implemented by a
+                /// different host type.
+                pub fn add_to_linker_with<T, {generics}>(
+                    linker: &mut wasmtime::component::Linker<T>,
+                    getters: {world_camel}Getters<{getter_generics}>,
+                ) -> wasmtime::Result<()>
+                    where
+            "
+        );
+        for (i, (_, host, _, immutable)) in getters.iter().enumerate() {
+            let bound = self.host_bound(*immutable);
+            uwriteln!(
+                self.src,
+                "G{i}: Fn(&mut T) -> &mut U{i} + Send + Sync + Copy + 'static,\n\
+                 U{i}: {host}{bound},"
+            );
+        }
+        if self.opts.async_ {
+            self.src.push_str("T: Send,\n");
+        }
+        self.src.push_str("{\n");
+        for (field, _, add_to_linker, _) in getters.iter() {
+            uwriteln!(self.src, "{add_to_linker}(linker, getters.{field})?;");
+        }
+        uwriteln!(self.src, "Ok(())\n}}");
+
         if self.import_functions.is_empty() {
             return;
         }
 
+        let maybe_send = if self.opts.async_ {
+            format!("{}, T: Send", self.host_bound(self.root_is_immutable()))
+        } else {
+            String::new()
+        };
         uwrite!(
             self.src,
             "
@@ -770,12 +1069,137 @@ impl<'a> InterfaceGenerator<'a> {
             TypeDefKind::Type(t) => self.type_alias(id, name, t, &ty.docs),
             TypeDefKind::Future(_) => todo!("generate for future"),
             TypeDefKind::Stream(_) => todo!("generate for stream"),
-            TypeDefKind::Handle(_) => todo!("#6722"),
-            TypeDefKind::Resource => todo!("#6722"),
+            TypeDefKind::Handle(handle) => self.type_handle(id, name, handle, &ty.docs),
+            TypeDefKind::Resource => self.type_resource(id, name, &ty.docs),
             TypeDefKind::Unknown => unreachable!(),
         }
     }
 
+    fn type_resource(&mut self, id: TypeId, name: &str, docs: &Docs) {
+        if self.gen.exported_resources.contains(&id) {
+            return self.type_exported_resource(name, docs);
+        }
+
+        // Resources in imported interfaces are implemented by the host, so
+        // the generated type is only used to name the resource in
+        // `Resource<T>` handles. The `Host{name}` trait with its methods and
+        // destructor is generated alongside the interface's `Host` trait.
+        self.rustdoc(docs);
+        let rust_name = to_rust_upper_camel_case(name);
+        uwriteln!(self.src, "pub enum {rust_name} {{}}");
+    }
+
+    /// Generates an owned handle type for a resource implemented by the
+    /// guest.
+    ///
+    /// Handles to guest resources are `ResourceAny` in function signatures,
+    /// which must be explicitly destroyed. The generated type pairs one with
+    /// the store it belongs to so that it's destroyed when dropped.
+    fn type_exported_resource(&mut self, name: &str, docs: &Docs) {
+        self.rustdoc(docs);
+        let rust_name = to_rust_upper_camel_case(name);
+        let (drop, drop_doc) = if self.gen.opts.async_ {
+            (
+                "
+                    pub async fn resource_drop_async(mut self) -> wasmtime::Result<()>
+                        where T: Send,
+                    {
+                        let resource = self.resource.take().unwrap();
+                        resource.resource_drop_async(&mut self.store).await
+                    }
+                ",
+                "The handle must be destroyed with `resource_drop_async`, as async \
+                 destructors can't be run from `Drop`.",
+            )
+        } else {
+            (
+                "
+                    pub fn resource_drop(mut self) -> wasmtime::Result<()> {
+                        let resource = self.resource.take().unwrap();
+                        resource.resource_drop(&mut self.store)
+                    }
+                ",
+                "The resource is destroyed when this handle is dropped.",
+            )
+        };
+        uwriteln!(
+            self.src,
+            "
+                ///
+                /// This is an owned handle to a `{name}` resource implemented by
+                /// the guest, along with the store it belongs to. {drop_doc}
+                pub struct {rust_name}<'a, T> {{
+                    store: wasmtime::StoreContextMut<'a, T>,
+                    resource: Option<wasmtime::component::ResourceAny>,
+                }}
+
+                impl<'a, T> {rust_name}<'a, T> {{
+                    /// Takes ownership of `resource`, an owned `{name}` handle
+                    /// returned by a function called on `store`.
+                    pub fn new(
+                        store: wasmtime::StoreContextMut<'a, T>,
+                        resource: wasmtime::component::ResourceAny,
+                    ) -> Self {{
+                        {rust_name} {{
+                            store,
+                            resource: Some(resource),
+                        }}
+                    }}
+
+                    /// Returns the handle, to pass as a `borrow<{name}>` argument.
+                    pub fn resource(&self) -> wasmtime::component::ResourceAny {{
+                        self.resource.unwrap()
+                    }}
+
+                    /// Returns the store, to call functions with while the
+                    /// resource is alive.
+                    pub fn store(&mut self) -> wasmtime::StoreContextMut<'_, T> {{
+                        wasmtime::AsContextMut::as_context_mut(&mut self.store)
+                    }}
+
+                    /// Releases the handle without destroying the resource, to
+                    /// pass as an `own<{name}>` argument.
+                    pub fn into_resource(mut self) -> wasmtime::component::ResourceAny {{
+                        self.resource.take().unwrap()
+                    }}
+
+                    /// Destroys the resource, returning any error from its
+                    /// destructor.
+                    {drop}
+                }}
+            "
+        );
+        if !self.gen.opts.async_ {
+            uwriteln!(
+                self.src,
+                "
+                    impl<T> Drop for {rust_name}<'_, T> {{
+                        fn drop(&mut self) {{
+                            // Errors can't be reported from here, use
+                            // `resource_drop` to observe them.
+                            if let Some(resource) = self.resource.take() {{
+                                let _ = resource.resource_drop(&mut self.store);
+                            }}
+                        }}
+                    }}
+                "
+            );
+        }
+    }
+
+    fn type_handle(&mut self, id: TypeId, _name: &str, handle: &Handle, docs: &Docs) {
+        let resource = match handle {
+            Handle::Own(ty) | Handle::Borrow(ty) => *ty,
+        };
+        for (name, _mode) in self.modes_of(id) {
+            self.rustdoc(docs);
+            self.push_str(&format!("pub type {name} = "));
+            self.print_handle(resource);
+            self.push_str(";\n");
+            self.assert_type(id, &name);
+        }
+    }
+
     fn type_record(&mut self, id: TypeId, _name: &str, record: &Record, docs: &Docs) {
         let info = self.info(id);
         for (name, mode) in self.modes_of(id) {
@@ -789,10 +1213,13 @@ impl<'a> InterfaceGenerator<'a> {
             self.push_str("#[derive(wasmtime::component::Lower)]\n");
             self.push_str("#[component(record)]\n");
 
-            if !info.has_list {
-                self.push_str("#[derive(Copy, Clone)]\n");
-            } else {
-                self.push_str("#[derive(Clone)]\n");
+            // Handles to resources can't be duplicated.
+            if !info.has_resource {
+                if !info.has_list {
+                    self.push_str("#[derive(Copy, Clone)]\n");
+                } else {
+                    self.push_str("#[derive(Clone)]\n");
+                }
             }
             self.push_str(&format!("pub struct {}", name));
             self.print_generics(lt);
@@ -808,26 +1235,28 @@ impl<'a> InterfaceGenerator<'a> {
             }
             self.push_str("}\n");
 
-            self.push_str("impl");
-            self.print_generics(lt);
-            self.push_str(" core::fmt::Debug for ");
-            self.push_str(&name);
-            self.print_generics(lt);
-            self.push_str(" {\n");
-            self.push_str(
-                "fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {\n",
-            );
-            self.push_str(&format!("f.debug_struct(\"{}\")", name));
-            for field in record.fields.iter() {
-                self.push_str(&format!(
-                    ".field(\"{}\", &self.{})",
-                    field.name,
-                    to_rust_ident(&field.name)
-                ));
+            if !self.skip_debug(id) {
+                self.push_str("impl");
+                self.print_generics(lt);
+                self.push_str(" core::fmt::Debug for ");
+                self.push_str(&name);
+                self.print_generics(lt);
+                self.push_str(" {\n");
+                self.push_str(
+                    "fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {\n",
+                );
+                self.push_str(&format!("f.debug_struct(\"{}\")", name));
+                for field in record.fields.iter() {
+                    self.push_str(&format!(
+                        ".field(\"{}\", &self.{})",
+                        field.name,
+                        to_rust_ident(&field.name)
+                    ));
+                }
+                self.push_str(".finish()\n");
+                self.push_str("}\n");
+                self.push_str("}\n");
             }
-            self.push_str(".finish()\n");
-            self.push_str("}\n");
-            self.push_str("}\n");
 
             if info.error {
                 self.push_str("impl");
@@ -848,6 +1277,149 @@ impl<'a> InterfaceGenerator<'a> {
             }
             self.assert_type(id, &name);
         }
+
+        if self.uses_two_names(&info) {
+            self.print_record_to_owned(id, record);
+        }
+    }
+
+    /// Emits a `From<&FooParam<'a>> for FooResult` impl for a record which is
+    /// generated in both a borrowed and an owned form, deep-copying any
+    /// borrowed lists and strings.
+    ///
+    /// Nothing is emitted if a field contains something which can't be
+    /// copied, such as a resource handle.
+    fn print_record_to_owned(&mut self, id: TypeId, record: &Record) {
+        let mut fields = Vec::new();
+        for field in record.fields.iter() {
+            let field_name = to_rust_ident(&field.name);
+            match self.to_owned_expr(&field.ty, &format!("value.{field_name}"), 0) {
+                Some(expr) => fields.push(format!("{field_name}: {expr},")),
+                None => return,
+            }
+        }
+        let param = self.param_name(id);
+        let result = self.result_name(id);
+        let fields = fields.join("\n");
+        uwriteln!(
+            self.src,
+            "
+                impl<'a> From<&{param}<'a>> for {result} {{
+                    fn from(value: &{param}<'a>) -> Self {{
+                        {result} {{
+                            {fields}
+                        }}
+                    }}
+                }}
+            "
+        );
+    }
+
+    /// Emits a `From<&FooParam<'a>> for FooResult` impl for a variant or union
+    /// which is generated in both a borrowed and an owned form, converting
+    /// each case's payload like `print_record_to_owned` converts fields.
+    fn print_enum_to_owned<'b>(
+        &mut self,
+        id: TypeId,
+        cases: impl IntoIterator<Item = (String, Option<&'b Type>)>,
+    ) {
+        let param = self.param_name(id);
+        let result = self.result_name(id);
+        let mut arms = Vec::new();
+        for (case_name, payload) in cases {
+            match payload {
+                Some(ty) => match self.to_owned_expr(ty, "(*e0)", 1) {
+                    Some(expr) => arms.push(format!(
+                        "{param}::{case_name}(e0) => {result}::{case_name}({expr}),"
+                    )),
+                    None => return,
+                },
+                None => arms.push(format!("{param}::{case_name} => {result}::{case_name},")),
+            }
+        }
+        let arms = arms.join("\n");
+        uwriteln!(
+            self.src,
+            "
+                impl<'a> From<&{param}<'a>> for {result} {{
+                    fn from(value: &{param}<'a>) -> Self {{
+                        match value {{
+                            {arms}
+                        }}
+                    }}
+                }}
+            "
+        );
+    }
+
+    /// Returns an expression which converts the borrowed value at `place` of
+    /// type `ty` to its owned form, or `None` if that isn't supported.
+    fn to_owned_expr(&self, ty: &Type, place: &str, depth: usize) -> Option<String> {
+        let id = match ty {
+            Type::String => return Some(format!("{place}.to_string()")),
+            Type::Id(id) => *id,
+            _ => return Some(place.to_string()),
+        };
+        let ty = &self.resolve.types[id];
+        if ty.name.is_some() {
+            let info = self.info(id);
+            if !info.has_list {
+                return Some(format!("{place}.clone()"));
+            }
+            match &ty.kind {
+                TypeDefKind::Record(_) | TypeDefKind::Variant(_) | TypeDefKind::Union(_)
+                    if self.uses_two_names(&info) =>
+                {
+                    let mut path = String::new();
+                    if let TypeOwner::Interface(owner) = ty.owner {
+                        if let Some(p) = self.path_to_interface(owner) {
+                            path = format!("{p}::");
+                        }
+                    }
+                    let result = self.result_name(id);
+                    return Some(format!("{path}{result}::from(&{place})"));
+                }
+                TypeDefKind::Record(_) | TypeDefKind::Variant(_) | TypeDefKind::Union(_) => {
+                    return None
+                }
+                _ => {}
+            }
+        }
+        let elem = format!("e{depth}");
+        let deref = format!("(*{elem})");
+        match &ty.kind {
+            TypeDefKind::Type(t) => self.to_owned_expr(t, place, depth),
+            TypeDefKind::List(t @ (Type::Id(_) | Type::String)) => {
+                let expr = self.to_owned_expr(t, &deref, depth + 1)?;
+                Some(format!("{place}.iter().map(|{elem}| {expr}).collect()"))
+            }
+            TypeDefKind::List(_) => Some(format!("{place}.to_vec()")),
+            TypeDefKind::Option(t) => {
+                let expr = self.to_owned_expr(t, &deref, depth + 1)?;
+                Some(format!("{place}.as_ref().map(|{elem}| {expr})"))
+            }
+            TypeDefKind::Result(r) => {
+                let arm = |ty: Option<&Type>, case: &str| match ty {
+                    Some(t) => {
+                        let expr = self.to_owned_expr(t, &deref, depth + 1)?;
+                        Some(format!("{case}({elem}) => {case}({expr}),"))
+                    }
+                    None => Some(format!("{case}(()) => {case}(()),")),
+                };
+                let ok = arm(r.ok.as_ref(), "Ok")?;
+                let err = arm(r.err.as_ref(), "Err")?;
+                Some(format!("match &{place} {{ {ok} {err} }}"))
+            }
+            TypeDefKind::Tuple(t) => {
+                let mut exprs = String::new();
+                for (i, t) in t.types.iter().enumerate() {
+                    exprs.push_str(&self.to_owned_expr(t, &format!("{place}.{i}"), depth)?);
+                    exprs.push_str(",");
+                }
+                Some(format!("({exprs})"))
+            }
+            _ => None,
+        }
     }
 
     fn type_tuple(&mut self, id: TypeId, _name: &str, tuple: &Tuple, docs: &Docs) {
@@ -966,10 +1538,12 @@ impl<'a> InterfaceGenerator<'a> {
             }
             self.push_str("#[derive(wasmtime::component::Lower)]\n");
             self.push_str(&format!("#[component({})]\n", derive_component));
-            if !info.has_list {
-                self.push_str("#[derive(Clone, Copy)]\n");
-            } else {
-                self.push_str("#[derive(Clone)]\n");
+            if !info.has_resource {
+                if !info.has_list {
+                    self.push_str("#[derive(Clone, Copy)]\n");
+                } else {
+                    self.push_str("#[derive(Clone)]\n");
+                }
             }
             self.push_str(&format!("pub enum {name}"));
             self.print_generics(lt);
@@ -989,15 +1563,17 @@ impl<'a> InterfaceGenerator<'a> {
             }
             self.push_str("}\n");
 
-            self.print_rust_enum_debug(
-                id,
-                mode,
-                &name,
-                cases
-                    .clone()
-                    .into_iter()
-                    .map(|(name, _attr, _docs, ty)| (name, ty)),
-            );
+            if !self.skip_debug(id) {
+                self.print_rust_enum_debug(
+                    id,
+                    mode,
+                    &name,
+                    cases
+                        .clone()
+                        .into_iter()
+                        .map(|(name, _attr, _docs, ty)| (name, ty)),
+                );
+            }
 
             if info.error {
                 self.push_str("impl");
@@ -1024,6 +1600,13 @@ impl<'a> InterfaceGenerator<'a> {
 
             self.assert_type(id, &name);
         }
+
+        if self.uses_two_names(&info) {
+            self.print_enum_to_owned(
+                id,
+                cases.into_iter().map(|(name, _attr, _docs, ty)| (name, ty)),
+            );
+        }
     }
 
     fn print_rust_enum_debug<'b>(
@@ -1101,6 +1684,30 @@ impl<'a> InterfaceGenerator<'a> {
         }
         self.push_str("}\n");
 
+        // Expose the integer discriminant of each case, which is the case's
+        // index in the WIT definition.
+        self.push_str("impl ");
+        self.push_str(&name);
+        self.push_str("{\n");
+        self.push_str("pub fn to_i32(&self) -> i32 {\n");
+        self.push_str("match self {\n");
+        for (i, case) in enum_.cases.iter().enumerate() {
+            let case = case.name.to_upper_camel_case();
+            uwriteln!(self.src, "{name}::{case} => {i},");
+        }
+        self.push_str("}\n");
+        self.push_str("}\n");
+        self.push_str("pub fn from_i32(v: i32) -> Option<Self> {\n");
+        self.push_str("match v {\n");
+        for (i, case) in enum_.cases.iter().enumerate() {
+            let case = case.name.to_upper_camel_case();
+            uwriteln!(self.src, "{i} => Some({name}::{case}),");
+        }
+        self.push_str("_ => None,\n");
+        self.push_str("}\n");
+        self.push_str("}\n");
+        self.push_str("}\n");
+
         // Auto-synthesize an implementation of the standard `Error` trait for
         // error-looking types based on their name.
         if info.error {
@@ -1138,34 +1745,36 @@ impl<'a> InterfaceGenerator<'a> {
 
             self.push_str("}\n");
 
-            self.push_str("impl core::fmt::Debug for ");
-            self.push_str(&name);
-            self.push_str(
-                "{\nfn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {\n",
-            );
-            self.push_str("f.debug_struct(\"");
-            self.push_str(&name);
-            self.push_str("\")\n");
-            self.push_str(".field(\"code\", &(*self as i32))\n");
-            self.push_str(".field(\"name\", &self.name())\n");
-            self.push_str(".field(\"message\", &self.message())\n");
-            self.push_str(".finish()\n");
-            self.push_str("}\n");
-            self.push_str("}\n");
+            if !self.skip_debug(id) {
+                self.push_str("impl core::fmt::Debug for ");
+                self.push_str(&name);
+                self.push_str(
+                    "{\nfn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {\n",
+                );
+                self.push_str("f.debug_struct(\"");
+                self.push_str(&name);
+                self.push_str("\")\n");
+                self.push_str(".field(\"code\", &self.to_i32())\n");
+                self.push_str(".field(\"name\", &self.name())\n");
+                self.push_str(".field(\"message\", &self.message())\n");
+                self.push_str(".finish()\n");
+                self.push_str("}\n");
+                self.push_str("}\n");
+            }
 
             self.push_str("impl core::fmt::Display for ");
             self.push_str(&name);
             self.push_str(
                 "{\nfn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {\n",
             );
-            self.push_str("write!(f, \"{} (error {})\", self.name(), *self as i32)");
+            self.push_str("write!(f, \"{} (error {})\", self.name(), self.to_i32())");
             self.push_str("}\n");
             self.push_str("}\n");
             self.push_str("\n");
             self.push_str("impl std::error::Error for ");
             self.push_str(&name);
             self.push_str("{}\n");
-        } else {
+        } else if !self.skip_debug(id) {
             self.print_rust_enum_debug(
                 id,
                 TypeMode::Owned,
@@ -1189,7 +1798,12 @@ impl<'a> InterfaceGenerator<'a> {
             self.push_str(" = ");
             self.print_ty(ty, mode);
             self.push_str(";\n");
-            self.assert_type(id, &name);
+            // Resources themselves have no representation in the canonical
+            // ABI, only handles to them do.
+            let def = resolve_type_definition_id(self.resolve, id);
+            if !matches!(self.resolve.types[def].kind, TypeDefKind::Resource) {
+                self.assert_type(id, &name);
+            }
         }
     }
 
@@ -1257,22 +1871,90 @@ impl<'a> InterfaceGenerator<'a> {
     }
 
     fn generate_add_to_linker(&mut self, id: InterfaceId, name: &str) {
+        let iface_key = name;
         let iface = &self.resolve.interfaces[id];
         let owner = TypeOwner::Interface(id);
 
+        // Each resource defined in this interface gets its own trait for the
+        // host to implement its methods and destructor.
+        let resources = iface
+            .types
+            .iter()
+            .filter(|(_, id)| matches!(self.resolve.types[**id].kind, TypeDefKind::Resource))
+            .map(|(name, id)| (name.as_str(), *id))
+            .collect::<Vec<_>>();
+        for (name, resource) in resources.iter() {
+            let camel = to_rust_upper_camel_case(name);
+            let mut names = iface
+                .functions
+                .values()
+                .filter(|func| func_resource(func) == Some(*resource))
+                .map(|func| (func_rust_name(func), func.name.clone()))
+                .collect::<Vec<_>>();
+            names.push(("drop".to_string(), format!("[resource-drop]{name}")));
+            uwriteln!(
+                self.src,
+                "/// Host implementation of the `{name}` resource."
+            );
+            push_required_methods_doc(&mut self.src, &names);
+            if self.gen.opts.async_ {
+                uwriteln!(self.src, "#[wasmtime::component::__internal::async_trait]")
+            }
+            uwriteln!(self.src, "pub trait Host{camel} {{");
+            for (_, func) in iface.functions.iter() {
+                if func_resource(func) == Some(*resource) {
+                    let cfg = self.gen.cfg_feature(&format!("{iface_key}#{}", func.name));
+                    self.push_str(&cfg);
+                    self.generate_function_trait_sig(func);
+                }
+            }
+            uwriteln!(
+                self.src,
+                "
+                    /// Called when the last owned handle to `rep` is dropped.
+                    fn drop(&mut self, rep: wasmtime::component::Resource<{camel}>) -> wasmtime::Result<()>;
+                }}
+                "
+            );
+        }
+
+        let names = iface
+            .functions
+            .values()
+            .filter(|func| func_resource(func).is_none())
+            .map(|func| (func_rust_name(func), func.name.clone()))
+            .collect::<Vec<_>>();
+        uwriteln!(
+            self.src,
+            "/// Host implementation of the `{name}` interface."
+        );
+        push_required_methods_doc(&mut self.src, &names);
         if self.gen.opts.async_ {
             uwriteln!(self.src, "#[wasmtime::component::__internal::async_trait]")
         }
         // Generate the `pub trait` which represents the host functionality for
         // this import.
-        uwriteln!(self.src, "pub trait Host {{");
+        let supertraits = resources
+            .iter()
+            .map(|(name, _)| format!("Host{}", to_rust_upper_camel_case(name)))
+            .collect::<Vec<_>>();
+        if supertraits.is_empty() {
+            uwriteln!(self.src, "pub trait Host {{");
+        } else {
+            uwriteln!(self.src, "pub trait Host: {} {{", supertraits.join(" + "));
+        }
         for (_, func) in iface.functions.iter() {
-            self.generate_function_trait_sig(func);
+            if func_resource(func).is_none() {
+                let cfg = self.gen.cfg_feature(&format!("{iface_key}#{}", func.name));
+                self.push_str(&cfg);
+                self.generate_function_trait_sig(func);
+            }
         }
         uwriteln!(self.src, "}}");
 
         let where_clause = if self.gen.opts.async_ {
-            "T: Send, U: Host + Send".to_string()
+            let immutable = self.has_immutable_imports(id);
+            format!("T: Send, U: Host{}", self.gen.host_bound(immutable))
         } else {
             "U: Host".to_string()
         };
@@ -1288,7 +1970,18 @@ impl<'a> InterfaceGenerator<'a> {
             "
         );
         uwriteln!(self.src, "let mut inst = linker.instance(\"{name}\")?;");
+        for (name, _) in resources.iter() {
+            let camel = to_rust_upper_camel_case(name);
+            uwriteln!(
+                self.src,
+                "inst.resource_fallible::<{camel}>(\"{name}\", move |mut store, rep| {{
+                    Host{camel}::drop(get(store.data_mut()), wasmtime::component::Resource::new_own(rep))
+                }})?;"
+            );
+        }
         for (_, func) in iface.functions.iter() {
+            let cfg = self.gen.cfg_feature(&format!("{iface_key}#{}", func.name));
+            self.push_str(&cfg);
             self.generate_add_function_to_linker(owner, func, "inst");
         }
         uwriteln!(self.src, "Ok(())");
@@ -1371,9 +2064,13 @@ impl<'a> InterfaceGenerator<'a> {
             );
         }
 
-        self.src.push_str("let host = get(caller.data_mut());\n");
+        if self.is_immutable_import(func) {
+            self.src.push_str("let host = &*get(caller.data_mut());\n");
+        } else {
+            self.src.push_str("let host = get(caller.data_mut());\n");
+        }
 
-        uwrite!(self.src, "let r = host.{}(", func.name.to_snake_case());
+        uwrite!(self.src, "let r = host.{}(", func_rust_name(func));
         for (i, _) in func.params.iter().enumerate() {
             uwrite!(self.src, "arg{},", i);
         }
@@ -1422,8 +2119,12 @@ impl<'a> InterfaceGenerator<'a> {
             self.push_str("async ");
         }
         self.push_str("fn ");
-        self.push_str(&to_rust_ident(&func.name));
-        self.push_str("(&mut self, ");
+        self.push_str(&func_rust_name(func));
+        if self.is_immutable_import(func) {
+            self.push_str("(&self, ");
+        } else {
+            self.push_str("(&mut self, ");
+        }
         for (name, param) in func.params.iter() {
             let name = to_rust_ident(name);
             self.push_str(&name);
@@ -1466,6 +2167,29 @@ impl<'a> InterfaceGenerator<'a> {
         self.push_str(";\n");
     }
 
+    /// Returns whether any host trait method of the imported interface `id`,
+    /// including resource methods, takes `&self`.
+    fn has_immutable_imports(&self, id: InterfaceId) -> bool {
+        self.resolve.interfaces[id]
+            .functions
+            .values()
+            .any(|func| self.is_immutable_import(func))
+    }
+
+    /// Returns whether the host trait method for the imported `func` takes
+    /// `&self`, as requested through `Opts::immutable_imports`.
+    fn is_immutable_import(&self, func: &Function) -> bool {
+        let names = &self.gen.opts.immutable_imports;
+        match self.current_interface {
+            Some((_, name, _)) => {
+                let iface = self.resolve.name_world_key(name);
+                let func = format!("{iface}#{}", func.name);
+                names.iter().any(|n| *n == iface || *n == func)
+            }
+            None => names.iter().any(|n| *n == func.name),
+        }
+    }
+
     fn extract_typed_function(&mut self, func: &Function) -> (String, String) {
         let prev = mem::take(&mut self.src);
         let snake = func.name.to_snake_case();
@@ -1589,6 +2313,43 @@ impl<'a> InterfaceGenerator<'a> {
 
         // End function body
         self.src.push_str("}\n");
+
+        if self.gen.opts.async_ && self.gen.opts.generate_blocking_wrappers {
+            self.define_rust_guest_export_blocking(func);
+        }
+    }
+
+    fn define_rust_guest_export_blocking(&mut self, func: &Function) {
+        let name = func.name.to_snake_case();
+        uwriteln!(
+            self.src,
+            "
+                /// Synchronous version of `call_{name}`, which blocks the
+                /// current thread until the call completes.
+            "
+        );
+        uwrite!(
+            self.src,
+            "pub fn call_{name}_blocking<S: wasmtime::AsContextMut>(&self, store: S, "
+        );
+        for (i, param) in func.params.iter().enumerate() {
+            uwrite!(self.src, "arg{}: ", i);
+            self.print_ty(&param.1, TypeMode::AllBorrowed("'_"));
+            self.push_str(",");
+        }
+        self.src.push_str(") -> wasmtime::Result<");
+        self.print_result_ty(&func.results, TypeMode::Owned);
+        self.src
+            .push_str("> where <S as wasmtime::AsContext>::Data: Send {\n");
+        uwrite!(
+            self.src,
+            "wasmtime::component::__internal::block_on(self.call_{name}(store, "
+        );
+        for (i, _) in func.params.iter().enumerate() {
+            uwrite!(self.src, "arg{}, ", i);
+        }
+        self.src.push_str("))\n");
+        self.src.push_str("}\n");
     }
 
     fn define_trappable_error_type(&mut self, id: TypeId, rust_name: String) {
@@ -1638,6 +2399,15 @@ impl<'a> InterfaceGenerator<'a> {
         );
     }
 
+    /// Whether the generated `Debug` impl for `id` was disabled through
+    /// `Opts::skip_debug`.
+    fn skip_debug(&self, id: TypeId) -> bool {
+        match &self.resolve.types[id].name {
+            Some(name) => self.gen.opts.skip_debug.iter().any(|n| n == name),
+            None => false,
+        }
+    }
+
     fn rustdoc(&mut self, docs: &Docs) {
         let docs = match &docs.contents {
             Some(docs) => docs,
@@ -1690,6 +2460,13 @@ impl<'a> RustGenerator<'a> for InterfaceGenerator<'a> {
     fn info(&self, ty: TypeId) -> TypeInfo {
         self.gen.types.get(ty)
     }
+
+    fn is_exported_resource(&self, mut ty: TypeId) -> bool {
+        while let TypeDefKind::Type(Type::Id(id)) = self.resolve.types[ty].kind {
+            ty = id;
+        }
+        self.gen.exported_resources.contains(&ty)
+    }
 }
 
 /// When an interface `use`s a type from another interface, it creates a new TypeId
@@ -1703,3 +2480,194 @@ fn resolve_type_definition_id(resolve: &Resolve, mut id: TypeId) -> TypeId {
         }
     }
 }
+
+/// Returns the resource that `func` is a constructor, method, or static
+/// function of, if any.
+fn func_resource(func: &Function) -> Option<TypeId> {
+    match func.kind {
+        FunctionKind::Freestanding => None,
+        FunctionKind::Method(id) | FunctionKind::Static(id) | FunctionKind::Constructor(id) => {
+            Some(id)
+        }
+    }
+}
+
+/// Returns the name of the Rust method generated for `func`.
+///
+/// Functions belonging to a resource are named `[method]foo.bar` and similar
+/// in WIT, but are generated as `bar` (or `new` for constructors) in the
+/// resource's `Host{Foo}` trait. Since that trait also has a `drop` method
+/// for the destructor, methods and static functions named `new` or `drop`
+/// are escaped with a trailing underscore, like Rust keywords.
+fn func_rust_name(func: &Function) -> String {
+    match func.kind {
+        FunctionKind::Freestanding => to_rust_ident(&func.name),
+        FunctionKind::Constructor(_) => "new".to_string(),
+        FunctionKind::Method(_) | FunctionKind::Static(_) => {
+            let (_, name) = func.name.split_once('.').unwrap();
+            match name {
+                "new" | "drop" => format!("{name}_"),
+                _ => to_rust_ident(name),
+            }
+        }
+    }
+}
+
+/// Writes a doc comment section listing the methods a host trait requires,
+/// so that they can be seen at a glance in the trait's documentation.
+///
+/// Each entry in `names` is a pair of the Rust method name and the name of
+/// the WIT item it implements.
+fn push_required_methods_doc(src: &mut Source, names: &[(String, String)]) {
+    if names.is_empty() {
+        return;
+    }
+    uwriteln!(src, "///");
+    uwriteln!(
+        src,
+        "/// Implementations must provide the following methods:"
+    );
+    uwriteln!(src, "///");
+    for (rust_name, wit_name) in names {
+        uwriteln!(src, "/// * `{rust_name}` for `{wit_name}`");
+    }
+}
+
+/// Renders `docs` as inner `//!` doc comments, suitable for placing at the
+/// top of the `pub mod` generated for an interface.
+fn module_docs(docs: &Docs) -> String {
+    let mut src = String::new();
+    if let Some(docs) = &docs.contents {
+        for line in docs.trim().lines() {
+            src.push_str("//! ");
+            src.push_str(line);
+            src.push_str("\n");
+        }
+    }
+    src
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn generate(wit: &str) -> anyhow::Result<String> {
+        let mut resolve = Resolve::default();
+        let pkg = resolve.push(UnresolvedPackage::parse("test.wit".as_ref(), wit)?)?;
+        let world = resolve.select_world(pkg, None)?;
+        Opts::default().generate(&resolve, world)
+    }
+
+    #[test]
+    fn unsupported_resources_are_errors() {
+        let err = generate(
+            "
+                package foo:foo
+                interface i {
+                    resource r
+                }
+                world w {
+                    import i
+                    export i
+                }
+            ",
+        )
+        .unwrap_err();
+        assert!(
+            err.to_string()
+                .contains("resource `r` is both imported and exported"),
+            "{err}"
+        );
+
+        let err = generate(
+            "
+                package foo:foo
+                world w {
+                    resource r
+                    import f: func(x: own<r>)
+                }
+            ",
+        )
+        .unwrap_err();
+        assert!(
+            err.to_string()
+                .contains("resource `r` is imported directly"),
+            "{err}"
+        );
+
+        generate(
+            "
+                package foo:foo
+                world w {
+                    import i: interface {
+                        resource r
+                    }
+                }
+            ",
+        )
+        .unwrap();
+    }
+
+    #[test]
+    fn exported_resources_are_resource_any() {
+        let src = generate(
+            "
+                package foo:foo
+                world w {
+                    export i: interface {
+                        resource r {
+                            constructor()
+                        }
+                        take: func(a: own<r>, b: borrow<r>)
+                    }
+                }
+            ",
+        )
+        .unwrap();
+        assert!(src.contains("pub struct R<'a, T>"), "{src}");
+        assert!(src.contains("impl<T> Drop for R<'_, T>"), "{src}");
+        assert!(
+            src.contains(
+                "arg0: wasmtime::component::ResourceAny,\
+                 arg1: wasmtime::component::ResourceAny,"
+            ),
+            "{src}"
+        );
+        assert!(!src.contains("Resource<R>"), "{src}");
+    }
+
+    #[test]
+    fn interface_docs_are_module_docs() {
+        let src = generate(include_str!(
+            "../../component-macro/tests/codegen/interface-docs.wit"
+        ))
+        .unwrap();
+
+        // Returns the leading `//!` lines of each generated `pub mod {name}`.
+        let docs_of = |name: &str| {
+            src.match_indices(&format!("pub mod {name} {{"))
+                .map(|(i, m)| {
+                    src[i + m.len()..]
+                        .lines()
+                        .map(str::trim)
+                        .skip_while(|line| line.is_empty())
+                        .take_while(|line| line.starts_with("//!"))
+                        .collect::<Vec<_>>()
+                })
+                .collect::<Vec<_>>()
+        };
+
+        let documented = [
+            "//! Documentation for the `documented` interface.",
+            "//!",
+            "//! This spans multiple lines and \"quotes\" things, and is emitted as the",
+            "//! module-level docs of the generated module.",
+        ];
+        assert_eq!(docs_of("documented"), [documented, documented]);
+        assert_eq!(
+            docs_of("documented_empty"),
+            [["//! Docs on an interface with no items."]]
+        );
+        assert_eq!(docs_of("undocumented"), [Vec::<&str>::new(), Vec::new()]);
+    }
+}
@@ -15,6 +15,10 @@ pub trait RustGenerator<'a> {
 
     fn push_str(&mut self, s: &str);
     fn info(&self, ty: TypeId) -> TypeInfo;
+
+    /// Whether `ty` is a resource implemented by the guest, which is
+    /// represented on the host by `ResourceAny` rather than `Resource<T>`.
+    fn is_exported_resource(&self, ty: TypeId) -> bool;
     fn path_to_interface(&self, interface: InterfaceId) -> Option<String>;
 
     /// This determines whether we generate owning types or (where appropriate)
@@ -57,6 +61,21 @@ pub trait RustGenerator<'a> {
         }
     }
 
+    /// Prints the type of an `own` or `borrow` handle to `resource`.
+    ///
+    /// Both owned and borrowed handles are represented on the host by the same
+    /// type: `Resource<T>` for host resources, where `T` is the type generated
+    /// for the resource itself, and `ResourceAny` for guest resources.
+    fn print_handle(&mut self, resource: TypeId) {
+        if self.is_exported_resource(resource) {
+            self.push_str("wasmtime::component::ResourceAny");
+        } else {
+            self.push_str("wasmtime::component::Resource<");
+            self.print_tyid(resource, TypeMode::Owned);
+            self.push_str(">");
+        }
+    }
+
     fn print_optional_ty(&mut self, ty: Option<&Type>, mode: TypeMode) {
         match ty {
             Some(ty) => self.print_ty(ty, mode),
@@ -185,8 +204,10 @@ pub trait RustGenerator<'a> {
                 self.push_str(">");
             }
 
-            TypeDefKind::Handle(_) => todo!("#6722"),
-            TypeDefKind::Resource => todo!("#6722"),
+            TypeDefKind::Handle(Handle::Own(ty) | Handle::Borrow(ty)) => self.print_handle(*ty),
+            TypeDefKind::Resource => {
+                panic!("unsupported anonymous type reference: resource")
+            }
 
             TypeDefKind::Type(t) => self.print_ty(t, mode),
             TypeDefKind::Unknown => unreachable!(),
@@ -297,8 +318,15 @@ pub trait RustGenerator<'a> {
                         TypeDefKind::Variant(_) => out.push_str("Variant"),
                         TypeDefKind::Enum(_) => out.push_str("Enum"),
                         TypeDefKind::Union(_) => out.push_str("Union"),
-                        TypeDefKind::Handle(_) => todo!("#6722"),
-                        TypeDefKind::Resource => todo!("#6722"),
+                        TypeDefKind::Handle(Handle::Own(ty)) => {
+                            self.write_name(&Type::Id(*ty), out);
+                            out.push_str("Own");
+                        }
+                        TypeDefKind::Handle(Handle::Borrow(ty)) => {
+                            self.write_name(&Type::Id(*ty), out);
+                            out.push_str("Borrow");
+                        }
+                        TypeDefKind::Resource => out.push_str("Resource"),
                         TypeDefKind::Unknown => unreachable!(),
                     },
                 }
@@ -23,6 +23,9 @@ pub struct TypeInfo {
 
     /// Whether or not this type (transitively) has a list.
     pub has_list: bool,
+
+    /// Whether or not this type (transitively) has a handle to a resource.
+    pub has_resource: bool,
 }
 
 impl std::ops::BitOrAssign for TypeInfo {
@@ -31,6 +34,7 @@ impl std::ops::BitOrAssign for TypeInfo {
         self.owned |= rhs.owned;
         self.error |= rhs.error;
         self.has_list |= rhs.has_list;
+        self.has_resource |= rhs.has_resource;
     }
 }
 
@@ -158,7 +162,7 @@ impl Types {
                 info = self.optional_type_info(resolve, stream.element.as_ref());
                 info |= self.optional_type_info(resolve, stream.end.as_ref());
             }
-            TypeDefKind::Handle(_) => {}
+            TypeDefKind::Handle(_) => info.has_resource = true,
             TypeDefKind::Resource => {}
             TypeDefKind::Unknown => unreachable!(),
         }
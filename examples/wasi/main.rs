@@ -9,12 +9,18 @@ You can execute this example with:
 use anyhow::Result;
 use wasmtime::*;
 use wasmtime_wasi::sync::WasiCtxBuilder;
+use wasmtime_wasi::WasiCtx;
+
+struct State {
+    wasi: WasiCtx,
+    limits: StoreLimits,
+}
 
 fn main() -> Result<()> {
     // Define the WASI functions globally on the `Config`.
     let engine = Engine::default();
     let mut linker = Linker::new(&engine);
-    wasmtime_wasi::add_to_linker(&mut linker, |s| s)?;
+    wasmtime_wasi::add_to_linker(&mut linker, |s: &mut State| &mut s.wasi)?;
 
     // Create a WASI context and put it in a Store; all instances in the store
     // share this context. `WasiCtxBuilder` provides a number of ways to
@@ -23,7 +29,16 @@ fn main() -> Result<()> {
         .inherit_stdio()
         .inherit_args()?
         .build();
-    let mut store = Store::new(&engine, wasi);
+
+    // Bound how much the program's memories and tables may grow, so that it
+    // can't exhaust the host's memory. Growing past these limits makes
+    // `memory.grow` and `table.grow` fail within the program instead.
+    let limits = StoreLimitsBuilder::new()
+        .memory_size(64 << 20)
+        .table_elements(10_000)
+        .build();
+    let mut store = Store::new(&engine, State { wasi, limits });
+    store.limiter(|s| &mut s.limits);
 
     // Instantiate our module with the imports we've created, and run it.
     let module = Module::from_file(&engine, "target/wasm32-wasi/debug/wasi.wasm")?;
@@ -1,5 +1,6 @@
 #![no_main]
 
+use cranelift_codegen::ir::AbiParam;
 use cranelift_codegen::ir::Function;
 use cranelift_codegen::ir::Signature;
 use cranelift_codegen::ir::UserExternalName;
@@ -13,8 +14,10 @@ use libfuzzer_sys::fuzz_target;
 use once_cell::sync::Lazy;
 use std::collections::HashMap;
 use std::fmt;
+use std::path::Path;
 use std::sync::atomic::AtomicU64;
 use std::sync::atomic::Ordering;
+use target_lexicon::Architecture;
 
 use cranelift_codegen::data_value::DataValue;
 use cranelift_codegen::ir::{LibCall, TrapCode};
@@ -145,6 +148,22 @@ impl PartialEq for RunResult {
     }
 }
 
+impl RunResult {
+    /// Like `==`, but considers any two NaNs of the given result types equal.
+    fn nan_insensitive_eq(&self, other: &Self, returns: &[AbiParam]) -> bool {
+        match (self, other) {
+            (RunResult::Success(l), RunResult::Success(r)) => {
+                l.len() == r.len()
+                    && returns
+                        .iter()
+                        .zip(l.iter().zip(r))
+                        .all(|(p, (l, r))| nan_insensitive_eq(p.value_type, l, r))
+            }
+            _ => self == other,
+        }
+    }
+}
+
 pub struct TestCase {
     /// TargetIsa to use when compiling this test case
     pub isa: isa::OwnedTargetIsa,
@@ -159,6 +178,12 @@ pub struct TestCase {
     pub inputs: Vec<TestCaseInput>,
     /// Should this `TestCase` be tested after optimizations.
     pub compare_against_host: bool,
+    /// Targets to declare when printing this `TestCase`.
+    pub targets: Vec<Architecture>,
+    /// Whether NaN results must match bit-for-bit. This is false when the functions
+    /// were generated without NaN canonicalization and the targets don't guarantee
+    /// NaN payloads.
+    pub exact_nans: bool,
 }
 
 impl fmt::Debug for TestCase {
@@ -166,7 +191,7 @@ impl fmt::Debug for TestCase {
         if !self.compare_against_host {
             writeln!(f, ";; Testing against optimized version")?;
         }
-        PrintableTestCase::run(&self.isa, &self.functions, &self.inputs).fmt(f)
+        self.printable().fmt(f)
     }
 }
 
@@ -223,6 +248,8 @@ impl TestCase {
         // Now reverse the functions so that the main function is at the start.
         functions.reverse();
 
+        let targets = gen.config.targets.clone();
+        let exact_nans = gen.config.canonicalize_nans || gen.config.exact_nan_payloads;
         let main = &functions[0];
         let inputs = gen.generate_test_inputs(&main.signature)?;
 
@@ -232,9 +259,23 @@ impl TestCase {
             ctrl_planes,
             inputs,
             compare_against_host,
+            targets,
+            exact_nans,
         })
     }
 
+    fn printable(&self) -> PrintableTestCase<'_> {
+        PrintableTestCase::run(&self.isa, &self.functions, &self.inputs).with_targets(&self.targets)
+    }
+
+    /// Writes a self-contained `.clif` reproducer for this test case to `path`, including the
+    /// flags, the functions, the inputs and the raw fuzzer `input` it was generated from.
+    pub fn write_reproducer(&self, path: impl AsRef<Path>, input: &[u8]) -> std::io::Result<()> {
+        self.printable()
+            .with_input_bytes(input)
+            .write_reproducer(path)
+    }
+
     fn to_optimized(&self) -> Self {
         let optimized_functions: Vec<Function> = self
             .functions
@@ -252,6 +293,8 @@ impl TestCase {
             ctrl_planes: self.ctrl_planes.clone(),
             inputs: self.inputs.clone(),
             compare_against_host: false,
+            targets: self.targets.clone(),
+            exact_nans: self.exact_nans,
         }
     }
 
@@ -360,12 +403,35 @@ fn run_test_inputs(testcase: &TestCase, run: impl Fn(&[DataValue]) -> RunResult)
             return;
         }
 
-        assert_eq!(int_res, res);
+        if testcase.exact_nans {
+            assert_eq!(int_res, res);
+        } else {
+            assert!(
+                int_res.nan_insensitive_eq(&res, &testcase.main().signature.returns),
+                "assertion failed: `(left == right)` ignoring NaN payloads\n  left: `{:?}`,\n right: `{:?}`",
+                int_res,
+                res
+            );
+        }
     }
 }
 
-fuzz_target!(|testcase: TestCase| {
-    let mut testcase = testcase;
+fuzz_target!(|data: &[u8]| {
+    // Generate the test case by hand rather than through the typed `fuzz_target!` form so
+    // that the raw input is still available when writing reproducers.
+    let mut testcase = match TestCase::arbitrary_take_rest(Unstructured::new(data)) {
+        Ok(testcase) => testcase,
+        Err(_) => return,
+    };
+
+    // `cargo fuzz fmt` requests the formatted input through this variable.
+    if let Ok(path) = std::env::var("RUST_LIBFUZZER_DEBUG_PATH") {
+        testcase
+            .write_reproducer(path, data)
+            .expect("failed to write reproducer");
+        return;
+    }
+
     let fuel: u8 = std::env::args()
         .find_map(|arg| arg.strip_prefix("--fuel=").map(|s| s.to_owned()))
         .map(|fuel| fuel.parse().expect("fuel should be a valid integer"))
@@ -26,12 +26,11 @@ use wasmtime_wasi_threads::WasiThreadsCtx;
 #[cfg(feature = "wasi-http")]
 use wasmtime_wasi_http::WasiHttp;
 
-fn parse_env_var(s: &str) -> Result<(String, Option<String>)> {
-    let mut parts = s.splitn(2, '=');
-    Ok((
-        parts.next().unwrap().to_string(),
-        parts.next().map(|s| s.to_string()),
-    ))
+fn parse_env_var(s: &str) -> Result<(String, String)> {
+    match s.split_once('=') {
+        Some((name, value)) if !name.is_empty() => Ok((name.to_string(), value.to_string())),
+        _ => bail!("must be of the form `NAME=VAL`"),
+    }
 }
 
 fn parse_map_dirs(s: &str) -> Result<(String, String)> {
@@ -134,12 +133,17 @@ pub struct RunCommand {
     /// Pass an environment variable to the program.
     ///
     /// The `--env FOO=BAR` form will set the environment variable named `FOO`
-    /// to the value `BAR` for the guest program using WASI. The `--env FOO`
-    /// form will set the environment variable named `FOO` to the same value it
-    /// has in the calling process for the guest, or in other words it will
-    /// cause the environment variable `FOO` to be inherited.
-    #[clap(long = "env", number_of_values = 1, value_name = "NAME[=VAL]", value_parser = parse_env_var)]
-    vars: Vec<(String, Option<String>)>,
+    /// to the value `BAR` for the guest program using WASI. Use
+    /// `--inherit-env` to pass through the calling process's environment.
+    #[clap(long = "env", number_of_values = 1, value_name = "NAME=VAL", value_parser = parse_env_var)]
+    vars: Vec<(String, String)>,
+
+    /// Inherit all environment variables of the calling process.
+    ///
+    /// Variables specified with `--env` take precedence over inherited ones.
+    /// Variables whose name or value isn't valid UTF-8 are skipped.
+    #[clap(long = "inherit-env")]
+    inherit_env: bool,
 
     /// The name of the function to run
     #[clap(long, value_name = "FUNCTION")]
@@ -291,6 +295,7 @@ impl RunCommand {
             preopen_dirs,
             &argv,
             &self.vars,
+            self.inherit_env,
             &self.common.wasi_modules.unwrap_or(WasiModules::default()),
             self.listenfd,
             preopen_sockets,
@@ -362,19 +367,11 @@ impl RunCommand {
         let mut preopen_dirs = Vec::new();
 
         for dir in self.dirs.iter() {
-            preopen_dirs.push((
-                dir.clone(),
-                Dir::open_ambient_dir(dir, ambient_authority())
-                    .with_context(|| format!("failed to open directory '{}'", dir))?,
-            ));
+            preopen_dirs.push((dir.clone(), open_preopen_dir(dir)?));
         }
 
         for (guest, host) in self.map_dirs.iter() {
-            preopen_dirs.push((
-                guest.clone(),
-                Dir::open_ambient_dir(host, ambient_authority())
-                    .with_context(|| format!("failed to open directory '{}'", host))?,
-            ));
+            preopen_dirs.push((guest.clone(), open_preopen_dir(host)?));
         }
 
         Ok(preopen_dirs)
@@ -573,16 +570,30 @@ impl RunCommand {
             let val = val
                 .to_str()
                 .ok_or_else(|| anyhow!("argument is not valid utf-8: {val:?}"))?;
-            values.push(match ty {
-                // TODO: integer parsing here should handle hexadecimal notation
-                // like `0x0...`, but the Rust standard library currently only
-                // parses base-10 representations.
-                ValType::I32 => Val::I32(val.parse()?),
-                ValType::I64 => Val::I64(val.parse()?),
-                ValType::F32 => Val::F32(val.parse()?),
-                ValType::F64 => Val::F64(val.parse()?),
-                t => bail!("unsupported argument type {:?}", t),
-            });
+            values.push(
+                match ty {
+                    // TODO: integer parsing here should handle hexadecimal notation
+                    // like `0x0...`, but the Rust standard library currently only
+                    // parses base-10 representations.
+                    ValType::I32 => val.parse().map(Val::I32).map_err(anyhow::Error::from),
+                    ValType::I64 => val.parse().map(Val::I64).map_err(anyhow::Error::from),
+                    ValType::F32 => val.parse().map(Val::F32).map_err(anyhow::Error::from),
+                    ValType::F64 => val.parse().map(Val::F64).map_err(anyhow::Error::from),
+                    t => bail!("unsupported argument type {:?}", t),
+                }
+                .with_context(|| format!("failed to parse argument `{val}` as {ty}"))?,
+            );
+        }
+        // Remaining arguments are only an error when invoking a specific
+        // function, otherwise they're the WASI arguments of the command.
+        if let Some(name) = &self.invoke {
+            if args.next().is_some() {
+                bail!(
+                    "too many arguments for `{}`: expected {}",
+                    name,
+                    ty.params().len()
+                )
+            }
         }
 
         // Invoke the function and then afterwards print all the results that came
@@ -670,6 +681,22 @@ struct Host {
     guest_profiler: Option<Arc<GuestProfiler>>,
 }
 
+/// Opens the host directory `dir` so it can be preopened for the guest.
+fn open_preopen_dir(dir: &str) -> Result<Dir> {
+    let path = Path::new(dir);
+    if !path.exists() {
+        bail!(
+            "failed to open directory '{}': no such file or directory",
+            dir
+        );
+    }
+    if !path.is_dir() {
+        bail!("failed to open directory '{}': not a directory", dir);
+    }
+    Dir::open_ambient_dir(path, ambient_authority())
+        .with_context(|| format!("failed to open directory '{}'", dir))
+}
+
 /// Populates the given `Linker` with WASI APIs.
 fn populate_with_wasi(
     linker: &mut Linker<Host>,
@@ -677,7 +704,8 @@ fn populate_with_wasi(
     module: Module,
     preopen_dirs: Vec<(String, Dir)>,
     argv: &[String],
-    vars: &[(String, Option<String>)],
+    vars: &[(String, String)],
+    inherit_env: bool,
     wasi_modules: &WasiModules,
     listenfd: bool,
     mut tcplisten: Vec<TcpListener>,
@@ -688,13 +716,26 @@ fn populate_with_wasi(
         let mut builder = WasiCtxBuilder::new();
         builder.inherit_stdio().args(argv)?;
 
+        if inherit_env {
+            for (key, value) in std::env::vars_os() {
+                let (key, value) = match (key.into_string(), value.into_string()) {
+                    (Ok(key), Ok(value)) => (key, value),
+                    (key, _) => {
+                        eprintln!(
+                            "warning: not inheriting environment variable `{}` which isn't valid UTF-8",
+                            key.unwrap_or_else(|k| k.to_string_lossy().into_owned())
+                        );
+                        continue;
+                    }
+                };
+                if !vars.iter().any(|(k, _)| *k == key) {
+                    builder.env(&key, &value)?;
+                }
+            }
+        }
+
         for (key, value) in vars {
-            let value = match value {
-                Some(value) => value.clone(),
-                None => std::env::var(key)
-                    .map_err(|_| anyhow!("environment varialbe `{key}` not found"))?,
-            };
-            builder.env(key, &value)?;
+            builder.env(key, value)?;
         }
 
         let mut num_fd: usize = 3;
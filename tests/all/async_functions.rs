@@ -665,6 +665,50 @@ async fn linker_module_reactor() -> Result<()> {
     Ok(())
 }
 
+#[tokio::test]
+async fn linker_instantiate_start_calls_async_import() -> Result<()> {
+    let engine = Engine::new(Config::new().async_support(true))?;
+    let mut store = Store::new(&engine, 0);
+    let mut linker = Linker::new(&engine);
+    linker.func_wrap0_async("host", "init", |mut caller: Caller<'_, i32>| {
+        Box::new(async move {
+            tokio::task::yield_now().await;
+            *caller.data_mut() += 1;
+            Ok(())
+        })
+    })?;
+    let module = Module::new(
+        &engine,
+        r#"
+            (module
+                (import "host" "init" (func $init))
+                (func $start call $init)
+                (start $start)
+                (func (export "f"))
+            )
+        "#,
+    )?;
+
+    // The start function suspends in the host import, so instantiation
+    // itself must be driven as a future.
+    let (instance, yields) =
+        CountPending::new(Box::pin(linker.instantiate_async(&mut store, &module))).await;
+    let instance = instance?;
+    assert!(yields > 0);
+    assert_eq!(*store.data(), 1);
+    assert!(instance.get_func(&mut store, "f").is_some());
+
+    // Registering a module by name runs its start function the same way.
+    let (result, yields) =
+        CountPending::new(Box::pin(linker.module_async(&mut store, "m", &module))).await;
+    result?;
+    assert!(yields > 0);
+    assert_eq!(*store.data(), 2);
+    assert!(linker.get(&mut store, "m", "f").is_some());
+
+    Ok(())
+}
+
 pub struct CountPending<F> {
     future: F,
     yields: usize,
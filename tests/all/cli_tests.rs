@@ -122,6 +122,24 @@ fn run_coredump_smoketest() -> Result<()> {
     Ok(())
 }
 
+#[test]
+fn run_guest_profile_smoketest() -> Result<()> {
+    let wasm = build_wasm("tests/all/cli_tests/hot_loop.wat")?;
+    let profile_file = NamedTempFile::new()?;
+    let profile_arg = format!("--profile=guest,{},1ms", profile_file.path().display());
+    run_wasmtime(&[
+        "run",
+        "--invoke",
+        "run",
+        "--disable-cache",
+        &profile_arg,
+        wasm.path().to_str().unwrap(),
+    ])?;
+    let profile = std::fs::read_to_string(profile_file.path())?;
+    assert!(profile.contains("hot_loop"), "{profile}");
+    Ok(())
+}
+
 // Running simple wat
 #[test]
 fn run_wasmtime_simple_wat() -> Result<()> {
@@ -157,6 +175,42 @@ fn run_wasmtime_simple_wat() -> Result<()> {
     Ok(())
 }
 
+// Invoking a function with multiple arguments, and with the wrong arguments.
+#[test]
+fn run_wasmtime_invoke_add() -> Result<()> {
+    let wasm = build_wasm("tests/all/cli_tests/simple.wat")?;
+    let wasm = wasm.path().to_str().unwrap();
+    assert_eq!(
+        run_wasmtime(&["run", "--invoke", "add", "--disable-cache", wasm, "1", "2"])?,
+        "3\n"
+    );
+
+    let err = run_wasmtime(&["run", "--invoke", "add", "--disable-cache", wasm, "1"])
+        .unwrap_err()
+        .to_string();
+    assert!(err.contains("not enough arguments for `add`"), "{err}");
+
+    let err = run_wasmtime(&[
+        "run",
+        "--invoke",
+        "add",
+        "--disable-cache",
+        wasm,
+        "1",
+        "2",
+        "3",
+    ])
+    .unwrap_err()
+    .to_string();
+    assert!(err.contains("too many arguments for `add`"), "{err}");
+
+    let err = run_wasmtime(&["run", "--invoke", "add", "--disable-cache", wasm, "1", "x"])
+        .unwrap_err()
+        .to_string();
+    assert!(err.contains("failed to parse argument `x` as i32"), "{err}");
+    Ok(())
+}
+
 // Running a wat that traps.
 #[test]
 fn run_wasmtime_unreachable_wat() -> Result<()> {
@@ -441,6 +495,51 @@ fn run_cwasm() -> Result<()> {
     Ok(())
 }
 
+#[test]
+fn run_cwasm_hello_wasi() -> Result<()> {
+    let td = TempDir::new()?;
+    let cwasm = td.path().join("hello.cwasm");
+    run_wasmtime(&[
+        "compile",
+        "tests/all/cli_tests/hello_wasi_snapshot1.wat",
+        "-o",
+        cwasm.to_str().unwrap(),
+    ])?;
+    let stdout = run_wasmtime(&["run", "--allow-precompiled", cwasm.to_str().unwrap()])?;
+    assert_eq!(stdout, "Hello, world!\n");
+
+    // Precompiled modules are rejected without the flag.
+    let output = run_wasmtime_for_output(&["run", cwasm.to_str().unwrap()], None)?;
+    assert!(!output.status.success());
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(
+        stderr.contains("--allow-precompiled"),
+        "bad stderr: {stderr}"
+    );
+
+    // Artifacts compiled with settings that don't match the engine are
+    // rejected with an error describing the mismatch.
+    let cwasm = td.path().join("epoch.cwasm");
+    run_wasmtime(&[
+        "compile",
+        "--epoch-interruption",
+        "tests/all/cli_tests/hello_wasi_snapshot1.wat",
+        "-o",
+        cwasm.to_str().unwrap(),
+    ])?;
+    let output = run_wasmtime_for_output(
+        &["run", "--allow-precompiled", cwasm.to_str().unwrap()],
+        None,
+    )?;
+    assert!(!output.status.success());
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(
+        stderr.contains("compiled with epoch interruption but it is not enabled"),
+        "bad stderr: {stderr}"
+    );
+    Ok(())
+}
+
 #[cfg(unix)]
 #[test]
 fn hello_wasi_snapshot0_from_stdin() -> Result<()> {
@@ -486,25 +585,75 @@ fn specify_env() -> Result<()> {
     assert!(output.status.success());
     assert_eq!(String::from_utf8_lossy(&output.stdout), "FOO=bar\n");
 
-    // Inherit a single env var
+    // A name without a value is rejected, even if it's set on the host
     let output = get_wasmtime_command()?
         .args(&["run", "--env", "FOO", "tests/all/cli_tests/print_env.wat"])
         .env("FOO", "bar")
         .output()?;
+    assert!(!output.status.success());
+
+    Ok(())
+}
+
+#[test]
+fn inherit_env() -> Result<()> {
+    // Inherit the whole environment
+    let output = get_wasmtime_command()?
+        .args(&["run", "--inherit-env", "tests/all/cli_tests/print_env.wat"])
+        .env("FOO", "bar")
+        .output()?;
     assert!(output.status.success());
-    assert_eq!(String::from_utf8_lossy(&output.stdout), "FOO=bar\n");
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(
+        stdout.lines().any(|l| l == "FOO=bar"),
+        "bad stdout: {stdout}"
+    );
 
-    // Inherit a nonexistent env var
+    // Explicit variables override inherited ones
     let output = get_wasmtime_command()?
         .args(&[
             "run",
+            "--inherit-env",
             "--env",
-            "SURELY_THIS_ENV_VAR_DOES_NOT_EXIST_ANYWHERE_RIGHT",
+            "FOO=baz",
             "tests/all/cli_tests/print_env.wat",
         ])
+        .env("FOO", "bar")
+        .output()?;
+    assert!(output.status.success());
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    let foo = stdout
+        .lines()
+        .filter(|l| l.starts_with("FOO="))
+        .collect::<Vec<_>>();
+    assert_eq!(foo, ["FOO=baz"]);
+
+    // Variables without a name are rejected
+    let output = get_wasmtime_command()?
+        .args(&["run", "--env", "=bar", "tests/all/cli_tests/print_env.wat"])
         .output()?;
     assert!(!output.status.success());
 
+    // Variables that aren't valid UTF-8 are skipped rather than aborting
+    #[cfg(unix)]
+    {
+        use std::ffi::OsStr;
+        use std::os::unix::ffi::OsStrExt;
+
+        let output = get_wasmtime_command()?
+            .args(&["run", "--inherit-env", "tests/all/cli_tests/print_env.wat"])
+            .env("FOO", "bar")
+            .env("NOT_UTF8", OsStr::from_bytes(b"\xff"))
+            .output()?;
+        assert!(output.status.success());
+        let stdout = String::from_utf8_lossy(&output.stdout);
+        assert!(
+            stdout.lines().any(|l| l == "FOO=bar"),
+            "bad stdout: {stdout}"
+        );
+        assert!(!stdout.contains("NOT_UTF8"), "bad stdout: {stdout}");
+    }
+
     Ok(())
 }
 
@@ -731,3 +880,64 @@ fn wasi_misaligned_pointer() -> Result<()> {
     );
     Ok(())
 }
+
+#[test]
+fn preopen_dir() -> Result<()> {
+    let td = TempDir::new()?;
+    std::fs::write(td.path().join("a.txt"), "a")?;
+    std::fs::write(td.path().join("b.txt"), "b")?;
+    let dir = td.path().to_str().unwrap();
+
+    let stdout = run_wasmtime(&["run", "--dir", dir, "tests/all/cli_tests/print_dir.wat"])?;
+    let mut entries = stdout
+        .lines()
+        .filter(|l| *l != "." && *l != "..")
+        .collect::<Vec<_>>();
+    entries.sort();
+    assert_eq!(entries, ["a.txt", "b.txt"]);
+
+    let mapdir = format!("/guest::{dir}");
+    let stdout = run_wasmtime(&[
+        "run",
+        "--mapdir",
+        &mapdir,
+        "tests/all/cli_tests/print_dir.wat",
+    ])?;
+    let mut entries = stdout
+        .lines()
+        .filter(|l| *l != "." && *l != "..")
+        .collect::<Vec<_>>();
+    entries.sort();
+    assert_eq!(entries, ["a.txt", "b.txt"]);
+
+    Ok(())
+}
+
+#[test]
+fn preopen_dir_not_a_directory() -> Result<()> {
+    let td = TempDir::new()?;
+    let file = td.path().join("file.txt");
+    std::fs::write(&file, "not a directory")?;
+
+    let output = get_wasmtime_command()?
+        .args(&["run", "--dir", file.to_str().unwrap()])
+        .arg("tests/all/cli_tests/print_dir.wat")
+        .output()?;
+    assert!(!output.status.success());
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(stderr.contains("not a directory"), "bad stderr: {stderr}");
+
+    let missing = td.path().join("missing");
+    let output = get_wasmtime_command()?
+        .args(&["run", "--dir", missing.to_str().unwrap()])
+        .arg("tests/all/cli_tests/print_dir.wat")
+        .output()?;
+    assert!(!output.status.success());
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(
+        stderr.contains("no such file or directory"),
+        "bad stderr: {stderr}"
+    );
+
+    Ok(())
+}
@@ -0,0 +1,7 @@
+(module
+  (func $hot_loop (export "run") (local i32)
+    (local.set 0 (i32.const 100000000))
+    (loop $l
+      (local.set 0 (i32.sub (local.get 0) (i32.const 1)))
+      (br_if $l (local.get 0))))
+)
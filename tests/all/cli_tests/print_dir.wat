@@ -0,0 +1,74 @@
+(module
+  (import "wasi_snapshot_preview1" "fd_write"
+    (func $fd_write (param i32 i32 i32 i32) (result i32)))
+
+  (import "wasi_snapshot_preview1" "fd_readdir"
+    (func $fd_readdir (param i32 i32 i32 i64 i32) (result i32)))
+
+  (memory (export "memory") 1)
+
+  ;; Prints the name of every entry in the first preopened directory (fd 3),
+  ;; one per line.
+  (func (export "_start")
+    (local $buf i32)
+    (local $end i32)
+    (local $namelen i32)
+
+    (local.set $buf (i32.mul (memory.grow (i32.const 1)) (i32.const 65536)))
+
+    (if (i32.ne
+          (call $fd_readdir
+            (i32.const 3)
+            (local.get $buf)
+            (i32.const 65536)
+            (i64.const 0)
+            (i32.const 200))
+          (i32.const 0))
+        (unreachable))
+
+    (local.set $end (i32.add (local.get $buf) (i32.load (i32.const 200))))
+
+    ;; Each `dirent` is a 24 byte header followed by `d_namlen` bytes of name.
+    (loop
+      (if (i32.ge_u (i32.add (local.get $buf) (i32.const 24)) (local.get $end))
+        (return))
+      (local.set $namelen (i32.load offset=16 (local.get $buf)))
+      (local.set $buf (i32.add (local.get $buf) (i32.const 24)))
+
+      (call $write_all (local.get $buf) (local.get $namelen))
+      (call $write_all (i32.const 10) (i32.const 1))
+
+      (local.set $buf (i32.add (local.get $buf) (local.get $namelen)))
+      br 0
+    )
+  )
+
+  (func $write_all (param $ptr i32) (param $len i32)
+    (local $rc i32)
+    (local $iov i32)
+    (local $written i32)
+
+    (local.set $written (i32.const 80))
+    (local.set $iov (i32.const 100))
+
+    (loop
+      (local.get $len)
+      if
+        (i32.store (local.get $iov) (local.get $ptr))
+        (i32.store offset=4 (local.get $iov) (local.get $len))
+        (local.set $rc
+          (call $fd_write
+            (i32.const 1)
+            (local.get $iov)
+            (i32.const 1)
+            (local.get $written)))
+        (if (i32.ne (local.get $rc) (i32.const 0)) (unreachable))
+
+        (local.set $len (i32.sub (local.get $len) (i32.load (local.get $written))))
+        (local.set $ptr (i32.add (local.get $ptr) (i32.load (local.get $written))))
+      end
+    )
+  )
+
+  (data (i32.const 10) "\n")
+)
@@ -4,4 +4,9 @@
     )
     (func (export "get_f32") (result f32) f32.const 100)
     (func (export "get_f64") (result f64) f64.const 100)
+    (func (export "add") (param i32 i32) (result i32)
+        local.get 0
+        local.get 1
+        i32.add
+    )
 )
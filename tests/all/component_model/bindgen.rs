@@ -1,6 +1,6 @@
 #![cfg(not(miri))]
 
-use super::engine;
+use super::{async_engine, engine};
 use anyhow::Result;
 use wasmtime::{
     component::{Component, Linker},
@@ -53,6 +53,7 @@ mod no_imports {
         let (no_imports, _) = NoImports::instantiate(&mut store, &component, &linker)?;
         no_imports.call_bar(&mut store)?;
         no_imports.foo().call_foo(&mut store)?;
+        assert_eq!(no_imports.export_names(), ["foo#foo", "bar"]);
         Ok(())
     }
 }
@@ -120,3 +121,717 @@ mod one_import {
         Ok(())
     }
 }
+
+mod skip_debug {
+    wasmtime::component::bindgen!({
+        inline: "
+            package foo:foo
+
+            world skip-debug {
+                import foo: interface {
+                    record big-blob {
+                        data: list<u8>,
+                    }
+
+                    record small {
+                        a: u32,
+                    }
+
+                    enum color {
+                        red,
+                        green,
+                    }
+
+                    foo: func(a: big-blob, b: small, c: color)
+                }
+            }
+        ",
+        skip_debug: ["big-blob", "color"],
+    });
+
+    // These would conflict with generated impls if they were still emitted.
+    impl std::fmt::Debug for foo::BigBlob {
+        fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+            write!(f, "BigBlob({} bytes)", self.data.len())
+        }
+    }
+
+    impl std::fmt::Debug for foo::Color {
+        fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+            f.write_str("color")
+        }
+    }
+
+    #[test]
+    fn run() {
+        let blob = foo::BigBlob {
+            data: vec![0; 1024],
+        };
+        assert_eq!(format!("{blob:?}"), "BigBlob(1024 bytes)");
+        assert_eq!(format!("{:?}", foo::Color::Red), "color");
+        assert_eq!(format!("{:?}", foo::Small { a: 1 }), "Small { a: 1 }");
+    }
+}
+
+mod enum_discriminants {
+    wasmtime::component::bindgen!({
+        inline: "
+            package foo:foo
+
+            world enum-discriminants {
+                import foo: interface {
+                    enum shape {
+                        circle,
+                        square,
+                        triangle,
+                    }
+
+                    foo: func(a: shape)
+                }
+            }
+        ",
+    });
+
+    #[test]
+    fn run() {
+        use foo::Shape;
+
+        for (i, shape) in [Shape::Circle, Shape::Square, Shape::Triangle]
+            .into_iter()
+            .enumerate()
+        {
+            let i = i32::try_from(i).unwrap();
+            assert_eq!(shape.to_i32(), i);
+            assert!(Shape::from_i32(i) == Some(shape));
+        }
+        assert!(Shape::from_i32(-1).is_none());
+        assert!(Shape::from_i32(3).is_none());
+    }
+}
+
+mod add_to_linker_with {
+    use super::*;
+
+    wasmtime::component::bindgen!({
+        inline: "
+            package foo:foo
+
+            world two-imports {
+                import foo: interface {
+                    foo: func()
+                }
+
+                import bar: interface {
+                    bar: func()
+                }
+
+                export run: func()
+            }
+        ",
+    });
+
+    #[test]
+    fn run() -> Result<()> {
+        let engine = engine();
+
+        let component = Component::new(
+            &engine,
+            r#"
+                (component
+                    (import "foo" (instance $foo
+                        (export "foo" (func))
+                    ))
+                    (import "bar" (instance $bar
+                        (export "bar" (func))
+                    ))
+                    (core func $foo (canon lower (func $foo "foo")))
+                    (core func $bar (canon lower (func $bar "bar")))
+                    (core module $m
+                        (import "" "foo" (func $foo))
+                        (import "" "bar" (func $bar))
+                        (func (export "run")
+                            call $foo
+                            call $bar)
+                    )
+                    (core instance $i (instantiate $m
+                        (with "" (instance
+                            (export "foo" (func $foo))
+                            (export "bar" (func $bar))
+                        ))
+                    ))
+
+                    (func (export "run") (canon lift (core func $i "run")))
+                )
+            "#,
+        )?;
+
+        #[derive(Default)]
+        struct FooHost {
+            hit: bool,
+        }
+
+        impl foo::Host for FooHost {
+            fn foo(&mut self) -> Result<()> {
+                self.hit = true;
+                Ok(())
+            }
+        }
+
+        #[derive(Default)]
+        struct BarHost {
+            hit: bool,
+        }
+
+        impl bar::Host for BarHost {
+            fn bar(&mut self) -> Result<()> {
+                self.hit = true;
+                Ok(())
+            }
+        }
+
+        #[derive(Default)]
+        struct MyState {
+            foo: FooHost,
+            bar: BarHost,
+        }
+
+        fn get_foo(state: &mut MyState) -> &mut FooHost {
+            &mut state.foo
+        }
+
+        fn get_bar(state: &mut MyState) -> &mut BarHost {
+            &mut state.bar
+        }
+
+        let mut linker = Linker::new(&engine);
+        TwoImports::add_to_linker_with(
+            &mut linker,
+            TwoImportsGetters {
+                foo: get_foo,
+                bar: get_bar,
+            },
+        )?;
+        let mut store = Store::new(&engine, MyState::default());
+        let (two_imports, _) = TwoImports::instantiate(&mut store, &component, &linker)?;
+        two_imports.call_run(&mut store)?;
+        assert!(store.data().foo.hit);
+        assert!(store.data().bar.hit);
+        Ok(())
+    }
+}
+
+mod feature_gates {
+    use super::*;
+
+    wasmtime::component::bindgen!({
+        inline: "
+            package foo:foo
+
+            world gated {
+                import stable: interface {
+                    foo: func()
+                    bar: func()
+                }
+
+                import unstable: interface {
+                    baz: func()
+                }
+
+                import qux: func()
+
+                export run: func()
+            }
+        ",
+        feature_gates: {
+            "unstable": "unstable-bindgen-test",
+            "stable#bar": "unstable-bindgen-test",
+            "qux": "unstable-bindgen-test",
+        },
+    });
+
+    // This would conflict with the generated module if it were emitted.
+    mod unstable {}
+
+    #[test]
+    fn run() -> Result<()> {
+        let engine = engine();
+
+        let component = Component::new(
+            &engine,
+            r#"
+                (component
+                    (import "stable" (instance $stable
+                        (export "foo" (func))
+                    ))
+                    (core func $foo (canon lower (func $stable "foo")))
+                    (core module $m
+                        (import "" "foo" (func $foo))
+                        (func (export "run") call $foo)
+                    )
+                    (core instance $i (instantiate $m
+                        (with "" (instance
+                            (export "foo" (func $foo))
+                        ))
+                    ))
+
+                    (func (export "run") (canon lift (core func $i "run")))
+                )
+            "#,
+        )?;
+
+        #[derive(Default)]
+        struct MyHost {
+            hit: bool,
+        }
+
+        // Neither `bar` nor `qux` need to be implemented as they're gated.
+        impl stable::Host for MyHost {
+            fn foo(&mut self) -> Result<()> {
+                self.hit = true;
+                Ok(())
+            }
+        }
+
+        impl GatedImports for MyHost {}
+
+        // The gated `unstable` interface isn't required by `add_to_linker`.
+        let mut linker = Linker::new(&engine);
+        Gated::add_to_linker(&mut linker, |h: &mut MyHost| h)?;
+        let mut store = Store::new(&engine, MyHost::default());
+        let (gated, _) = Gated::instantiate(&mut store, &component, &linker)?;
+        gated.call_run(&mut store)?;
+        assert!(store.data().hit);
+        Ok(())
+    }
+}
+
+mod immutable_imports {
+    use super::*;
+
+    wasmtime::component::bindgen!({
+        inline: "
+            package foo:foo
+
+            world immutable {
+                import counter: interface {
+                    get: func() -> u32
+                    bump: func()
+                }
+
+                import peek: func() -> u32
+
+                export run: func() -> u32
+            }
+        ",
+        immutable_imports: ["counter#get", "peek"],
+    });
+
+    #[derive(Default)]
+    struct MyHost {
+        count: u32,
+    }
+
+    impl counter::Host for MyHost {
+        fn get(&self) -> Result<u32> {
+            Ok(self.count)
+        }
+
+        fn bump(&mut self) -> Result<()> {
+            self.count += 1;
+            Ok(())
+        }
+    }
+
+    impl ImmutableImports for MyHost {
+        fn peek(&self) -> Result<u32> {
+            Ok(100)
+        }
+    }
+
+    // Methods listed in `immutable_imports` only need a shared reference.
+    fn read(host: &(impl counter::Host + ImmutableImports)) -> Result<u32> {
+        Ok(host.get()? + host.peek()?)
+    }
+
+    #[test]
+    fn run() -> Result<()> {
+        let engine = engine();
+
+        let component = Component::new(
+            &engine,
+            r#"
+                (component
+                    (import "counter" (instance $counter
+                        (export "get" (func (result u32)))
+                        (export "bump" (func))
+                    ))
+                    (import "peek" (func $peek (result u32)))
+                    (core func $get_lowered (canon lower (func $counter "get")))
+                    (core func $bump_lowered (canon lower (func $counter "bump")))
+                    (core func $peek_lowered (canon lower (func $peek)))
+                    (core module $m
+                        (import "" "get" (func $get (result i32)))
+                        (import "" "bump" (func $bump))
+                        (import "" "peek" (func $peek (result i32)))
+                        (func (export "run") (result i32)
+                            call $bump
+                            call $bump
+                            call $get
+                            call $peek
+                            i32.add)
+                    )
+                    (core instance $i (instantiate $m
+                        (with "" (instance
+                            (export "get" (func $get_lowered))
+                            (export "bump" (func $bump_lowered))
+                            (export "peek" (func $peek_lowered))
+                        ))
+                    ))
+
+                    (func (export "run") (result u32) (canon lift (core func $i "run")))
+                )
+            "#,
+        )?;
+
+        let mut linker = Linker::new(&engine);
+        Immutable::add_to_linker(&mut linker, |h: &mut MyHost| h)?;
+        let mut store = Store::new(&engine, MyHost::default());
+        let (immutable, _) = Immutable::instantiate(&mut store, &component, &linker)?;
+        assert_eq!(immutable.call_run(&mut store)?, 102);
+        assert_eq!(read(store.data())?, 102);
+        Ok(())
+    }
+}
+
+mod immutable_imports_async {
+    use super::*;
+    use std::cell::Cell;
+
+    wasmtime::component::bindgen!({
+        inline: "
+            package foo:foo
+
+            world split {
+                import reader: interface {
+                    get: func() -> u32
+                }
+
+                import writer: interface {
+                    set: func(value: u32)
+                }
+
+                export run: func() -> u32
+            }
+        ",
+        async: true,
+        immutable_imports: ["reader#get"],
+    });
+
+    #[derive(Default)]
+    struct Reader;
+
+    #[async_trait::async_trait]
+    impl reader::Host for Reader {
+        async fn get(&self) -> Result<u32> {
+            Ok(7)
+        }
+    }
+
+    // Only takes `&mut self`, so it needn't be `Sync`.
+    #[derive(Default)]
+    struct Writer {
+        value: Cell<u32>,
+    }
+
+    #[async_trait::async_trait]
+    impl writer::Host for Writer {
+        async fn set(&mut self, value: u32) -> Result<()> {
+            self.value.set(value);
+            Ok(())
+        }
+    }
+
+    #[derive(Default)]
+    struct MyState {
+        reader: Reader,
+        writer: Writer,
+    }
+
+    fn get_reader(state: &mut MyState) -> &mut Reader {
+        &mut state.reader
+    }
+
+    fn get_writer(state: &mut MyState) -> &mut Writer {
+        &mut state.writer
+    }
+
+    #[test]
+    fn run() -> Result<()> {
+        let engine = async_engine();
+
+        let component = Component::new(
+            &engine,
+            r#"
+                (component
+                    (import "reader" (instance $reader
+                        (export "get" (func (result u32)))
+                    ))
+                    (import "writer" (instance $writer
+                        (export "set" (func (param "value" u32)))
+                    ))
+                    (core func $get_lowered (canon lower (func $reader "get")))
+                    (core func $set_lowered (canon lower (func $writer "set")))
+                    (core module $m
+                        (import "" "get" (func $get (result i32)))
+                        (import "" "set" (func $set (param i32)))
+                        (func (export "run") (result i32)
+                            call $get
+                            call $set
+                            i32.const 1)
+                    )
+                    (core instance $i (instantiate $m
+                        (with "" (instance
+                            (export "get" (func $get_lowered))
+                            (export "set" (func $set_lowered))
+                        ))
+                    ))
+
+                    (func (export "run") (result u32) (canon lift (core func $i "run")))
+                )
+            "#,
+        )?;
+
+        let mut linker = Linker::new(&engine);
+        Split::add_to_linker_with(
+            &mut linker,
+            SplitGetters {
+                reader: get_reader,
+                writer: get_writer,
+            },
+        )?;
+        let mut store = Store::new(&engine, MyState::default());
+        let runtime = tokio::runtime::Builder::new_current_thread().build()?;
+        let (split, _) =
+            runtime.block_on(Split::instantiate_async(&mut store, &component, &linker))?;
+        assert_eq!(runtime.block_on(split.call_run(&mut store))?, 1);
+        assert_eq!(store.data().writer.value.get(), 7);
+        Ok(())
+    }
+}
+
+mod blocking_wrappers {
+    use super::*;
+
+    wasmtime::component::bindgen!({
+        inline: "
+            package foo:foo
+
+            world blocking {
+                export answer: func() -> u32
+            }
+        ",
+        async: true,
+        generate_blocking_wrappers: true,
+    });
+
+    #[test]
+    fn run() -> Result<()> {
+        let engine = async_engine();
+
+        let component = Component::new(
+            &engine,
+            r#"
+                (component
+                    (core module $m
+                        (func (export "answer") (result i32)
+                            i32.const 42)
+                    )
+                    (core instance $i (instantiate $m))
+
+                    (func (export "answer") (result u32)
+                        (canon lift (core func $i "answer")))
+                )
+            "#,
+        )?;
+
+        let linker = Linker::new(&engine);
+        let mut store = Store::new(&engine, ());
+        let runtime = tokio::runtime::Builder::new_current_thread().build()?;
+        let (blocking, _) =
+            runtime.block_on(Blocking::instantiate_async(&mut store, &component, &linker))?;
+
+        // Both the async export and its blocking wrapper are generated.
+        let answer = runtime.block_on(blocking.call_answer(&mut store))?;
+        assert_eq!(answer, 42);
+        assert_eq!(blocking.call_answer_blocking(&mut store)?, 42);
+        Ok(())
+    }
+}
+
+mod resource_import {
+    use super::*;
+    use wasmtime::component::Resource;
+
+    wasmtime::component::bindgen!({
+        inline: "
+            package foo:foo
+
+            world resource-import {
+                import foo: interface {
+                    resource r {
+                        constructor(value: u32)
+                        value: func() -> u32
+                    }
+                }
+
+                export run: func() -> u32
+            }
+        ",
+    });
+
+    #[test]
+    fn run() -> Result<()> {
+        let engine = engine();
+
+        let component = Component::new(
+            &engine,
+            r#"
+                (component
+                    (import "foo" (instance $i
+                        (export "r" (type $r (sub resource)))
+                        (export "[constructor]r" (func (param "value" u32) (result (own $r))))
+                        (export "[method]r.value" (func (param "self" (borrow $r)) (result u32)))
+                    ))
+                    (alias export $i "r" (type $r))
+
+                    (core func $ctor (canon lower (func $i "[constructor]r")))
+                    (core func $value (canon lower (func $i "[method]r.value")))
+                    (core func $drop (canon resource.drop $r))
+
+                    (core module $m
+                        (import "" "ctor" (func $ctor (param i32) (result i32)))
+                        (import "" "value" (func $value (param i32) (result i32)))
+                        (import "" "drop" (func $drop (param i32)))
+
+                        (func (export "run") (result i32)
+                            (local $r i32)
+                            (local.set $r (call $ctor (i32.const 42)))
+                            (call $value (local.get $r))
+                            (call $drop (local.get $r)))
+                    )
+                    (core instance $i (instantiate $m
+                        (with "" (instance
+                            (export "ctor" (func $ctor))
+                            (export "value" (func $value))
+                            (export "drop" (func $drop))
+                        ))
+                    ))
+
+                    (func (export "run") (result u32) (canon lift (core func $i "run")))
+                )
+            "#,
+        )?;
+
+        #[derive(Default)]
+        struct MyImports {
+            dropped: Vec<u32>,
+        }
+
+        impl foo::HostR for MyImports {
+            fn new(&mut self, value: u32) -> Result<Resource<foo::R>> {
+                Ok(Resource::new_own(value))
+            }
+
+            fn value(&mut self, self_: Resource<foo::R>) -> Result<u32> {
+                assert!(!self_.owned());
+                Ok(self_.rep())
+            }
+
+            fn drop(&mut self, rep: Resource<foo::R>) -> Result<()> {
+                self.dropped.push(rep.rep());
+                Ok(())
+            }
+        }
+
+        impl foo::Host for MyImports {}
+
+        let mut linker = Linker::new(&engine);
+        foo::add_to_linker(&mut linker, |f: &mut MyImports| f)?;
+        let mut store = Store::new(&engine, MyImports::default());
+        let (resource_import, _) = ResourceImport::instantiate(&mut store, &component, &linker)?;
+        assert_eq!(resource_import.call_run(&mut store)?, 42);
+        assert_eq!(store.data().dropped, [42]);
+        Ok(())
+    }
+}
+
+mod resource_export {
+    use super::*;
+    use wasmtime::AsContextMut;
+
+    wasmtime::component::bindgen!({
+        inline: "
+            package foo:foo
+
+            world resource-export {
+                export i: interface {
+                    resource r {
+                        constructor(rep: u32)
+                        drops: static func() -> u32
+                    }
+                }
+            }
+        ",
+    });
+
+    #[test]
+    fn run() -> Result<()> {
+        let engine = engine();
+
+        let component = Component::new(
+            &engine,
+            r#"
+                (component
+                    (component $c
+                        (core module $m
+                            (global $drops (mut i32) i32.const 0)
+                            (func (export "dtor") (param i32)
+                                (global.set $drops (i32.add (global.get $drops) (i32.const 1))))
+                            (func (export "drops") (result i32) global.get $drops)
+                        )
+                        (core instance $i (instantiate $m))
+                        (type $r' (resource (rep i32) (dtor (func $i "dtor"))))
+                        (export $r "r" (type $r'))
+                        (core func $ctor (canon resource.new $r))
+                        (func (export "[constructor]r") (param "rep" u32) (result (own $r))
+                            (canon lift (core func $ctor)))
+                        (func (export "[static]r.drops") (result u32)
+                            (canon lift (core func $i "drops")))
+                    )
+                    (instance $i (instantiate $c))
+                    (export "i" (instance $i))
+                )
+            "#,
+        )?;
+
+        let linker = Linker::new(&engine);
+        let mut store = Store::new(&engine, ());
+        let (resource_export, _) = ResourceExport::instantiate(&mut store, &component, &linker)?;
+        let i = resource_export.i();
+
+        let r = i.call_constructor_r(&mut store, 42)?;
+        let mut r = exports::i::R::new(store.as_context_mut(), r);
+        assert!(r.resource().owned());
+        assert_eq!(i.call_static_r_drops(r.store())?, 0);
+
+        // Dropping the handle runs the guest's destructor.
+        drop(r);
+        assert_eq!(i.call_static_r_drops(&mut store)?, 1);
+
+        // `resource_drop` runs the destructor explicitly, returning its result.
+        let r = i.call_constructor_r(&mut store, 43)?;
+        exports::i::R::new(store.as_context_mut(), r).resource_drop()?;
+        assert_eq!(i.call_static_r_drops(&mut store)?, 2);
+        Ok(())
+    }
+}
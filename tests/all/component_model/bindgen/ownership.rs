@@ -327,3 +327,48 @@ fn borrowing_with_duplication() -> Result<()> {
 
     Ok(())
 }
+
+#[test]
+fn borrowed_record_to_owned() {
+    wasmtime::component::bindgen!({
+        inline: "
+        package inline:inline
+        world test {
+            export blobs: interface {
+                record blob {
+                    name: string,
+                    data: list<u8>,
+                    contents: contents,
+                }
+
+                variant contents {
+                    empty,
+                    bytes(list<u8>),
+                }
+
+                roundtrip: func(a: blob) -> blob
+            }
+        }",
+        ownership: Borrowing {
+            duplicate_if_necessary: true
+        }
+    });
+
+    let data = [1, 2, 3, 4];
+    let contents = [5, 6];
+    let borrowed = exports::blobs::BlobParam {
+        name: "blob",
+        data: &data,
+        contents: exports::blobs::ContentsParam::Bytes(&contents),
+    };
+    let owned = exports::blobs::BlobResult::from(&borrowed);
+    assert_eq!(owned.name, "blob");
+    assert_eq!(owned.data, vec![1, 2, 3, 4]);
+    match owned.contents {
+        exports::blobs::ContentsResult::Bytes(bytes) => assert_eq!(bytes, vec![5, 6]),
+        exports::blobs::ContentsResult::Empty => panic!("expected bytes"),
+    }
+
+    let owned = exports::blobs::ContentsResult::from(&exports::blobs::ContentsParam::Empty);
+    assert!(matches!(owned, exports::blobs::ContentsResult::Empty));
+}
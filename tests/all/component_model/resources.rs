@@ -383,6 +383,42 @@ fn drop_host_twice() -> Result<()> {
     Ok(())
 }
 
+#[test]
+fn fallible_host_dtor() -> Result<()> {
+    let engine = super::engine();
+    let c = Component::new(
+        &engine,
+        r#"
+            (component
+                (import "t" (type $t (sub resource)))
+
+                (core func $dtor (canon resource.drop $t))
+                (func (export "dtor") (param "x" (own $t))
+                    (canon lift (core func $dtor)))
+            )
+        "#,
+    )?;
+
+    struct MyType;
+
+    let mut store = Store::new(&engine, ());
+    let mut linker = Linker::new(&engine);
+    linker
+        .root()
+        .resource_fallible::<MyType>("t", |_, rep| anyhow::bail!("failed to drop {rep}"))?;
+    let i = linker.instantiate(&mut store, &c)?;
+    let dtor = i.get_typed_func::<(&Resource<MyType>,), ()>(&mut store, "dtor")?;
+
+    let t = Resource::new_own(100);
+    let err = dtor.call(&mut store, (&t,)).unwrap_err();
+    assert!(
+        format!("{err:?}").contains("failed to drop 100"),
+        "bad error: {err:?}"
+    );
+
+    Ok(())
+}
+
 #[test]
 fn manually_destroy() -> Result<()> {
     let engine = super::engine();
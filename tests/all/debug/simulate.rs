@@ -97,3 +97,37 @@ fn test_debug_dwarf_simulate_with_invalid_name_x86_64() -> Result<()> {
 )"#,
     )
 }
+
+#[test]
+#[cfg(all(
+    any(target_os = "linux", target_os = "macos"),
+    target_pointer_width = "64"
+))]
+fn test_debug_dwarf_simulate_line_program() -> Result<()> {
+    use object::{Object, ObjectSection};
+    use wasmtime::{Config, Engine};
+
+    let wasm = parse_str(
+        r#"
+        (module
+            (func (param i32) (result i32)
+                local.get 0
+                i32.const 1
+                i32.add
+            )
+        )"#,
+    )?;
+    let mut config = Config::new();
+    config.debug_info(true);
+    let engine = Engine::new(&config)?;
+    let bytes = engine.precompile_module(&wasm)?;
+
+    // Modules without DWARF of their own still get a simulated line program
+    // mapping native code offsets back to wasm bytecode offsets.
+    let obj = object::File::parse(&bytes[..])?;
+    let section = obj
+        .section_by_name(".debug_line")
+        .expect("missing .debug_line section");
+    assert!(!section.data()?.is_empty());
+    Ok(())
+}
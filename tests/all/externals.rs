@@ -193,6 +193,106 @@ fn get_set_externref_globals_via_api() -> anyhow::Result<()> {
     Ok(())
 }
 
+#[test]
+fn externref_data_borrows() {
+    use std::sync::RwLock;
+
+    let r = ExternRef::new(RwLock::new(String::from("hello")));
+    let alias = r.clone();
+    let lock = r.data().downcast_ref::<RwLock<String>>().unwrap();
+
+    // Shared borrows through any handle coexist.
+    {
+        let a = lock.read().unwrap();
+        let b = alias
+            .data()
+            .downcast_ref::<RwLock<String>>()
+            .unwrap()
+            .read()
+            .unwrap();
+        assert_eq!(*a, *b);
+    }
+
+    // A second mutable borrow through an aliasing handle is rejected.
+    {
+        let mut guard = lock.write().unwrap();
+        guard.push_str(", world");
+        let other = alias.data().downcast_ref::<RwLock<String>>().unwrap();
+        assert!(other.try_write().is_err());
+        assert!(other.try_read().is_err());
+    }
+    assert_eq!(*lock.read().unwrap(), "hello, world");
+
+    // Downcasting to the wrong type fails instead of reinterpreting the data.
+    assert!(r.data().downcast_ref::<String>().is_none());
+    assert!(r.data().downcast_ref::<RwLock<u32>>().is_none());
+}
+
+#[test]
+fn round_trip_host_externref_through_wasm_global() -> anyhow::Result<()> {
+    #[derive(Debug, PartialEq)]
+    struct HostData {
+        name: String,
+        id: u32,
+    }
+
+    let mut cfg = Config::new();
+    cfg.wasm_reference_types(true);
+    let engine = Engine::new(&cfg)?;
+    let mut store = Store::new(&engine, ());
+    let module = Module::new(
+        &engine,
+        r#"
+            (module
+                (global (export "g") (mut externref) (ref.null extern))
+                (func (export "swap") (param externref) (result externref)
+                    global.get 0
+                    local.get 0
+                    global.set 0))
+        "#,
+    )?;
+    let instance = Instance::new(&mut store, &module, &[])?;
+    let global = instance.get_global(&mut store, "g").unwrap();
+    let swap = instance.get_func(&mut store, "swap").unwrap();
+
+    // Set from the host via the `From<ExternRef>` conversion and read it back.
+    let data = ExternRef::new(HostData {
+        name: "first".to_string(),
+        id: 1,
+    });
+    global.set(&mut store, data.clone().into())?;
+    let r = global.get(&mut store).externref().unwrap().unwrap();
+    assert!(r.ptr_eq(&data));
+    assert_eq!(
+        r.data().downcast_ref::<HostData>(),
+        Some(&HostData {
+            name: "first".to_string(),
+            id: 1,
+        })
+    );
+    assert!(r.data().downcast_ref::<String>().is_none());
+
+    // Pass a second reference through wasm, which stores it in the global and
+    // hands back the previous one.
+    let second = ExternRef::new(HostData {
+        name: "second".to_string(),
+        id: 2,
+    });
+    let mut results = [Val::null()];
+    swap.call(&mut store, &[second.clone().into()], &mut results)?;
+    let prev = results[0].unwrap_externref().unwrap();
+    assert!(prev.ptr_eq(&data));
+    let r = global.get(&mut store).unwrap_externref().unwrap();
+    assert!(r.ptr_eq(&second));
+    assert_eq!(r.data().downcast_ref::<HostData>().unwrap().id, 2);
+
+    // Non-externref values don't downcast to an externref.
+    assert!(Val::I32(0).externref().is_none());
+    assert!(Val::ExternRef(None).externref().unwrap().is_none());
+
+    Ok(())
+}
+
 #[test]
 fn get_set_funcref_globals_via_api() -> anyhow::Result<()> {
     let mut cfg = Config::new();
@@ -440,3 +540,34 @@ fn read_write_memory_via_api() {
     let res = mem.write(&mut store, usize::MAX, &mut buffer);
     assert!(res.is_err());
 }
+
+#[test]
+fn read_write_memory_tracks_current_size() -> anyhow::Result<()> {
+    let mut store = Store::<()>::default();
+    let mem = Memory::new(&mut store, MemoryType::new(1, Some(2)))?;
+    let page = mem.data_size(&store);
+
+    // Zero-length accesses are fine right at the end of memory, but not past it.
+    mem.write(&mut store, page, &[])?;
+    mem.read(&store, page, &mut [])?;
+    assert!(mem.write(&mut store, page + 1, &[]).is_err());
+    assert!(mem.read(&store, page + 1, &mut []).is_err());
+
+    // An access straddling the end of memory fails until the memory grows.
+    let value = b"across pages";
+    let offset = page - 4;
+    assert!(mem.write(&mut store, offset, value).is_err());
+    assert!(mem.data(&store)[offset..].iter().all(|b| *b == 0));
+
+    mem.grow(&mut store, 1)?;
+    mem.write(&mut store, offset, value)?;
+    let mut buffer = [0u8; 12];
+    mem.read(&store, offset, &mut buffer)?;
+    assert_eq!(&buffer, value);
+
+    // The error is a proper `std::error::Error` that converts into `anyhow`.
+    let err = anyhow::Error::from(mem.read(&store, 2 * page, &mut buffer).unwrap_err());
+    assert!(err.to_string().contains("out of bounds"), "{err}");
+
+    Ok(())
+}
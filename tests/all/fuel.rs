@@ -234,3 +234,54 @@ fn unconditionally_trapping_memory_accesses_save_fuel_before_trapping() {
     assert!(consumed_fuel > 0);
     assert_eq!(init_fuel, consumed_fuel + store.fuel_remaining().unwrap());
 }
+
+#[test]
+#[cfg_attr(miri, ignore)]
+fn loop_iterations_bounded_by_fuel() {
+    let mut config = Config::new();
+    config.consume_fuel(true);
+    let engine = Engine::new(&config).unwrap();
+    let module = Module::new(
+        &engine,
+        r#"
+            (module
+              (global $count (export "count") (mut i32) (i32.const 0))
+              (func (export "run")
+                loop
+                  global.get $count
+                  i32.const 1
+                  i32.add
+                  global.set $count
+                  br 0
+                end))
+        "#,
+    )
+    .unwrap();
+
+    let mut store = Store::new(&engine, ());
+    let instance = Instance::new(&mut store, &module, &[]).unwrap();
+    let run = instance
+        .get_typed_func::<(), ()>(&mut store, "run")
+        .unwrap();
+    let count = instance.get_global(&mut store, "count").unwrap();
+
+    store.add_fuel(1_000).unwrap();
+    let trap = run.call(&mut store, ()).unwrap_err();
+    assert_eq!(trap.downcast::<Trap>().unwrap(), Trap::OutOfFuel);
+    let first = count.get(&mut store).unwrap_i32();
+    // Each iteration executes several operations, so the loop must stop well
+    // before the number of fuel units handed out.
+    assert!(first > 0 && first < 1_000, "ran {first} iterations");
+
+    // Adding the same amount of fuel again allows the same number of
+    // iterations to run again.
+    store.add_fuel(1_000).unwrap();
+    let trap = run.call(&mut store, ()).unwrap_err();
+    assert_eq!(trap.downcast::<Trap>().unwrap(), Trap::OutOfFuel);
+    let second = count.get(&mut store).unwrap_i32() - first;
+    assert!(
+        (second - first).abs() <= 1,
+        "{first} vs {second} iterations"
+    );
+    assert!(store.fuel_consumed().unwrap() >= 2_000);
+}
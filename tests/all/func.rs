@@ -1422,3 +1422,78 @@ fn calls_with_funcref_and_externref() -> anyhow::Result<()> {
 
     Ok(())
 }
+
+#[test]
+fn func_type_display_round_trip() -> Result<()> {
+    for s in [
+        "() -> ()",
+        "(i32) -> ()",
+        "() -> (f64)",
+        "(i32, i32) -> (i64)",
+        "(i32, i64, f32, f64, v128) -> (externref, funcref)",
+    ] {
+        let ty = s.parse::<FuncType>()?;
+        assert_eq!(ty.to_string(), s);
+    }
+
+    let ty = FuncType::new([ValType::I32, ValType::I32], [ValType::I64]);
+    assert_eq!(ty.to_string(), "(i32, i32) -> (i64)");
+    assert_eq!(" ( i32,i32 )->( i64 ) ".parse::<FuncType>()?, ty);
+
+    assert!("(i32)".parse::<FuncType>().is_err());
+    assert!("i32 -> ()".parse::<FuncType>().is_err());
+    assert!("(i8) -> ()".parse::<FuncType>().is_err());
+    assert!("(i32,) -> ()".parse::<FuncType>().is_err());
+    Ok(())
+}
+
+#[test]
+#[cfg_attr(miri, ignore)]
+fn caller_reads_string_from_memory() -> Result<()> {
+    let mut store = Store::<Option<String>>::default();
+    let mut linker = Linker::new(store.engine());
+    linker.func_wrap(
+        "host",
+        "log",
+        |mut caller: Caller<'_, Option<String>>, ptr: u32, len: u32| -> Result<()> {
+            let memory = match caller.get_export("memory") {
+                Some(Extern::Memory(memory)) => memory,
+                _ => bail!("failed to find `memory` export"),
+            };
+            let bytes = memory
+                .data(&caller)
+                .get(ptr as usize..)
+                .and_then(|s| s.get(..len as usize))
+                .ok_or_else(|| anyhow::anyhow!("pointer/length out of bounds"))?;
+            let s = std::str::from_utf8(bytes)?.to_string();
+            *caller.data_mut() = Some(s);
+            Ok(())
+        },
+    )?;
+    let module = Module::new(
+        store.engine(),
+        r#"
+            (module
+                (import "host" "log" (func $log (param i32 i32)))
+                (memory (export "memory") 1)
+                (data (i32.const 100) "hello from wasm")
+                (func (export "run")
+                    (call $log (i32.const 100) (i32.const 15)))
+                (func (export "oob")
+                    (call $log (i32.const 65530) (i32.const 15))))
+        "#,
+    )?;
+    let instance = linker.instantiate(&mut store, &module)?;
+
+    let run = instance.get_typed_func::<(), ()>(&mut store, "run")?;
+    run.call(&mut store, ())?;
+    assert_eq!(store.data().as_deref(), Some("hello from wasm"));
+
+    let oob = instance.get_typed_func::<(), ()>(&mut store, "oob")?;
+    let err = oob.call(&mut store, ()).unwrap_err();
+    assert!(
+        format!("{err:?}").contains("pointer/length out of bounds"),
+        "bad error: {err:?}"
+    );
+    Ok(())
+}
@@ -55,6 +55,18 @@ fn mutability() -> anyhow::Result<()> {
     Ok(())
 }
 
+#[test]
+fn type_mismatch() -> anyhow::Result<()> {
+    let mut store = Store::<()>::default();
+    let ty = GlobalType::new(ValType::I32, Mutability::Var);
+    assert!(Global::new(&mut store, ty.clone(), 0i64.into()).is_err());
+
+    let g = Global::new(&mut store, ty, 0.into())?;
+    assert!(g.set(&mut store, 1.0f32.into()).is_err());
+    assert_eq!(g.get(&mut store).i32(), Some(0));
+    Ok(())
+}
+
 // Make sure that a global is still usable after its original instance is
 // dropped. This is a bit of a weird test and really only fails depending on the
 // implementation, but for now should hopefully be resilient enough to catch at
@@ -733,3 +733,162 @@ fn wasi_imports() -> Result<()> {
 
     Ok(())
 }
+
+#[test]
+#[cfg_attr(miri, ignore)]
+fn wasi_stdin_bytes() -> Result<()> {
+    let engine = Engine::default();
+    let mut linker = Linker::new(&engine);
+    wasmtime_wasi::add_to_linker(&mut linker, |s| s)?;
+
+    // Reads stdin in 16-byte chunks until end-of-file and returns the total
+    // number of bytes read.
+    let wasm = wat::parse_str(
+        r#"
+        (import "wasi_snapshot_preview1" "fd_read"
+            (func $fd_read (param i32 i32 i32 i32) (result i32)))
+        (memory (export "memory") 1)
+        (func (export "read_stdin") (result i32)
+            (local $total i32)
+            (i32.store (i32.const 0) (i32.const 64))
+            (i32.store (i32.const 4) (i32.const 16))
+            (loop $more
+                (if (call $fd_read (i32.const 0) (i32.const 0) (i32.const 1) (i32.const 8))
+                    (then unreachable))
+                (if (i32.load (i32.const 8))
+                    (then
+                        (local.set $total
+                            (i32.add (local.get $total) (i32.load (i32.const 8))))
+                        (br $more))))
+            (local.get $total))
+        "#,
+    )?;
+
+    let module = Module::new(&engine, wasm)?;
+    let ctx = WasiCtxBuilder::new().stdin_bytes(vec![7; 100]).build();
+    let mut store = Store::new(&engine, ctx);
+    let instance = linker.instantiate(&mut store, &module)?;
+
+    let read_stdin = instance.get_typed_func::<(), i32>(&mut store, "read_stdin")?;
+    assert_eq!(read_stdin.call(&mut store, ())?, 100);
+    // The buffer stays exhausted.
+    assert_eq!(read_stdin.call(&mut store, ())?, 0);
+
+    Ok(())
+}
+
+#[test]
+#[cfg_attr(miri, ignore)]
+fn wasi_stdout_pipe() -> Result<()> {
+    let engine = Engine::default();
+    let mut linker = Linker::new(&engine);
+    wasmtime_wasi::add_to_linker(&mut linker, |s| s)?;
+
+    let wasm = wat::parse_str(
+        r#"
+        (import "wasi_snapshot_preview1" "fd_write"
+            (func $fd_write (param i32 i32 i32 i32) (result i32)))
+        (memory (export "memory") 1)
+        (data (i32.const 16) "Hello, world!\n")
+        (func (export "_start")
+            (i32.store (i32.const 0) (i32.const 16))
+            (i32.store (i32.const 4) (i32.const 14))
+            (drop (call $fd_write (i32.const 1) (i32.const 0) (i32.const 1) (i32.const 8)))
+            (drop (call $fd_write (i32.const 2) (i32.const 0) (i32.const 1) (i32.const 8))))
+        "#,
+    )?;
+
+    let module = Module::new(&engine, wasm)?;
+    let mut builder = WasiCtxBuilder::new();
+    let stdout = builder.stdout_pipe();
+    let stderr = builder.stderr_pipe();
+    let mut store = Store::new(&engine, builder.build());
+    let instance = linker.instantiate(&mut store, &module)?;
+
+    let start = instance.get_typed_func::<(), ()>(&mut store, "_start")?;
+    start.call(&mut store, ())?;
+    assert_eq!(stdout.contents(), b"Hello, world!\n");
+    assert_eq!(stderr.contents(), b"Hello, world!\n");
+
+    Ok(())
+}
+
+#[test]
+#[cfg_attr(miri, ignore)]
+fn wasi_sock_send_recv_echo() -> Result<()> {
+    use std::io::{Read, Write};
+
+    // Networking may be disabled in some CI environments, in which case there's
+    // nothing to test here.
+    let listener = match std::net::TcpListener::bind("127.0.0.1:0") {
+        Ok(listener) => listener,
+        Err(_) => return Ok(()),
+    };
+    let addr = listener.local_addr()?;
+    let server = std::thread::spawn(move || -> std::io::Result<()> {
+        let (mut stream, _) = listener.accept()?;
+        let mut buf = [0; 64];
+        loop {
+            let n = stream.read(&mut buf)?;
+            if n == 0 {
+                return Ok(());
+            }
+            stream.write_all(&buf[..n])?;
+        }
+    });
+
+    let engine = Engine::default();
+    let mut linker = Linker::new(&engine);
+    wasmtime_wasi::add_to_linker(&mut linker, |s| s)?;
+
+    // Sends a message on the socket at fd 3, shuts down its write half and
+    // then receives until end-of-stream, returning the number of bytes read
+    // into memory at offset 128.
+    let wasm = wat::parse_str(
+        r#"
+        (import "wasi_snapshot_preview1" "sock_send"
+            (func $sock_send (param i32 i32 i32 i32 i32) (result i32)))
+        (import "wasi_snapshot_preview1" "sock_recv"
+            (func $sock_recv (param i32 i32 i32 i32 i32 i32) (result i32)))
+        (import "wasi_snapshot_preview1" "sock_shutdown"
+            (func $sock_shutdown (param i32 i32) (result i32)))
+        (memory (export "memory") 1)
+        (data (i32.const 64) "hello over tcp")
+        (func (export "echo") (result i32)
+            (local $total i32)
+            (i32.store (i32.const 0) (i32.const 64))
+            (i32.store (i32.const 4) (i32.const 14))
+            (if (call $sock_send (i32.const 3) (i32.const 0) (i32.const 1) (i32.const 0) (i32.const 8))
+                (then unreachable))
+            (if (i32.ne (i32.load (i32.const 8)) (i32.const 14))
+                (then unreachable))
+            (if (call $sock_shutdown (i32.const 3) (i32.const 2))
+                (then unreachable))
+            (loop $more
+                (i32.store (i32.const 16) (i32.add (i32.const 128) (local.get $total)))
+                (i32.store (i32.const 20) (i32.sub (i32.const 64) (local.get $total)))
+                (if (call $sock_recv (i32.const 3) (i32.const 16) (i32.const 1) (i32.const 0) (i32.const 24) (i32.const 28))
+                    (then unreachable))
+                (if (i32.load (i32.const 24))
+                    (then
+                        (local.set $total
+                            (i32.add (local.get $total) (i32.load (i32.const 24))))
+                        (br $more))))
+            (local.get $total))
+        "#,
+    )?;
+
+    let module = Module::new(&engine, wasm)?;
+    let stream = cap_std::net::TcpStream::from_std(std::net::TcpStream::connect(addr)?);
+    let ctx = WasiCtxBuilder::new().preopened_socket(3, stream)?.build();
+    let mut store = Store::new(&engine, ctx);
+    let instance = linker.instantiate(&mut store, &module)?;
+
+    let echo = instance.get_typed_func::<(), i32>(&mut store, "echo")?;
+    let len = echo.call(&mut store, ())? as usize;
+    let memory = instance.get_memory(&mut store, "memory").unwrap();
+    assert_eq!(&memory.data(&store)[128..][..len], b"hello over tcp");
+
+    server.join().unwrap()?;
+    Ok(())
+}
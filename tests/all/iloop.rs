@@ -135,3 +135,33 @@ fn function_interrupt_from_afar() -> anyhow::Result<()> {
     assert_eq!(trap, Trap::Interrupt);
     Ok(())
 }
+
+#[test]
+fn deadline_reached_by_timer_thread() -> anyhow::Result<()> {
+    // A guest which spins without ever calling out to the host, interrupted
+    // only once a timer thread has ticked the epoch past the deadline.
+    static STOP: AtomicBool = AtomicBool::new(false);
+    let mut store = interruptable_store();
+    store.set_epoch_deadline(5);
+    let module = Module::new(store.engine(), r#"(func (export "loop") (loop br 0))"#)?;
+    let instance = Instance::new(&mut store, &module, &[])?;
+
+    let engine = store.engine().clone();
+    let thread = std::thread::spawn(move || {
+        let mut ticks = 0;
+        while !STOP.load(SeqCst) {
+            std::thread::sleep(std::time::Duration::from_millis(1));
+            engine.increment_epoch();
+            ticks += 1;
+        }
+        ticks
+    });
+
+    let iloop = instance.get_typed_func::<(), ()>(&mut store, "loop")?;
+    let trap = iloop.call(&mut store, ()).unwrap_err().downcast::<Trap>()?;
+    STOP.store(true, SeqCst);
+    let ticks = thread.join().unwrap();
+    assert!(ticks >= 5, "interrupted after only {ticks} ticks");
+    assert_eq!(trap, Trap::Interrupt);
+    Ok(())
+}
@@ -12,6 +12,25 @@ fn wrong_import_numbers() -> Result<()> {
     Ok(())
 }
 
+#[test]
+fn wrong_import_kind_names_import() -> Result<()> {
+    let mut store = Store::<()>::default();
+    let module = Module::new(
+        store.engine(),
+        r#"(module (import "env" "g" (global i32)))"#,
+    )?;
+
+    let func = Func::wrap(&mut store, || {});
+    let err = Instance::new(&mut store, &module, &[func.into()]).unwrap_err();
+    let msg = format!("{err:?}");
+    assert!(
+        msg.contains("incompatible import type for `env::g`"),
+        "{msg}"
+    );
+    assert!(msg.contains("expected global, but found function"), "{msg}");
+    Ok(())
+}
+
 #[test]
 #[cfg_attr(miri, ignore)]
 fn initializes_linear_memory() -> Result<()> {
@@ -187,6 +187,56 @@ fn module_interposition() -> Result<()> {
     Ok(())
 }
 
+// Replacing an instance's definitions by re-registering it under the same
+// name, for example to reload code.
+#[test]
+#[cfg_attr(miri, ignore)]
+fn instance_replacement() -> Result<()> {
+    let mut store = Store::<()>::default();
+    let mut linker = Linker::new(store.engine());
+    linker.allow_shadowing(true);
+    let engine = store.engine().clone();
+    let version = |n: i32| {
+        Module::new(
+            &engine,
+            format!(r#"(module (func (export "version") (result i32) (i32.const {n})))"#),
+        )
+    };
+    let user = Module::new(
+        &engine,
+        r#"(module
+            (import "lib" "version" (func $version (result i32)))
+            (func (export "run") (result i32) (call $version))
+        )"#,
+    )?;
+
+    let lib1 = version(1)?;
+    let lib1 = linker.instantiate(&mut store, &lib1)?;
+    linker.instance(&mut store, "lib", lib1)?;
+    let user1 = linker.instantiate(&mut store, &user)?;
+    let run1 = user1.get_typed_func::<(), i32>(&mut store, "run")?;
+    assert_eq!(run1.call(&mut store, ())?, 1);
+
+    let lib2 = version(2)?;
+    let lib2 = linker.instantiate(&mut store, &lib2)?;
+    linker.instance(&mut store, "lib", lib2)?;
+
+    // A fresh lookup resolves to the new instance's exports...
+    let func = linker
+        .get(&mut store, "lib", "version")
+        .unwrap()
+        .into_func()
+        .unwrap();
+    assert_eq!(func.typed::<(), i32>(&store)?.call(&mut store, ())?, 2);
+    let user2 = linker.instantiate(&mut store, &user)?;
+    let run2 = user2.get_typed_func::<(), i32>(&mut store, "run")?;
+    assert_eq!(run2.call(&mut store, ())?, 2);
+
+    // ...while the instance linked against the old one keeps using it.
+    assert_eq!(run1.call(&mut store, ())?, 1);
+    Ok(())
+}
+
 #[test]
 #[cfg_attr(miri, ignore)]
 fn allow_unknown_exports() -> Result<()> {
@@ -363,6 +413,43 @@ fn instance_pre() -> Result<()> {
     Ok(())
 }
 
+#[test]
+#[cfg_attr(miri, ignore)]
+fn instance_pre_instances_have_independent_memory() -> Result<()> {
+    let engine = Engine::default();
+    let mut linker = Linker::new(&engine);
+    linker.func_wrap("", "double", |x: i32| x * 2)?;
+
+    let module = Module::new(
+        &engine,
+        r#"(module
+            (import "" "double" (func $double (param i32) (result i32)))
+            (memory (export "memory") 1)
+            (func (export "store") (param i32)
+                i32.const 0
+                local.get 0
+                call $double
+                i32.store)
+            (func (export "load") (result i32)
+                i32.const 0
+                i32.load)
+        )"#,
+    )?;
+    let instance_pre = linker.instantiate_pre(&module)?;
+
+    let mut store = Store::new(&engine, ());
+    let a = instance_pre.instantiate(&mut store)?;
+    let b = instance_pre.instantiate(&mut store)?;
+
+    a.get_typed_func::<i32, ()>(&mut store, "store")?
+        .call(&mut store, 21)?;
+    let load_a = a.get_typed_func::<(), i32>(&mut store, "load")?;
+    let load_b = b.get_typed_func::<(), i32>(&mut store, "load")?;
+    assert_eq!(load_a.call(&mut store, ())?, 42);
+    assert_eq!(load_b.call(&mut store, ())?, 0);
+    Ok(())
+}
+
 #[test]
 #[cfg_attr(miri, ignore)]
 fn test_trapping_unknown_import() -> Result<()> {
@@ -362,6 +362,22 @@ fn tiny_static_heap() -> Result<()> {
     Ok(())
 }
 
+#[test]
+fn grow_updates_data_size() -> Result<()> {
+    let mut store = Store::<()>::default();
+    let mem = Memory::new(&mut store, MemoryType::new(1, Some(3)))?;
+    assert_eq!(mem.data_size(&store), 65536);
+
+    assert_eq!(mem.grow(&mut store, 2)?, 1);
+    assert_eq!(mem.size(&store), 3);
+    assert_eq!(mem.data_size(&store), 3 * 65536);
+    assert_eq!(mem.data(&store).len(), 3 * 65536);
+
+    assert!(mem.grow(&mut store, 1).is_err());
+    assert_eq!(mem.data_size(&store), 3 * 65536);
+    Ok(())
+}
+
 #[test]
 fn static_forced_max() -> Result<()> {
     let mut config = Config::new();
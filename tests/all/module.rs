@@ -69,6 +69,32 @@ fn aot_compiles() -> Result<()> {
     Ok(())
 }
 
+#[test]
+#[cfg_attr(miri, ignore)]
+fn aot_compiles_on_another_thread() -> Result<()> {
+    let engine = Engine::default();
+    let bytes = std::thread::spawn({
+        let engine = engine.clone();
+        move || {
+            engine.precompile_module(
+                "(module (func (export \"f\") (param i32) (result i32) local.get 0))".as_bytes(),
+            )
+        }
+    })
+    .join()
+    .unwrap()?;
+
+    let module = unsafe { Module::deserialize(&engine, &bytes)? };
+
+    let mut store = Store::new(&engine, ());
+    let instance = Instance::new(&mut store, &module, &[])?;
+
+    let f = instance.get_typed_func::<i32, i32>(&mut store, "f")?;
+    assert_eq!(f.call(&mut store, 101)?, 101);
+
+    Ok(())
+}
+
 #[test]
 #[cfg_attr(miri, ignore)]
 fn serialize_deterministic() {
@@ -113,6 +139,36 @@ fn serialize_deterministic() {
     assert_deterministic("(module (elem func) (elem func))");
 }
 
+#[test]
+#[cfg_attr(miri, ignore)]
+fn parallel_compilation_deterministic() -> Result<()> {
+    let mut wat = String::from("(module\n");
+    for i in 0..200 {
+        wat.push_str(&format!(
+            "(func (export \"f{i}\") (param i32) (result i32)
+                local.get 0
+                i32.const {i}
+                i32.add
+                call $leaf)\n"
+        ));
+    }
+    wat.push_str("(func $leaf (param i32) (result i32) local.get 0))");
+
+    let precompile = |parallel: bool| -> Result<Vec<u8>> {
+        let mut config = Config::new();
+        config.parallel_compilation(parallel);
+        Engine::new(&config)?.precompile_module(wat.as_bytes())
+    };
+    let sequential = precompile(false)?;
+    let parallel = precompile(true)?;
+    assert!(
+        sequential == parallel,
+        "parallel compilation is not deterministic"
+    );
+
+    Ok(())
+}
+
 // This test asserts that the optimization to transform separate data segments
 // into an initialization image doesn't unnecessarily create a massive module by
 // accident with a very large initialization image in it.
@@ -216,3 +272,175 @@ fn missing_sse_and_floats_still_works() -> Result<()> {
 
     Ok(())
 }
+
+#[test]
+fn function_metrics() -> Result<()> {
+    let wat = r#"
+        (module
+            (func (export "a") (result i32) i32.const 1)
+            (func (export "b") (param i32) (result i32)
+                local.get 0
+                i32.const 2
+                i32.mul)
+        )
+    "#;
+
+    let engine = Engine::default();
+    let module = Module::new(&engine, wat)?;
+    assert!(module.function_metrics().is_none());
+
+    let mut config = Config::new();
+    config.collect_compilation_metrics(true);
+    let engine = Engine::new(&config)?;
+    let module = Module::new(&engine, wat)?;
+    let metrics = module.function_metrics().unwrap();
+    assert_eq!(metrics.len(), 2);
+    for ((size, _time), (_start, len)) in metrics.iter().zip(module.function_locations()) {
+        assert!(*size > 0);
+        assert!(*size <= len);
+    }
+
+    Ok(())
+}
+
+#[test]
+#[cfg_attr(any(
+    miri,
+    // TODO(6530): s390x doesn't support tail calls yet.
+    target_arch = "s390x"
+), ignore)]
+fn tail_call_requires_config() -> Result<()> {
+    let wat = r#"
+        (module
+            (func $f (result i32)
+                i32.const 1)
+            (func (export "g") (result i32)
+                return_call $f))
+    "#;
+
+    let engine = Engine::default();
+    assert!(Module::new(&engine, wat).is_err());
+
+    let mut config = Config::new();
+    config.wasm_tail_call(true);
+    let engine = Engine::new(&config)?;
+    Module::new(&engine, wat)?;
+    Ok(())
+}
+
+#[test]
+#[cfg_attr(miri, ignore)]
+fn module_limits_config() -> Result<()> {
+    let wat = r#"
+        (module
+            (func (local i32 i64 f32))
+            (func))
+    "#;
+
+    let engine = Engine::default();
+    Module::new(&engine, wat)?;
+
+    let mut config = Config::new();
+    config.module_limits(ModuleLimits {
+        max_locals: 2,
+        ..ModuleLimits::default()
+    });
+    let engine = Engine::new(&config)?;
+    let err = Module::new(&engine, wat).unwrap_err();
+    assert!(
+        format!("{err:?}").contains("local count of 3 exceeds limit of 2"),
+        "{err:?}"
+    );
+
+    config.module_limits(ModuleLimits {
+        max_functions: 1,
+        ..ModuleLimits::default()
+    });
+    let engine = Engine::new(&config)?;
+    let err = Module::new(&engine, wat).unwrap_err();
+    assert!(
+        format!("{err:?}").contains("function count of 2 exceeds limit of 1"),
+        "{err:?}"
+    );
+    Ok(())
+}
+
+#[test]
+#[cfg_attr(miri, ignore)]
+fn custom_sections() -> Result<()> {
+    let wat = r#"
+        (module
+            (@custom "a" "first")
+            (@custom "b" (after func) "second")
+            (func))
+    "#;
+    let expected = [("a", &b"first"[..]), ("b", &b"second"[..])];
+
+    // Custom sections are dropped unless requested.
+    let engine = Engine::default();
+    let module = Module::new(&engine, wat)?;
+    assert_eq!(module.custom_sections().len(), 0);
+
+    let mut config = Config::new();
+    config.retain_custom_sections(true);
+    let engine = Engine::new(&config)?;
+    let module = Module::new(&engine, wat)?;
+    assert_eq!(module.custom_sections().collect::<Vec<_>>(), expected);
+
+    // They're also preserved through serialization.
+    let bytes = module.serialize()?;
+    let module = unsafe { Module::deserialize(&engine, &bytes)? };
+    assert_eq!(module.custom_sections().collect::<Vec<_>>(), expected);
+    Ok(())
+}
+
+#[test]
+fn imports_and_exports_reported() -> Result<()> {
+    let engine = Engine::default();
+    let module = Module::new(
+        &engine,
+        r#"
+            (module
+                (import "env" "f" (func (param i32) (result i64)))
+                (import "env" "g" (global (mut f32)))
+                (import "other" "m" (memory 1 2))
+                (table (export "t") 3 funcref)
+                (global (export "c") i32 (i32.const 0))
+                (func (export "run") (param f64)))
+        "#,
+    )?;
+
+    let imports = module.imports().collect::<Vec<_>>();
+    assert_eq!(imports.len(), 3);
+    assert_eq!((imports[0].module(), imports[0].name()), ("env", "f"));
+    let f = imports[0].ty().unwrap_func().clone();
+    assert_eq!(f.params().collect::<Vec<_>>(), [ValType::I32]);
+    assert_eq!(f.results().collect::<Vec<_>>(), [ValType::I64]);
+    assert_eq!((imports[1].module(), imports[1].name()), ("env", "g"));
+    let g = imports[1].ty().unwrap_global().clone();
+    assert_eq!(*g.content(), ValType::F32);
+    assert_eq!(g.mutability(), Mutability::Var);
+    assert_eq!((imports[2].module(), imports[2].name()), ("other", "m"));
+    let m = imports[2].ty().unwrap_memory().clone();
+    assert_eq!(m.minimum(), 1);
+    assert_eq!(m.maximum(), Some(2));
+
+    let exports = module.exports().collect::<Vec<_>>();
+    assert_eq!(
+        exports.iter().map(|e| e.name()).collect::<Vec<_>>(),
+        ["t", "c", "run"]
+    );
+    let t = exports[0].ty().unwrap_table().clone();
+    assert_eq!(t.element(), ValType::FuncRef);
+    assert_eq!(t.minimum(), 3);
+    let c = exports[1].ty().unwrap_global().clone();
+    assert_eq!(*c.content(), ValType::I32);
+    assert_eq!(c.mutability(), Mutability::Const);
+    let run = exports[2].ty().unwrap_func().clone();
+    assert_eq!(run.params().collect::<Vec<_>>(), [ValType::F64]);
+    assert_eq!(run.results().len(), 0);
+    assert!(module.get_export("run").is_some());
+    assert!(module.get_export("missing").is_none());
+
+    Ok(())
+}
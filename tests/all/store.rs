@@ -20,3 +20,30 @@ fn into_inner() {
     Store::new(&engine, A).into_data();
     assert_eq!(HITS.load(SeqCst), 2);
 }
+
+#[test]
+fn host_state_is_per_store() {
+    use std::sync::Arc;
+    use wasmtime::AsContextMut;
+
+    let engine = Engine::default();
+    let mut a = Store::new(&engine, ());
+    let mut b = Store::new(&engine, ());
+
+    let first = a.as_context_mut().__host_state(|| AtomicUsize::new(1));
+    let again = a.as_context_mut().__host_state(|| AtomicUsize::new(2));
+    assert!(Arc::ptr_eq(&first, &again));
+    assert_eq!(again.load(SeqCst), 1);
+
+    // Each type gets its own value, and other stores don't share it.
+    let s = a.as_context_mut().__host_state(|| String::from("a"));
+    assert_eq!(*s, "a");
+    let other = b.as_context_mut().__host_state(|| AtomicUsize::new(3));
+    assert_eq!(other.load(SeqCst), 3);
+
+    // The value is dropped along with the store.
+    drop(a);
+    assert_eq!(Arc::strong_count(&first), 2);
+    drop(again);
+    assert_eq!(Arc::strong_count(&first), 1);
+}
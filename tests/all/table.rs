@@ -52,6 +52,58 @@ fn copy_wrong() {
     );
 }
 
+#[test]
+fn grow() -> Result<()> {
+    let mut store = Store::<()>::default();
+    let ty = TableType::new(ValType::ExternRef, 1, Some(3));
+    let table = Table::new(&mut store, ty, Val::ExternRef(None))?;
+    let init = Val::ExternRef(Some(ExternRef::new(42u32)));
+    assert_eq!(table.grow(&mut store, 2, init)?, 1);
+    assert_eq!(table.size(&store), 3);
+    match table.get(&mut store, 2) {
+        Some(Val::ExternRef(Some(r))) => assert_eq!(r.data().downcast_ref::<u32>(), Some(&42)),
+        _ => panic!(),
+    }
+    assert!(table.grow(&mut store, 1, Val::ExternRef(None)).is_err());
+    assert_eq!(table.size(&store), 3);
+    Ok(())
+}
+
+#[test]
+fn fill_out_of_bounds() -> Result<()> {
+    let mut store = Store::<()>::default();
+    let ty = TableType::new(ValType::ExternRef, 2, None);
+    let table = Table::new(&mut store, ty, Val::ExternRef(None))?;
+    let err = table
+        .fill(&mut store, 1, Val::ExternRef(None), 2)
+        .unwrap_err();
+    assert_eq!(err.downcast_ref::<Trap>(), Some(&Trap::TableOutOfBounds));
+    Ok(())
+}
+
+#[test]
+fn copy_between_tables() -> Result<()> {
+    let mut store = Store::<()>::default();
+    let ty = TableType::new(ValType::ExternRef, 4, None);
+    let src = Table::new(&mut store, ty.clone(), Val::ExternRef(None))?;
+    let dst = Table::new(&mut store, ty, Val::ExternRef(None))?;
+    let val = Val::ExternRef(Some(ExternRef::new("hello")));
+    src.fill(&mut store, 1, val, 2)?;
+
+    Table::copy(&mut store, &dst, 2, &src, 1, 2)?;
+    for i in 0..4 {
+        let is_some = match dst.get(&mut store, i) {
+            Some(Val::ExternRef(r)) => r.is_some(),
+            _ => panic!(),
+        };
+        assert_eq!(is_some, i >= 2);
+    }
+
+    let err = Table::copy(&mut store, &dst, 3, &src, 0, 2).unwrap_err();
+    assert_eq!(err.downcast_ref::<Trap>(), Some(&Trap::TableOutOfBounds));
+    Ok(())
+}
+
 #[test]
 #[cfg_attr(miri, ignore)]
 fn null_elem_segment_works_with_imported_table() -> Result<()> {
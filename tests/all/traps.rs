@@ -145,6 +145,60 @@ fn test_trap_trace() -> Result<()> {
     Ok(())
 }
 
+#[test]
+fn test_trap_trace_deep_call_chain() -> Result<()> {
+    let mut store = Store::<()>::default();
+    let wat = r#"
+        (module $chain
+            (func (export "run") (param i32) (result i32)
+                local.get 0
+                call $outer)
+            (func $outer (param i32) (result i32)
+                local.get 0
+                call $recurse)
+            (func $recurse (param i32) (result i32)
+                local.get 0
+                i32.eqz
+                if (result i32)
+                    i32.const 1
+                    i32.const 0
+                    call $divide
+                else
+                    local.get 0
+                    i32.const 1
+                    i32.sub
+                    call $recurse
+                end)
+            (func $divide (param i32 i32) (result i32)
+                local.get 0
+                local.get 1
+                i32.div_u)
+        )
+    "#;
+
+    let module = Module::new(store.engine(), wat)?;
+    let instance = Instance::new(&mut store, &module, &[])?;
+    let run = instance.get_typed_func::<i32, i32>(&mut store, "run")?;
+
+    let depth = 10;
+    let e = run.call(&mut store, depth).unwrap_err();
+    let trace = e.downcast_ref::<WasmBacktrace>().unwrap().frames();
+
+    // The trapping callee is the youngest frame, followed by every level of
+    // the recursion and then the callers that entered it.
+    let names = trace.iter().map(|f| f.func_name()).collect::<Vec<_>>();
+    let mut expected = vec![Some("divide")];
+    expected.extend((0..=depth).map(|_| Some("recurse")));
+    expected.extend([Some("outer"), None]);
+    assert_eq!(names, expected);
+    assert_eq!(trace[0].func_index(), 3);
+    assert!(trace.iter().all(|f| f.module_name() == Some("chain")));
+    assert!(trace.iter().all(|f| f.func_offset().is_some()));
+    assert_eq!(e.downcast::<Trap>()?, Trap::IntegerDivisionByZero);
+
+    Ok(())
+}
+
 #[test]
 fn test_trap_through_host() -> Result<()> {
     let wat = r#"
@@ -3,14 +3,16 @@ use anyhow::{anyhow, Result};
 use core::fmt::Formatter;
 use cranelift_codegen::isa::{CallConv, IsaBuilder};
 use cranelift_codegen::settings;
-use cranelift_codegen::{Final, MachBufferFinalized, TextSectionBuilder};
+use cranelift_codegen::{Final, MachBufferFinalized, MachReloc, TextSectionBuilder};
 use std::{
     error,
     fmt::{self, Debug, Display},
 };
 use target_lexicon::{Architecture, Triple};
 use wasmparser::{FuncValidator, FunctionBody, ValidatorResources};
-use wasmtime_environ::{ModuleTranslation, WasmFuncType};
+use wasmtime_environ::{
+    DefinedFuncIndex, FunctionBodyData, ModuleTranslation, TypeConvert, WasmFuncType,
+};
 
 #[cfg(feature = "x64")]
 pub(crate) mod x64;
@@ -213,3 +215,38 @@ impl Debug for &dyn TargetIsa {
         )
     }
 }
+
+/// The machine code and relocations of a function compiled with
+/// [`compile_function`].
+pub struct CompiledFunction {
+    /// The raw machine code bytes.
+    pub code: Vec<u8>,
+    /// The relocations that need to be applied to `code` before it can be
+    /// executed.
+    pub relocs: Vec<MachReloc>,
+}
+
+/// Compile the defined function `index` of `translation` for `isa`.
+///
+/// This is an entry point for embedders that want to use Winch to compile
+/// individual functions without going through the full module pipeline.
+pub fn compile_function(
+    isa: &dyn TargetIsa,
+    translation: &ModuleTranslation,
+    index: DefinedFuncIndex,
+    data: FunctionBodyData<'_>,
+) -> Result<CompiledFunction> {
+    let types = translation.get_types();
+    let index = translation.module.func_index(index);
+    let sig = types[types.function_at(index.as_u32())].unwrap_func();
+    let sig = translation.module.convert_func_type(sig);
+
+    let FunctionBodyData { body, validator } = data;
+    let mut validator = validator.into_validator(Default::default());
+    let buffer = isa.compile_function(&sig, &body, translation, &mut validator)?;
+
+    Ok(CompiledFunction {
+        code: buffer.data().to_vec(),
+        relocs: buffer.relocs().to_vec(),
+    })
+}
@@ -0,0 +1,8 @@
+;;! targets = ["x86_64", "aarch64"]
+;;! expect_error = "type mismatch"
+
+(module
+    (func (result i32)
+	(i64.const 10)
+    )
+)
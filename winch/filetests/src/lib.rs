@@ -3,7 +3,7 @@ pub mod disasm;
 #[cfg(test)]
 mod test {
     use super::disasm::disasm;
-    use anyhow::Context;
+    use anyhow::{anyhow, Context, Result};
     use cranelift_codegen::settings::{self, Configurable};
     use serde::{Deserialize, Serialize};
     use similar::TextDiff;
@@ -12,15 +12,35 @@ mod test {
     use wasmtime_environ::ModuleTranslation;
     use wasmtime_environ::{
         wasmparser::{Parser as WasmParser, Validator},
-        DefinedFuncIndex, FunctionBodyData, ModuleEnvironment, Tunables, TypeConvert,
+        DefinedFuncIndex, FunctionBodyData, ModuleEnvironment, Tunables,
     };
-    use winch_codegen::{lookup, TargetIsa};
+    use winch_codegen::{compile_function, lookup, TargetIsa};
     use winch_test_macros::generate_file_tests;
 
     #[derive(Clone, Debug, Serialize, Deserialize)]
     struct TestConfig {
-        target: String,
+        target: Option<String>,
+        #[serde(default)]
+        targets: Vec<String>,
         flags: Option<Vec<String>>,
+        expect_error: Option<String>,
+    }
+
+    impl TestConfig {
+        /// All the targets that the test should be compiled for.
+        fn targets(&self) -> Vec<&str> {
+            let targets: Vec<_> = self
+                .target
+                .iter()
+                .chain(self.targets.iter())
+                .map(|t| t.as_str())
+                .collect();
+            assert!(
+                !targets.is_empty(),
+                "the test configuration must specify `target` or `targets`"
+            );
+            targets
+        }
     }
 
     /// A helper function to parse the test configuration from the top of the file.
@@ -88,35 +108,52 @@ mod test {
 
         let config = parse_config(wat);
         let wasm = wat::parse_str(&wat).unwrap();
-        let triple = Triple::from_str(&config.target).unwrap();
+        let targets = config.targets();
 
         let binding = parse_expected_result(wat);
         let expected = binding.as_str();
 
-        let shared_flags = settings::Flags::new(settings::builder());
-        let mut isa_builder = lookup(triple).unwrap();
-        for flag in config.flags.iter().flat_map(|f| f) {
-            isa_builder.set(&flag, "true").unwrap();
-        }
-        let isa = isa_builder.finish(shared_flags).unwrap();
+        let mut outputs = Vec::with_capacity(targets.len());
+        for target in targets.iter() {
+            let result = compile_for_target(target, &config, &wasm);
 
-        let mut validator = Validator::new();
-        let parser = WasmParser::new(0);
-        let mut types = Default::default();
-        let tunables = Tunables::default();
-        let mut translation = ModuleEnvironment::new(&tunables, &mut validator, &mut types)
-            .translate(parser, &wasm)
-            .context("Failed to translate WebAssembly module")
-            .unwrap();
-        let _ = types.finish();
+            if let Some(expected_error) = &config.expect_error {
+                match result {
+                    Ok(_) => panic!(
+                        "expected compilation for `{}` to fail with `{}`",
+                        target, expected_error
+                    ),
+                    Err(e) => {
+                        let message = format!("{:?}", e);
+                        assert!(
+                            message.contains(expected_error.as_str()),
+                            "expected error containing `{}` for `{}`, got: {}",
+                            expected_error,
+                            target,
+                            message
+                        );
+                    }
+                }
+                continue;
+            }
 
-        let body_inputs = std::mem::take(&mut translation.function_body_inputs);
+            let output = result
+                .with_context(|| format!("Couldn't compile for target `{}`", target))
+                .unwrap();
+            // Files that declare a single target keep the plain disassembly,
+            // otherwise each target's disassembly is preceded by its name.
+            if targets.len() == 1 {
+                outputs.push(output);
+            } else {
+                outputs.push(format!("{}:\n{}", target, output));
+            }
+        }
 
-        let binding = body_inputs
-            .into_iter()
-            .map(|func| compile(&isa, &translation, func).join("\n"))
-            .collect::<Vec<String>>()
-            .join("\n\n");
+        if config.expect_error.is_some() {
+            return;
+        }
+
+        let binding = outputs.join("\n\n");
         let actual = binding.as_str();
 
         if std::env::var("WINCH_TEST_BLESS").unwrap_or_default() == "1" {
@@ -145,24 +182,80 @@ mod test {
         }
     }
 
+    /// Compiles every function in `wasm` for `target`, returning their
+    /// disassembly.
+    fn compile_for_target(target: &str, config: &TestConfig, wasm: &[u8]) -> Result<String> {
+        let triple = Triple::from_str(target).map_err(|e| anyhow!("invalid target: {}", e))?;
+
+        let shared_flags = settings::Flags::new(settings::builder());
+        let mut isa_builder = lookup(triple)?;
+        for flag in config.flags.iter().flat_map(|f| f) {
+            isa_builder.set(&flag, "true")?;
+        }
+        let isa = isa_builder.finish(shared_flags)?;
+
+        let mut validator = Validator::new();
+        let parser = WasmParser::new(0);
+        let mut types = Default::default();
+        let tunables = Tunables::default();
+        let mut translation = ModuleEnvironment::new(&tunables, &mut validator, &mut types)
+            .translate(parser, wasm)
+            .context("Failed to translate WebAssembly module")?;
+        let _ = types.finish();
+
+        let body_inputs = std::mem::take(&mut translation.function_body_inputs);
+
+        let funcs = body_inputs
+            .into_iter()
+            .map(|func| Ok(compile(&isa, &translation, func)?.join("\n")))
+            .collect::<Result<Vec<String>>>()?;
+        Ok(funcs.join("\n\n"))
+    }
+
     fn compile(
         isa: &Box<dyn TargetIsa>,
         translation: &ModuleTranslation,
         f: (DefinedFuncIndex, FunctionBodyData<'_>),
-    ) -> Vec<String> {
-        let module = &translation.module;
-        let types = &translation.get_types();
+    ) -> Result<Vec<String>> {
+        let compiled =
+            compile_function(&**isa, translation, f.0, f.1).context("Couldn't compile function")?;
 
-        let index = module.func_index(f.0);
-        let sig = types[types.function_at(index.as_u32())].unwrap_func();
-        let sig = translation.module.convert_func_type(&sig);
+        disasm(&compiled.code, isa)
+    }
+
+    #[test]
+    fn compile_single_function() {
+        let wasm = wat::parse_str(
+            r#"
+            (module
+                (func (param i32 i32) (result i32)
+                    (local.get 0)
+                    (local.get 1)
+                    (i32.add)
+                )
+            )
+            "#,
+        )
+        .unwrap();
 
-        let FunctionBodyData { body, validator } = f.1;
-        let mut validator = validator.into_validator(Default::default());
-        let buffer = isa
-            .compile_function(&sig, &body, &translation, &mut validator)
-            .expect("Couldn't compile function");
+        let isa = lookup(Triple::from_str("x86_64").unwrap())
+            .unwrap()
+            .finish(settings::Flags::new(settings::builder()))
+            .unwrap();
 
-        disasm(buffer.data(), isa).unwrap()
+        let mut validator = Validator::new();
+        let mut types = Default::default();
+        let tunables = Tunables::default();
+        let mut translation = ModuleEnvironment::new(&tunables, &mut validator, &mut types)
+            .translate(WasmParser::new(0), &wasm)
+            .unwrap();
+        let _ = types.finish();
+
+        let (index, body) = std::mem::take(&mut translation.function_body_inputs)
+            .into_iter()
+            .next()
+            .unwrap();
+        let compiled = compile_function(&*isa, &translation, index, body).unwrap();
+        assert!(!compiled.code.is_empty());
     }
 }